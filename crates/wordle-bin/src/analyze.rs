@@ -0,0 +1,152 @@
+use crate::schema;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::fs;
+use std::io::Read;
+use wordle_lib::analysis::{analyze_game, parse_transcripts, GuessAnalysis, Transcript};
+use wordle_lib::cli::Config;
+use wordle_lib::WordDictionary;
+
+fn print_game(conf: &Config, analyses: &[GuessAnalysis]) {
+    for analysis in analyses.iter() {
+        println!(
+            "{} {} {:>5} -> {:<5} skill {:>3.0} luck {:+.2}",
+            conf.alphabet.decode(analysis.guess),
+            analysis.wm,
+            analysis.before,
+            analysis.after,
+            analysis.skill,
+            analysis.luck
+        );
+        if !analysis.violations.is_empty() {
+            let violations: Vec<_> = analysis.violations.iter().map(|v| v.to_string()).collect();
+            println!(
+                "  warning: {}, leaving {:.2} more candidates on average than a consistent guess",
+                violations.join(", "),
+                analysis.missed
+            );
+        }
+    }
+    if !analyses.is_empty() {
+        let skill: f64 = analyses.iter().map(|analysis| analysis.skill).sum();
+        let luck: f64 = analyses.iter().map(|analysis| analysis.luck).sum();
+        println!(
+            "skill {:.0} luck {:+.2}",
+            skill / analyses.len() as f64,
+            luck
+        );
+    }
+}
+
+fn game_json(conf: &Config, game: &Transcript, analyses: &[GuessAnalysis]) -> Value {
+    let guesses: Vec<_> = analyses
+        .iter()
+        .map(|analysis| {
+            let violations: Vec<_> = analysis.violations.iter().map(|v| v.to_string()).collect();
+            json!({
+                "guess": conf.alphabet.decode(analysis.guess),
+                "feedback": analysis.wm.to_string(),
+                "before": analysis.before,
+                "after": analysis.after,
+                "skill": analysis.skill,
+                "luck": analysis.luck,
+                "violations": violations,
+                "missed": analysis.missed,
+            })
+        })
+        .collect();
+    let skill: f64 = analyses.iter().map(|analysis| analysis.skill).sum();
+    let luck: f64 = analyses.iter().map(|analysis| analysis.luck).sum();
+    json!({
+        "answer": game.answer.map(|answer| conf.alphabet.decode(answer)),
+        "solved": game.solved(),
+        "guesses": guesses,
+        "skill": skill / analyses.len().max(1) as f64,
+        "luck": luck,
+    })
+}
+
+// games come from --transcript-file, or else stdin
+pub fn read_transcripts(conf: &Config) -> Result<Vec<Transcript>, Box<dyn Error>> {
+    let text = match &conf.transcript_file {
+        Some(path) => fs::read_to_string(path)?,
+        None => {
+            let mut text = String::new();
+            std::io::stdin().read_to_string(&mut text)?;
+            text
+        }
+    };
+    Ok(parse_transcripts(&text, &conf.alphabet, conf.answer)?)
+}
+
+// several games add up to totals, which json output always includes
+pub fn run(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<(), Box<dyn Error>> {
+    let games = read_transcripts(conf)?;
+    let mut solved = Vec::new();
+    let mut skill = 0.0;
+    let mut luck = 0.0;
+    let mut moves = 0;
+    let mut reports = Vec::new();
+    for (idx, game) in games.iter().enumerate() {
+        let analyses = analyze_game(guesses, answers, &game.game())?;
+        if conf.json {
+            reports.push(game_json(conf, game, &analyses));
+        } else {
+            if games.len() > 1 {
+                if idx > 0 {
+                    println!();
+                }
+                match game.answer {
+                    Some(answer) => println!("game {}: {}", idx + 1, conf.alphabet.decode(answer)),
+                    None => println!("game {}", idx + 1),
+                }
+            }
+            print_game(conf, &analyses);
+        }
+        if game.solved() {
+            solved.push(game.guesses.len());
+        }
+        skill += analyses.iter().map(|analysis| analysis.skill).sum::<f64>();
+        luck += analyses.iter().map(|analysis| analysis.luck).sum::<f64>();
+        moves += analyses.len();
+    }
+    let mut distribution = vec![0; solved.iter().copied().max().unwrap_or(0)];
+    for &guesses in solved.iter() {
+        distribution[guesses - 1] += 1;
+    }
+    let mean = solved.iter().sum::<usize>() as f64 / solved.len().max(1) as f64;
+    if conf.json {
+        schema::print(
+            "analysis",
+            json!({
+                "games": reports,
+                "totals": {
+                    "games": games.len(),
+                    "solved": solved.len(),
+                    "mean": mean,
+                    "skill": skill / moves.max(1) as f64,
+                    "luck": luck / games.len().max(1) as f64,
+                    "distribution": distribution,
+                },
+            }),
+        );
+    } else if games.len() > 1 {
+        println!();
+        println!(
+            "games {} solved {} mean {:.2} skill {:.0} luck {:+.2}",
+            games.len(),
+            solved.len(),
+            mean,
+            skill / moves.max(1) as f64,
+            luck / games.len() as f64
+        );
+        for (idx, count) in distribution.iter().enumerate() {
+            println!("{}: {}", idx + 1, count);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use crate::schema;
+use serde_json::json;
+use std::error::Error;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, BufWriter, Write};
+use wordle_lib::cli::Config;
+use wordle_lib::io::OpeningBook;
+use wordle_lib::{
+    rank_guesses, solve_seeded, CancellationToken, Constraints, Dictionary, WordDictionary,
+};
+
+fn read(conf: &Config, path: &str) -> Result<OpeningBook, Box<dyn Error>> {
+    match File::open(path) {
+        Ok(file) => Ok(OpeningBook::read(BufReader::new(file), &conf.alphabet)?),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(OpeningBook::default()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+// written beside the book and renamed over it, so a crash mid-write keeps the old book
+fn write(conf: &Config, path: &str, book: &OpeningBook) -> io::Result<()> {
+    let tmp = format!("{}.tmp", path);
+    let mut w = BufWriter::new(File::create(&tmp)?);
+    book.write(&conf.alphabet, &mut w)?;
+    w.flush()?;
+    drop(w);
+    fs::rename(tmp, path)
+}
+
+// adds trees for the --top-k openers by entropy, or just the --guess opener, to the book,
+// saving after each so an interrupted build picks up where it stopped
+pub fn run(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<(), Box<dyn Error>> {
+    let path = conf.book.as_deref().ok_or("book needs a file")?;
+    let mut book = read(conf, path)?;
+    let openers: Vec<[u8; 5]> = match conf.first_guess {
+        Some(guess) => vec![guess],
+        None => rank_guesses(guesses, answers)
+            .iter()
+            .take(conf.top_k)
+            .map(|guess| guess.word)
+            .collect(),
+    };
+    for opener in openers {
+        if book.contains(opener) {
+            continue;
+        }
+        let solution = solve_seeded(
+            guesses,
+            answers,
+            opener,
+            &Constraints::default(),
+            conf.breadth,
+            conf.depth,
+            conf.hard,
+            conf.tiebreak,
+            conf.guess_policy,
+            &CancellationToken::default(),
+        );
+        match solution {
+            Some(solution) => {
+                eprintln!(
+                    "{}: {}",
+                    conf.alphabet.decode(opener),
+                    solution.size() as f32 / answers.len() as f32
+                );
+                book.insert(solution);
+                write(conf, path, &book)?;
+            }
+            None => eprintln!("{}: no solution", conf.alphabet.decode(opener)),
+        }
+    }
+    if conf.json {
+        let openers: Vec<_> = book
+            .openers()
+            .into_iter()
+            .map(|opener| {
+                json!({
+                    "guess": conf.alphabet.decode(opener),
+                    "mean": book.get(opener).unwrap().size() as f64 / answers.len() as f64,
+                })
+            })
+            .collect();
+        schema::print("book", json!({ "openers": openers }));
+        return Ok(());
+    }
+    for opener in book.openers() {
+        let solution = book.get(opener).unwrap();
+        println!(
+            "{}: {}",
+            conf.alphabet.decode(opener),
+            solution.size() as f32 / answers.len() as f32
+        );
+    }
+    Ok(())
+}
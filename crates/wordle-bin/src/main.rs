@@ -1,85 +1,694 @@
+use serde_json::json;
+use std::env;
 use std::error::Error;
+use std::fmt;
+use std::fmt::Display;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, BufWriter, IsTerminal};
+use std::process::ExitCode;
+use wordle_lib::analysis::anti_solve;
+use wordle_lib::cli::{Command, Config};
+use wordle_lib::io::{ColorScheme, TreeFormat};
 use wordle_lib::{
-    solve, solve_easy, solve_hard, solve_hard_limited, Config, Guess, OffsetDictionary,
-    WordDictionary,
+    hint, play_game, rank_guesses, set_cpu_limit, set_memory_limit, solve,
+    solve_from_state_with_progress, solve_seeded, solve_with_progress, CancellationToken,
+    Constraints, CountingAllocator, EntropyGreedyStrategy, GameState, Hint, HumanStrategy,
+    MinimaxBucketStrategy, OptimalTreeStrategy, RandomStrategy, Rng, Solution, Strategy,
+    StrategyKind, Word, WordDictionary,
 };
 use wordle_lib::{Dictionary, LEGAL_ANSWERS, LEGAL_GUESSES};
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let conf = Config::from_args(std::env::args());
-    let answers = &WordDictionary::new(LEGAL_ANSWERS);
-    let mut guesses = &WordDictionary::new(LEGAL_GUESSES);
-    if conf.limit_guesses {
-        guesses = answers;
-    }
-    if conf.search && conf.hard && conf.limit_guesses {
-        let dict = &OffsetDictionary::new();
-        let go = |idx, guess: [u8; 5]| {
-            if let Some(total) = solve_hard_limited(idx as u16, dict, conf.depth - 1) {
-                eprintln!(
-                    "{}: {}",
-                    String::from_utf8_lossy(&guess),
-                    total as f32 / answers.len() as f32
-                );
+mod analyze;
+mod book;
+mod manifest;
+mod positions;
+mod protocol;
+mod schema;
+mod search;
+mod serve;
+mod share;
+mod stats;
+mod tournament;
+mod train;
+mod tui;
+mod update_lists;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// scripts can tell these apart:
+//
+//   2   a search came up short
+//   3   bad input: flags, settings, word lists, trees or books that don't parse
+//   4   i/o or the environment: a file that can't be read or written, an address that can't be
+//       listened on, a terminal that can't be set up
+//   5   a search was cancelled before it finished
+const EXIT_NO_SOLUTION: u8 = 2;
+const EXIT_INVALID_INPUT: u8 = 3;
+const EXIT_IO: u8 = 4;
+const EXIT_CANCELLED: u8 = 5;
+
+// some answer can't be solved within this many guesses
+#[derive(Debug)]
+struct NoSolution(usize);
+
+impl Display for NoSolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no solution within {} guesses", self.0)
+    }
+}
+
+impl Error for NoSolution {}
+
+// the named search was cancelled before it finished
+#[derive(Debug)]
+struct Cancelled(&'static str);
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} cancelled", self.0)
+    }
+}
+
+impl Error for Cancelled {}
+
+fn read_tree(path: &str) -> Result<Solution, Box<dyn Error>> {
+    Ok(Solution::read_dag(BufReader::new(File::open(path)?))?)
+}
+
+// a word list file, with errors naming the file and line
+fn read_list(conf: &Config, path: &str) -> Result<WordDictionary, Box<dyn Error>> {
+    let text = fs::read_to_string(path)?;
+    let dict = if conf.dedupe_lists {
+        let (dict, dropped) = WordDictionary::from_lines_deduped(&text, &conf.alphabet)
+            .map_err(|err| format!("{}: {}", path, err))?;
+        if dropped > 0 {
+            eprintln!("{}: dropped {} duplicate words", path, dropped);
+        }
+        dict
+    } else {
+        WordDictionary::from_lines_in(&text, &conf.alphabet)
+            .map_err(|err| format!("{}: {}", path, err))?
+    };
+    Ok(if conf.sort_lists { dict.sorted() } else { dict })
+}
+
+fn report_limit(conf: &Config, failures: usize) {
+    eprintln!(
+        "all answers solved within {} guesses: {}, failures: {}",
+        conf.depth,
+        if failures == 0 { "yes" } else { "no" },
+        failures
+    );
+}
+
+// everything --quiet prints
+fn summary(mean: Option<f64>, failures: usize) {
+    match mean {
+        Some(mean) => println!("mean: {}, failures: {}", mean, failures),
+        None => println!("mean: n/a, failures: {}", failures),
+    }
+}
+
+fn make_strategy<'a>(
+    kind: StrategyKind,
+    conf: &Config,
+    seed: u64,
+    guesses: &'a WordDictionary,
+    answers: &WordDictionary,
+) -> Result<Box<dyn Strategy + 'a>, Box<dyn Error>> {
+    Ok(match kind {
+        StrategyKind::Human => Box::new(HumanStrategy::new(
+            guesses,
+            conf.top_k,
+            conf.temperature,
+            seed,
+        )),
+        StrategyKind::OptimalTree => {
+            let solution = match &conf.tree {
+                Some(path) => read_tree(path)?,
+                None => solve(
+                    guesses,
+                    answers,
+                    conf.breadth,
+                    conf.depth,
+                    conf.hard,
+                    &CancellationToken::default(),
+                )
+                .ok_or(NoSolution(conf.depth))?,
+            };
+            Box::new(OptimalTreeStrategy::new(solution))
+        }
+        StrategyKind::EntropyGreedy => Box::new(EntropyGreedyStrategy::new(guesses)),
+        StrategyKind::MinimaxBucket => Box::new(MinimaxBucketStrategy::new(guesses)),
+        StrategyKind::Random => Box::new(RandomStrategy::new(seed)),
+    })
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let mut conf = Config::from_args(std::env::args())?;
+    let manifest = match &conf.from_manifest {
+        Some(path) => Some(manifest::read(path)?),
+        None => None,
+    };
+    if let Some(manifest) = &manifest {
+        conf = manifest::config(manifest)?;
+    }
+    conf.validate()?;
+    #[cfg(feature = "tracing")]
+    if let Some(level) = &conf.log_level {
+        tracing_subscriber::fmt()
+            .with_max_level(level.parse::<tracing_subscriber::filter::LevelFilter>()?)
+            .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+            .with_writer(std::io::stderr)
+            .init();
+    }
+    if let Some(threads) = conf.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()?;
+    }
+    if let Some(percent) = conf.cpu_percent() {
+        set_cpu_limit(percent);
+    }
+    if let Some(bytes) = conf.max_memory {
+        set_memory_limit(bytes);
+    }
+    if conf.command == Command::UpdateLists {
+        return update_lists::run(&conf);
+    }
+    // feedback is scored through the lists, so --rules goes with them
+    let answers = &match &conf.answers_file {
+        Some(path) => read_list(&conf, path)?,
+        None => WordDictionary::new(LEGAL_ANSWERS),
+    }
+    .with_rules(conf.rules);
+    let mut guesses = match &conf.guesses_file {
+        Some(path) => read_list(&conf, path)?,
+        None => WordDictionary::new(LEGAL_GUESSES),
+    }
+    .with_rules(conf.rules);
+    let missing = guesses.missing(answers);
+    if conf.merge_answers {
+        guesses = guesses.merged(answers);
+    } else if !missing.is_empty() && !conf.limit_guesses {
+        // an answer that can't be guessed leaves a branch the solver can never finish
+        let words: Vec<_> = missing
+            .iter()
+            .take(5)
+            .map(|w| conf.alphabet.decode(*w))
+            .collect();
+        eprintln!(
+            "warning: {} answers are not valid guesses ({}{}), use --merge-answers to add them",
+            missing.len(),
+            words.join(" "),
+            if missing.len() > words.len() {
+                " ..."
             } else {
-                eprintln!("{}: no solution", String::from_utf8_lossy(&guess));
+                ""
             }
+        );
+    }
+    let guesses = if conf.limit_guesses {
+        answers
+    } else {
+        &guesses
+    };
+    let allowed;
+    let guesses = match &conf.forbidden_file {
+        Some(path) => {
+            let forbidden = read_list(&conf, path)?;
+            allowed = guesses.excluding(&forbidden);
+            &allowed
+        }
+        None => guesses,
+    };
+    // answers stay reachable since a final guess only has to be the answer
+    let common;
+    let guesses = match &conf.common_file {
+        Some(path) => {
+            let text = fs::read_to_string(path)?;
+            let words = WordDictionary::from_frequencies(&text, &conf.alphabet, conf.common_count)?;
+            common = guesses.intersection(&words);
+            &common
+        }
+        None => guesses,
+    };
+    if let Some(manifest) = &manifest {
+        manifest::check_lists(manifest, guesses, answers)?;
+    }
+    if let Some(guess) = conf.first_guess {
+        Word(guess).check_in(guesses)?;
+    }
+    if let Some(answer) = conf.answer {
+        Word(answer).check_in(answers)?;
+    }
+    if conf.protocol {
+        protocol::run(&conf, guesses, answers)?;
+    } else if conf.command == Command::Serve {
+        serve::run(&conf, guesses, answers)?;
+    } else if conf.command == Command::Tui {
+        tui::run(&conf, guesses, answers)?;
+    } else if conf.command == Command::Tournament {
+        tournament::run(&conf, guesses, answers)?;
+    } else if conf.command == Command::Analyze {
+        analyze::run(&conf, guesses, answers)?;
+    } else if conf.command == Command::Share {
+        share::run(&conf, guesses, answers)?;
+    } else if conf.command == Command::Stats {
+        stats::run(&conf, guesses, answers)?;
+    } else if conf.command == Command::GameStats {
+        stats::games(&conf, guesses, answers)?;
+    } else if conf.command == Command::Leaderboard {
+        stats::run_leaderboard(&conf, guesses, answers)?;
+    } else if conf.command == Command::Difficulty {
+        stats::difficulty(&conf, guesses, answers)?;
+    } else if conf.command == Command::Traps {
+        stats::traps(&conf, guesses, answers)?;
+    } else if conf.command == Command::Diff {
+        let this = match &conf.tree {
+            Some(path) => read_tree(path)?,
+            None => solve(
+                guesses,
+                answers,
+                conf.breadth,
+                conf.depth,
+                conf.hard,
+                &CancellationToken::default(),
+            )
+            .ok_or(NoSolution(conf.depth))?,
         };
-        match conf.first_guess {
-            Some(guess) => go(answers.index_of(guess).unwrap(), guess),
-            None => {
-                let mut i = 0;
-                guesses.for_each(|guess| {
-                    go(i, guess);
-                    i += 1;
+        let other = read_tree(conf.other_tree.as_deref().ok_or("diff needs a tree file")?)?;
+        let divergences = this.diff(&other);
+        let delta = other.size() as i32 - this.size() as i32;
+        if conf.json {
+            let divergences: Vec<_> = divergences
+                .iter()
+                .map(|divergence| {
+                    json!({
+                        "path": schema::moves(&conf.alphabet, &divergence.path),
+                        "this": conf.alphabet.decode(divergence.this),
+                        "other": conf.alphabet.decode(divergence.other),
+                        "delta": divergence.delta,
+                    })
                 })
-            }
+                .collect();
+            schema::print(
+                "diff",
+                json!({ "divergences": divergences, "delta": delta }),
+            );
+            return Ok(());
+        }
+        for divergence in divergences.iter() {
+            let path: Vec<_> = divergence
+                .path
+                .iter()
+                .map(|(guess, wm)| format!("{} {}", conf.alphabet.decode(*guess), wm))
+                .collect();
+            println!(
+                "{}: {} -> {} ({:+})",
+                if path.is_empty() {
+                    "root".to_string()
+                } else {
+                    path.join(" ")
+                },
+                conf.alphabet.decode(divergence.this),
+                conf.alphabet.decode(divergence.other),
+                divergence.delta
+            );
         }
-    } else if conf.search && conf.hard {
-        let go = |guess| {
-            if let Some(total) = solve_hard(guess, guesses, answers, conf.depth - 1) {
-                eprintln!(
-                    "{}: {}",
-                    String::from_utf8_lossy(&guess),
-                    total as f32 / answers.len() as f32
+        eprintln!("divergences: {}, delta: {:+}", divergences.len(), delta);
+    } else if conf.command == Command::Book {
+        book::run(&conf, guesses, answers)?;
+    } else if conf.command == Command::Train {
+        train::run(&conf, guesses, answers)?;
+    } else if conf.command == Command::Anti {
+        let stall = anti_solve(
+            guesses,
+            answers,
+            conf.breadth,
+            conf.depth,
+            &CancellationToken::default(),
+        )
+        .ok_or(Cancelled("anti search"))?;
+        if conf.json {
+            schema::print(
+                "anti",
+                json!({
+                    "line": schema::moves(&conf.alphabet, &stall.line),
+                    "guesses": stall.guesses,
+                }),
+            );
+            return Ok(());
+        }
+        for (guess, wm) in stall.line.iter() {
+            println!("{} {}", conf.alphabet.decode(*guess), wm);
+        }
+        eprintln!("guaranteed guesses: {}", stall.guesses);
+    } else if conf.command == Command::Openers {
+        stats::openers(&conf, guesses, answers)?;
+    } else if conf.command == Command::Pattern {
+        let include: Option<String> = conf.include.as_ref().map(|include| {
+            let letters: Vec<_> = include.chars().map(String::from).collect();
+            letters.join(",")
+        });
+        let constraints = Constraints::from_flags(
+            conf.pattern.as_deref(),
+            include.as_deref(),
+            conf.exclude.as_deref(),
+        )?;
+        let matches = constraints.filter(guesses);
+        let ranked = rank_guesses(&matches, answers);
+        if conf.json {
+            schema::print(
+                "pattern",
+                json!({
+                    "matches": matches.len(),
+                    "suggestions": schema::suggestions(&conf.alphabet, &ranked),
+                }),
+            );
+            return Ok(());
+        }
+        for guess in ranked {
+            let marker = if guess.candidate { " *" } else { "" };
+            println!(
+                "{}: {} solve {:.0}% narrow {:.0}%{}",
+                conf.alphabet.decode(guess.word),
+                guess.entropy,
+                guess.solve_chance * 100.0,
+                guess.narrow_chance * 100.0,
+                marker
+            );
+        }
+        eprintln!("matches: {}", matches.len());
+    } else if let Some(level) = conf.hint {
+        let hint = position_hint(level, &conf, guesses, answers)?;
+        if conf.json {
+            schema::print("hint", json!({ "level": level, "hint": hint.to_string() }));
+        } else {
+            println!("{}", hint);
+        }
+    } else if conf.green.is_some() || conf.yellow.is_some() || conf.gray.is_some() {
+        let constraints = Constraints::from_flags(
+            conf.green.as_deref(),
+            conf.yellow.as_deref(),
+            conf.gray.as_deref(),
+        )?;
+        let candidates = constraints.filter(answers);
+        if conf.json {
+            let ranked = rank_guesses(&candidates, &candidates);
+            schema::print(
+                "suggestions",
+                json!({
+                    "candidates": candidates.len(),
+                    "suggestions": schema::suggestions(&conf.alphabet, &ranked),
+                }),
+            );
+        } else {
+            for guess in rank_guesses(&candidates, &candidates) {
+                println!(
+                    "{}: {} solve {:.0}% narrow {:.0}%",
+                    conf.alphabet.decode(guess.word),
+                    guess.entropy,
+                    guess.solve_chance * 100.0,
+                    guess.narrow_chance * 100.0
                 );
-            } else {
-                eprintln!("{}: no solution", String::from_utf8_lossy(&guess));
             }
-        };
-        match conf.first_guess {
-            Some(guess) => go(guess),
-            None => guesses.for_each(|guess| go(guess)),
+            eprintln!("candidates: {}", candidates.len());
         }
-    } else if conf.search {
-        let go = |guess| {
-            if let Some(total) = solve_easy(guess, guesses, answers, conf.depth - 1) {
-                eprintln!(
-                    "{}: {}",
-                    String::from_utf8_lossy(&guess),
-                    total as f32 / answers.len() as f32
-                );
-            } else {
-                eprintln!("{}: no solution", String::from_utf8_lossy(&guess));
+    } else if conf.simulate {
+        let seed = conf.seed.unwrap_or_else(Rng::random_seed);
+        let strategy = make_strategy(conf.strategy, &conf, seed, guesses, answers)?;
+        let mut total = 0;
+        let mut failures = 0;
+        let mut games = Vec::new();
+        answers.for_each(|answer| {
+            let n = play_game(&*strategy, answers, answer, conf.depth);
+            let show = !conf.json && !conf.quiet;
+            if conf.json {
+                games.push(json!({
+                    "answer": conf.alphabet.decode(answer),
+                    "guesses": n,
+                }));
             }
-        };
-        match conf.first_guess {
-            Some(guess) => go(guess),
-            None => guesses.for_each(|guess| go(guess)),
+            match n {
+                Some(n) => {
+                    if show {
+                        println!("{}: {}", conf.alphabet.decode(answer), n);
+                    }
+                    total += n;
+                }
+                None => {
+                    if show {
+                        println!("{}: failed", conf.alphabet.decode(answer));
+                    }
+                    failures += 1;
+                }
+            }
+        });
+        // over the games won, so there is none when every game is lost
+        let won = answers.len() - failures;
+        let mean = (won > 0).then(|| total as f64 / won as f64);
+        if conf.json {
+            schema::print(
+                "simulation",
+                json!({
+                    "strategy": conf.strategy.to_string(),
+                    "seed": seed,
+                    "mean": mean,
+                    "max_guesses": conf.depth,
+                    "failures": failures,
+                    "games": games,
+                }),
+            );
+        } else if conf.quiet {
+            summary(mean, failures);
+        } else {
+            eprintln!("seed: {}", seed);
+            match mean {
+                Some(mean) => eprintln!("mean: {}", mean as f32),
+                None => eprintln!("mean: n/a"),
+            }
+            report_limit(&conf, failures);
+        }
+        if failures > 0 {
+            return Err(NoSolution(conf.depth).into());
         }
+    } else if conf.search {
+        search::run(&conf, guesses, answers)?;
+    } else if conf.positions_file.is_some() {
+        positions::run(&conf, guesses, answers)?;
     } else {
-        let solution = if let Some(guess) = conf.first_guess {
-            let guess = Guess::new(guess, answers);
-            guess.slow_solution(guesses, answers, conf.breadth, conf.depth - 1, conf.hard)
+        // with --history only the rest of the game is solved, among the answers that fit it
+        let state = match &conf.history {
+            Some(history) => GameState::from_history(answers, history, &conf.alphabet)?,
+            None => GameState::new(answers),
+        };
+        let depth = conf.depth.saturating_sub(state.history.len());
+        let solution = if let Some(path) = &conf.tree {
+            Some(read_tree(path)?)
+        } else if let Some(guess) = conf.first_guess {
+            let constraints = Constraints::default();
+            solve_seeded(
+                guesses,
+                answers,
+                guess,
+                &constraints,
+                conf.breadth,
+                conf.depth,
+                conf.hard,
+                conf.tiebreak,
+                conf.guess_policy,
+                &CancellationToken::default(),
+            )
+        } else if !state.history.is_empty() {
+            solve_from_state_with_progress(
+                &state,
+                guesses,
+                conf.breadth,
+                conf.depth,
+                conf.hard,
+                conf.tiebreak,
+                conf.guess_policy,
+                &CancellationToken::default(),
+                |_| {},
+            )
         } else {
-            solve(guesses, answers, conf.breadth, conf.depth, conf.hard)
+            solve_with_progress(
+                guesses,
+                answers,
+                conf.breadth,
+                conf.depth,
+                conf.hard,
+                conf.tiebreak,
+                conf.guess_policy,
+                &CancellationToken::default(),
+                |_| {},
+            )
         };
-        if let Some(solution) = solution {
-            solution.print(&mut String::new());
-            eprintln!("mean: {}", solution.size as f32 / answers.len() as f32);
+        if let Some(manifest) = &manifest {
+            manifest::check_result(manifest, &conf, &state, solution.as_ref())?;
+        }
+        if let Some(solution) = &solution {
+            if let Some(path) = &conf.manifest {
+                manifest::write(path, &conf, guesses, answers, &state, solution)?;
+            }
+        }
+        let answers = &state.answers;
+        let failures = solution
+            .as_ref()
+            .map_or(answers.len(), |solution| solution.failures(answers, depth));
+        let mean = solution
+            .as_ref()
+            .map(|solution| solution.size() as f64 / answers.len() as f64);
+        if let Some(solution) = &solution {
+            if let Some(path) = &conf.export {
+                solution.write_dag(&mut BufWriter::new(File::create(path)?))?;
+            }
+        }
+        if conf.json {
+            schema::print(
+                "tree",
+                json!({
+                    "history": schema::moves(&conf.alphabet, &state.history),
+                    "mean": mean,
+                    "max_guesses": conf.depth,
+                    "failures": failures,
+                    "tree": solution
+                        .as_ref()
+                        .map(|solution| schema::tree(&conf.alphabet, solution.root())),
+                }),
+            );
+        } else if conf.quiet {
+            summary(mean, failures);
+        } else if let Some(solution) = &solution {
+            if conf.trace {
+                solution.print_trace(&conf.alphabet, answers);
+            } else {
+                match conf.format {
+                    TreeFormat::Flat => solution.print(&conf.alphabet, &mut String::new()),
+                    TreeFormat::Tree => {
+                        let color = io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none();
+                        let scheme = if color {
+                            conf.color_scheme
+                        } else {
+                            ColorScheme::Ascii
+                        };
+                        solution.print_tree(&conf.alphabet, scheme);
+                    }
+                    TreeFormat::Markdown => solution.write_markdown(
+                        &conf.alphabet,
+                        conf.color_scheme,
+                        &mut io::stdout().lock(),
+                    )?,
+                    TreeFormat::Html => solution.write_html(
+                        &conf.alphabet,
+                        conf.color_scheme,
+                        &mut io::stdout().lock(),
+                    )?,
+                }
+            }
+            eprintln!("mean: {}", solution.size() as f32 / answers.len() as f32);
+            report_limit(&conf, failures);
         } else {
-            eprintln!("no solution");
+            report_limit(&conf, failures);
+        }
+        if failures > 0 {
+            return Err(NoSolution(conf.depth).into());
         }
     }
     Ok(())
 }
+
+// the hints can see what --history and the letter flags have already revealed, whichever are
+// given, and the optimal guess counts the guesses made against the depth
+fn position_hint(
+    level: u8,
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<Hint, Box<dyn Error>> {
+    let constraints = Constraints::from_flags(
+        conf.green.as_deref(),
+        conf.yellow.as_deref(),
+        conf.gray.as_deref(),
+    )?;
+    let mut state = match &conf.history {
+        Some(history) => GameState::from_history(answers, history, &conf.alphabet)?,
+        None => GameState::new(answers),
+    };
+    state.answers = constraints.filter(&state.answers);
+    Ok(hint(
+        level,
+        guesses,
+        &state,
+        conf.breadth,
+        conf.depth,
+        conf.hard,
+    )?)
+}
+
+// trees and books that don't parse come back as invalid data, which is bad input like the rest
+fn exit_code(err: &(dyn Error + 'static)) -> u8 {
+    if err.is::<NoSolution>() {
+        EXIT_NO_SOLUTION
+    } else if err.is::<Cancelled>() {
+        EXIT_CANCELLED
+    } else if err.is::<rayon::ThreadPoolBuildError>() {
+        EXIT_IO
+    } else if let Some(err) = err.downcast_ref::<io::Error>() {
+        match err.kind() {
+            io::ErrorKind::InvalidData | io::ErrorKind::InvalidInput => EXIT_INVALID_INPUT,
+            _ => EXIT_IO,
+        }
+    } else {
+        EXIT_INVALID_INPUT
+    }
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::from(exit_code(&*err))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{exit_code, position_hint, read_list, EXIT_INVALID_INPUT, EXIT_IO};
+    use wordle_lib::cli::Config;
+    use wordle_lib::{Hint, WordDictionary};
+
+    #[test]
+    fn hint_positions() {
+        let answers = WordDictionary::new(b"lightmightsightcrane");
+        let mut conf = Config::default();
+        let hint = |level, conf: &Config| position_hint(level, conf, &answers, &answers).unwrap();
+        assert_eq!(hint(1, &conf), Hint::Remaining(4));
+        conf.history = Some("salet:aaaac".to_string());
+        assert_eq!(hint(1, &conf), Hint::Remaining(1));
+        assert_eq!(hint(4, &conf), Hint::Guess(*b"might"));
+        conf.history = Some("salet:aaaaa".to_string());
+        assert!(position_hint(1, &conf, &answers, &answers).is_err());
+        conf.history = None;
+        conf.green = Some("_i___".to_string());
+        assert_eq!(hint(1, &conf), Hint::Remaining(3));
+    }
+
+    #[test]
+    fn exit_codes() {
+        let conf = Config::default();
+        let code = |path: &str| exit_code(&*read_list(&conf, path).err().unwrap());
+        assert_eq!(code("no/such/answers.txt"), EXIT_IO);
+        let path = std::env::temp_dir().join("wordle-exit-codes.txt");
+        std::fs::write(&path, "light\nmite\n").unwrap();
+        let bad = code(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(bad, EXIT_INVALID_INPUT);
+    }
+}
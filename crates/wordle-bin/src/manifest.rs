@@ -0,0 +1,103 @@
+use serde_json::{json, Value};
+use std::env;
+use std::error::Error;
+use std::fs;
+use wordle_lib::cli::Config;
+use wordle_lib::{Dictionary, GameState, Solution, WordDictionary};
+
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+fn list(dict: &WordDictionary) -> Value {
+    json!({
+        "count": dict.len(),
+        "sha256": dict.sha256(),
+    })
+}
+
+// the tree only covers the answers left after any --history, with the guesses still to come
+fn summary(conf: &Config, state: &GameState, solution: &Solution) -> Value {
+    let depth = conf.depth.saturating_sub(state.history.len());
+    json!({
+        "guess": conf.alphabet.decode(solution.guess()),
+        "total": solution.size(),
+        "mean": solution.size() as f64 / state.answers.len() as f64,
+        "failures": solution.failures(&state.answers, depth),
+    })
+}
+
+pub fn write(
+    path: &str,
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    state: &GameState,
+    solution: &Solution,
+) -> Result<(), Box<dyn Error>> {
+    let manifest = json!({
+        "version": VERSION,
+        "args": conf.solve_args(),
+        "guesses": list(guesses),
+        "answers": list(answers),
+        "result": summary(conf, state, solution),
+    });
+    fs::write(path, serde_json::to_string_pretty(&manifest)? + "\n")?;
+    Ok(())
+}
+
+pub fn read(path: &str) -> Result<Value, Box<dyn Error>> {
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+// the recorded flags go ahead of the command line, so arguments given explicitly still win
+pub fn config(manifest: &Value) -> Result<Config, Box<dyn Error>> {
+    let recorded = manifest["args"]
+        .as_array()
+        .ok_or("manifest has no args")?
+        .iter()
+        .map(|arg| arg.as_str().map(String::from))
+        .collect::<Option<Vec<_>>>()
+        .ok_or("manifest args must be strings")?;
+    let mut args = env::args();
+    Ok(Config::from_args(
+        args.next().into_iter().chain(recorded).chain(args),
+    )?)
+}
+
+pub fn check_lists(
+    manifest: &Value,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<(), String> {
+    if manifest["version"] != VERSION {
+        eprintln!(
+            "warning: manifest was written by version {}, this is {}",
+            manifest["version"], VERSION
+        );
+    }
+    for (name, dict) in [("guesses", guesses), ("answers", answers)] {
+        if manifest[name]["sha256"] != dict.sha256() {
+            return Err(format!("{} list does not match manifest", name));
+        }
+    }
+    Ok(())
+}
+
+pub fn check_result(
+    manifest: &Value,
+    conf: &Config,
+    state: &GameState,
+    solution: Option<&Solution>,
+) -> Result<(), String> {
+    let actual = match solution {
+        Some(solution) => summary(conf, state, solution),
+        None => Value::Null,
+    };
+    if manifest["result"] != actual {
+        return Err(format!(
+            "result does not match manifest: expected {}, got {}",
+            manifest["result"], actual
+        ));
+    }
+    eprintln!("reproduced manifest: yes");
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use crate::{schema, NoSolution};
+use serde_json::json;
+use std::error::Error;
+use std::fs;
+use wordle_lib::cli::Config;
+use wordle_lib::{
+    solve_from_state_with_progress, CancellationToken, Dictionary, GameState, WordDictionary,
+};
+
+// solves every position in the file, one history like "salet:apaca,crony:acaaa" per line, and
+// reports the best next guess with the mean number of guesses still to come
+pub fn run(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<(), Box<dyn Error>> {
+    let path = conf.positions_file.as_deref().unwrap();
+    let text = fs::read_to_string(path)?;
+    let mut positions = Vec::new();
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let state = GameState::from_history(answers, line, &conf.alphabet)
+            .map_err(|err| format!("{}: line {}: {}", path, idx + 1, err))?;
+        positions.push((line, state));
+    }
+    let mut failures = 0;
+    let mut results = Vec::new();
+    for (history, state) in positions.iter() {
+        let solution = solve_from_state_with_progress(
+            state,
+            guesses,
+            conf.breadth,
+            conf.depth,
+            conf.hard,
+            conf.tiebreak,
+            conf.guess_policy,
+            &CancellationToken::default(),
+            |_| {},
+        );
+        let best = solution.map(|solution| {
+            (
+                solution.guess(),
+                solution.size() as f64 / state.answers.len() as f64,
+            )
+        });
+        if best.is_none() {
+            failures += 1;
+        }
+        if conf.json {
+            results.push(json!({
+                "history": schema::moves(&conf.alphabet, &state.history),
+                "candidates": state.answers.len(),
+                "guess": best.map(|(guess, _)| conf.alphabet.decode(guess)),
+                "mean": best.map(|(_, mean)| mean),
+            }));
+        } else if !conf.quiet {
+            match best {
+                Some((guess, mean)) => {
+                    println!("{} -> {}: {}", history, conf.alphabet.decode(guess), mean)
+                }
+                None => println!("{} -> no solution", history),
+            }
+        }
+    }
+    if conf.json {
+        schema::print(
+            "positions",
+            json!({
+                "max_guesses": conf.depth,
+                "failures": failures,
+                "positions": results,
+            }),
+        );
+    } else {
+        eprintln!("positions: {}, failures: {}", positions.len(), failures);
+    }
+    if failures > 0 {
+        return Err(NoSolution(conf.depth).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,176 @@
+use crate::schema;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::BufRead;
+use std::ops::ControlFlow;
+use wordle_lib::cli::Config;
+use wordle_lib::{
+    rank_guesses, solve_from_state_streaming, CancellationToken, Dictionary, GameState,
+    WordDictionary, WordMatch,
+};
+
+// suggestions only depend on the surviving answers, so the order guesses were made in doesn't
+// matter
+pub fn history_key(state: &GameState) -> Vec<([u8; 5], u8)> {
+    let mut key: Vec<_> = state
+        .history
+        .iter()
+        .map(|(guess, wm)| (*guess, wm.index()))
+        .collect();
+    key.sort_unstable();
+    key.dedup();
+    key
+}
+
+pub fn suggest(conf: &Config, guesses: &WordDictionary, state: &GameState) -> Value {
+    let mut ranked = rank_guesses(guesses, &state.answers);
+    ranked.truncate(conf.top_k);
+    json!({
+        "candidates": state.answers.len(),
+        "suggestions": schema::suggestions(&conf.alphabet, &ranked),
+    })
+}
+
+fn handle(
+    conf: &Config,
+    guesses: &WordDictionary,
+    state: &mut GameState,
+    initial: &GameState,
+    cache: &mut HashMap<Vec<([u8; 5], u8)>, Value>,
+    line: &str,
+) -> Result<Value, String> {
+    let request: Value = serde_json::from_str(line).map_err(|err| err.to_string())?;
+    if request.get("reset").and_then(Value::as_bool) == Some(true) {
+        *state = initial.clone();
+    }
+    match (request.get("guess"), request.get("feedback")) {
+        (Some(guess), Some(feedback)) => {
+            let guess = guess.as_str().ok_or("guess must be a string")?;
+            let feedback = feedback.as_str().ok_or("feedback must be a string")?;
+            let guess = conf.alphabet.encode(guess)?;
+            let wm: WordMatch = feedback.parse()?;
+            state.guess(guess, wm);
+        }
+        (None, None) => {}
+        _ => return Err("guess and feedback must be given together".to_string()),
+    }
+    let repaired = repair(conf, state, &initial.answers);
+    let mut reply = if request.get("solve").and_then(Value::as_bool) == Some(true) {
+        let stream = request.get("stream").and_then(Value::as_bool) == Some(true);
+        let emit = |event: Value| println!("{}", event);
+        solve(
+            conf,
+            guesses,
+            state,
+            stream,
+            &CancellationToken::default(),
+            &emit,
+        )?
+    } else {
+        cache
+            .entry(history_key(state))
+            .or_insert_with(|| suggest(conf, guesses, state))
+            .clone()
+    };
+    add_repaired(&mut reply, repaired);
+    Ok(reply)
+}
+
+// with --tolerate-typos, a history no answer fits has one feedback corrected, and the reply
+// says which
+pub fn repair(conf: &Config, state: &mut GameState, answers: &WordDictionary) -> Option<Value> {
+    if !conf.tolerate_typos {
+        return None;
+    }
+    let repair = state.repair(answers)?;
+    let (guess, wm) = state.history[repair.entry];
+    let repaired = json!({
+        "entry": repair.entry,
+        "guess": conf.alphabet.decode(guess),
+        "feedback": wm.to_string(),
+        "corrected": repair.feedback.to_string(),
+    });
+    *state = repair.state;
+    Some(repaired)
+}
+
+pub fn add_repaired(reply: &mut Value, repaired: Option<Value>) {
+    if let (Some(repaired), Value::Object(reply)) = (repaired, reply) {
+        reply.insert("repaired".to_string(), repaired);
+    }
+}
+
+// progress events are emitted as they arrive, ahead of the final reply, and with stream so is
+// every guess looked at for the first move
+pub fn solve(
+    conf: &Config,
+    guesses: &WordDictionary,
+    state: &GameState,
+    stream: bool,
+    cancel: &CancellationToken,
+    emit: &(dyn Fn(Value) + Sync),
+) -> Result<Value, String> {
+    if state.history.len() >= conf.depth {
+        return Err("no guesses left".to_string());
+    }
+    let answers = &state.answers;
+    let solution = solve_from_state_streaming(
+        state,
+        guesses,
+        conf.breadth,
+        conf.depth,
+        conf.hard,
+        conf.tiebreak,
+        conf.guess_policy,
+        cancel,
+        |progress| {
+            emit(json!({
+                "progress": {
+                    "evaluated": progress.evaluated,
+                    "candidates": progress.candidates,
+                    "incumbent": progress
+                        .incumbent
+                        .map(|size| size as f64 / answers.len() as f64),
+                }
+            }))
+        },
+        |candidate| {
+            if stream {
+                emit(json!({
+                    "considering": {
+                        "guess": conf.alphabet.decode(candidate.word),
+                        "entropy": candidate.entropy,
+                        "buckets": candidate.buckets,
+                    }
+                }));
+            }
+            ControlFlow::Continue(())
+        },
+    )
+    .ok_or("no solution")?;
+    Ok(json!({
+        "guess": conf.alphabet.decode(solution.guess()),
+        "mean": solution.size() as f64 / answers.len() as f64,
+    }))
+}
+
+pub fn run(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<(), Box<dyn Error>> {
+    let initial = GameState::new(answers);
+    let mut state = initial.clone();
+    let mut cache = HashMap::new();
+    for line in std::io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let reply = handle(conf, guesses, &mut state, &initial, &mut cache, &line)
+            .unwrap_or_else(|err| json!({ "error": err }));
+        println!("{}", reply);
+    }
+    Ok(())
+}
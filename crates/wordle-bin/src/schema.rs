@@ -0,0 +1,123 @@
+use serde_json::{json, Value};
+use wordle_lib::{Alphabet, RankedGuess, SolutionNode, WordMatch};
+
+// raised whenever a field is renamed, removed or changes meaning. adding a field keeps the
+// version, so readers should ignore fields they don't know
+pub const VERSION: u32 = 1;
+
+// --json output is one object per line on stdout, tagged with the schema version and the kind
+// of report it holds. words are spelled in the --alphabet, feedback is five of a (absent),
+// p (present) and c (correct), and a missing result is null. the kinds and their fields:
+//
+//   tree          history, mean, max_guesses, failures, and tree: a node of guess, size and
+//                 children, each child a node with the feedback leading to it
+//   diff          divergences of path, this, other and delta, and the total delta
+//   pattern       matches, and suggestions of word, entropy, candidate, solve_chance and
+//                 narrow_chance
+//   suggestions   candidates, and suggestions as for pattern
+//   hint          level, and the hint as text
+//   simulation    strategy, seed, mean, max_guesses, failures, and games of answer and guesses
+//   anti          line of guess and feedback moves, and guesses
+//   search_result guess, status (solved, over_limit, no_solution or infeasible), total, mean,
+//                 and metrics with --metrics
+//   optimal       guess
+//   positions     max_guesses, failures, and positions of history, candidates, guess and mean
+//   analysis      games of answer, solved, guesses, skill and luck, and their totals
+//   tournament    seed, and standings of name, mean, variance, failure_rate and wins
+//   letter_stats  letters of letter, words and by_position, and coverage of guess and coverage
+//   game_stats    played, wins, win_rate, current_streak, max_streak, skill and distribution
+//   openers       openers of words and coverage
+//   leaderboard   guesses of guess, entropy, remaining, two_ply and tree
+//   difficulty    answers of answer, optimal, neighbors and greedy
+//   traps         seed, and traps of pattern, words, mean, worst and worst_game
+//   book          openers of guess and mean
+//   share         rows of feedback and guesses, or the answers
+//   lists         answers and guesses, each a count and the file written
+pub fn print(kind: &str, body: Value) {
+    println!("{}", envelope(kind, body));
+}
+
+// the envelope's fields win over any of the body's with the same name
+fn envelope(kind: &str, body: Value) -> Value {
+    let mut doc = match body {
+        Value::Object(body) => body,
+        _ => Default::default(),
+    };
+    doc.insert("version".to_string(), json!(VERSION));
+    doc.insert("kind".to_string(), json!(kind));
+    Value::Object(doc)
+}
+
+// each child carries the feedback that leads to it from its parent
+pub fn tree(alphabet: &Alphabet, node: SolutionNode<'_>) -> Value {
+    let children: Vec<Value> = node
+        .children()
+        .map(|(wm, child)| {
+            let mut child = tree(alphabet, child);
+            child["feedback"] = json!(wm.to_string());
+            child
+        })
+        .collect();
+    json!({
+        "guess": alphabet.decode(node.guess()),
+        "size": node.size(),
+        "children": children,
+    })
+}
+
+pub fn moves(alphabet: &Alphabet, moves: &[([u8; 5], WordMatch)]) -> Value {
+    moves
+        .iter()
+        .map(|(guess, wm)| {
+            json!({
+                "guess": alphabet.decode(*guess),
+                "feedback": wm.to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn suggestions(alphabet: &Alphabet, ranked: &[RankedGuess]) -> Value {
+    ranked
+        .iter()
+        .map(|guess| {
+            json!({
+                "word": alphabet.decode(guess.word),
+                "entropy": guess.entropy,
+                "candidate": guess.candidate,
+                "solve_chance": guess.solve_chance,
+                "narrow_chance": guess.narrow_chance,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::schema::{envelope, moves, VERSION};
+    use serde_json::json;
+    use wordle_lib::{Alphabet, WordMatch};
+
+    #[test]
+    fn tagged() {
+        let doc = envelope("optimal", json!({ "guess": "crane" }));
+        assert_eq!(
+            doc,
+            json!({ "version": VERSION, "kind": "optimal", "guess": "crane" })
+        );
+        let doc = envelope("hint", json!({ "kind": "other", "version": 0 }));
+        assert_eq!(doc["kind"], "hint");
+        assert_eq!(doc["version"], VERSION);
+    }
+
+    #[test]
+    fn spelled() {
+        let alphabet = Alphabet::with_extra_letters("ñ").unwrap();
+        let guess = alphabet.encode("ñandu").unwrap();
+        let wm = WordMatch::from(guess, guess);
+        assert_eq!(
+            moves(&alphabet, &[(guess, wm)]),
+            json!([{ "guess": "ñandu", "feedback": "ccccc" }])
+        );
+    }
+}
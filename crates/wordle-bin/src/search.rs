@@ -0,0 +1,421 @@
+use crate::{schema, NoSolution};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+use wordle_lib::analysis::{hard_mode_feasible, trap_sets};
+use wordle_lib::cli::Config;
+use wordle_lib::{
+    optimal_lower_bound, solve_easy_within, solve_hard_limited_allowed_within, solve_hard_within,
+    Alphabet, CancellationToken, Dictionary, MatchTable, OffsetDictionary, SearchMetrics,
+    WordDictionary,
+};
+
+// the largest total --max-mean and --max-total allow
+fn max_total(conf: &Config, answers: &WordDictionary) -> u32 {
+    let by_mean = conf.max_mean.map_or(u32::MAX, |mean| {
+        (mean * answers.len() as f64).floor() as u32
+    });
+    by_mean.min(conf.max_total.unwrap_or(u32::MAX))
+}
+
+// what searching one opener found, and how to print it as text or json
+#[derive(Clone)]
+struct Outcome {
+    line: String,
+    result: Value,
+    total: Option<u32>,
+}
+
+impl Outcome {
+    fn show(&self, conf: &Config) {
+        if conf.json {
+            schema::print("search_result", self.result.clone());
+        } else {
+            eprintln!("{}", self.line);
+        }
+    }
+}
+
+fn report(
+    conf: &Config,
+    guess: [u8; 5],
+    total: Option<u32>,
+    answers: &WordDictionary,
+    metrics: &SearchMetrics,
+    start: Instant,
+) -> Outcome {
+    let mut line = format!("{}: ", conf.alphabet.decode(guess));
+    let status = match total {
+        Some(total) => {
+            line.push_str(&(total as f32 / answers.len() as f32).to_string());
+            "solved"
+        }
+        // an opener over the limit is abandoned without finding out whether it has a solution
+        None if conf.max_mean.is_some() || conf.max_total.is_some() => {
+            line.push_str("no solution within limit");
+            "over_limit"
+        }
+        None => {
+            line.push_str("no solution");
+            "no_solution"
+        }
+    };
+    let mut result = json!({
+        "guess": conf.alphabet.decode(guess),
+        "status": status,
+        "total": total,
+        "mean": total.map(|total| total as f64 / answers.len() as f64),
+    });
+    if conf.metrics {
+        let ms = start.elapsed().as_millis() as u64;
+        let hit_rate = match metrics.hit_rate() {
+            Some(hit_rate) => format!("{:.1}%", hit_rate),
+            None => "n/a".to_string(),
+        };
+        line.push_str(&format!(
+            " nodes={} partitions={} transpositions={} hit_rate={} evictions={} ms={}",
+            metrics.nodes(),
+            metrics.partitions(),
+            metrics.transpositions(),
+            hit_rate,
+            metrics.evictions(),
+            ms
+        ));
+        result["metrics"] = json!({
+            "nodes": metrics.nodes(),
+            "partitions": metrics.partitions(),
+            "lookups": metrics.lookups(),
+            "transpositions": metrics.transpositions(),
+            "hit_rate": metrics.hit_rate(),
+            "evictions": metrics.evictions(),
+            "ms": ms,
+        });
+    }
+    Outcome {
+        line,
+        result,
+        total,
+    }
+}
+
+fn infeasible(conf: &Config, guess: [u8; 5]) -> Outcome {
+    Outcome {
+        line: format!("{}: infeasible", conf.alphabet.decode(guess)),
+        result: json!({
+            "guess": conf.alphabet.decode(guess),
+            "status": "infeasible",
+            "total": null,
+            "mean": null,
+        }),
+        total: None,
+    }
+}
+
+// outcomes finished out of order wait here until every earlier opener has been printed, and
+// openers skipped after --stop-at-optimal leave no line
+struct OrderedOutput {
+    next: usize,
+    pending: BTreeMap<usize, Option<Outcome>>,
+}
+
+impl OrderedOutput {
+    fn emit(&mut self, conf: &Config, idx: usize, outcome: Option<Outcome>) {
+        self.pending.insert(idx, outcome);
+        while let Some(outcome) = self.pending.remove(&self.next) {
+            if let Some(outcome) = outcome {
+                outcome.show(conf);
+            }
+            self.next += 1;
+        }
+    }
+}
+
+// what a resume file's results depend on, so that a run never picks up openers another search
+// settled
+fn resume_header(conf: &Config, guesses: &WordDictionary, answers: &WordDictionary) -> Value {
+    json!({
+        "args": conf.solve_args(),
+        "max_total": max_total(conf, answers),
+        "guesses": guesses.sha256(),
+        "answers": answers.sha256(),
+    })
+}
+
+// openers finished by earlier runs, and the file each newly finished opener is added to, one
+// json object per line so a crash loses at most the line being written. the first line is the
+// header of the search the file is for
+struct ResumeFile {
+    done: HashMap<[u8; 5], Outcome>,
+    file: Mutex<File>,
+}
+
+impl ResumeFile {
+    fn open(path: &str, alphabet: &Alphabet, header: Value) -> Result<Self, Box<dyn Error>> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err.into()),
+        };
+        let mut lines = text.split_inclusive('\n');
+        let found = match lines.next().and_then(|line| line.strip_suffix('\n')) {
+            Some(line) => Some(serde_json::from_str::<Value>(line)?),
+            // a new file, or one cut off before its header was written
+            None => None,
+        };
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        let Some(found) = found else {
+            file.set_len(0)?;
+            writeln!(file, "{}", json!({ "header": header }))?;
+            return Ok(Self {
+                done: HashMap::new(),
+                file: Mutex::new(file),
+            });
+        };
+        match found.get("header") {
+            Some(found) if *found == header => {}
+            Some(found) => {
+                return Err(format!(
+                    "{} is from a different search: {} instead of {}",
+                    path, found, header
+                )
+                .into())
+            }
+            None => return Err(format!("{} has no header to check it against", path).into()),
+        }
+        let mut done = HashMap::new();
+        let mut valid = text.find('\n').unwrap() + 1;
+        for line in lines {
+            let Some(line) = line.strip_suffix('\n') else {
+                // cut off by a crash, so that opener runs again
+                break;
+            };
+            let record: Value = serde_json::from_str(line)?;
+            valid += line.len() + 1;
+            let guess = record["guess"]
+                .as_str()
+                .and_then(|guess| alphabet.encode(guess).ok())
+                .ok_or_else(|| format!("bad resume record: {}", line))?;
+            let line = record["line"]
+                .as_str()
+                .ok_or_else(|| format!("bad resume record: {}", line))?;
+            let total = record["total"].as_u64().map(|total| total as u32);
+            // records from before --json only have the text line
+            let result = match record.get("result") {
+                Some(result) => result.clone(),
+                None => json!({
+                    "guess": alphabet.decode(guess),
+                    "status": if total.is_some() { "solved" } else { "no_solution" },
+                    "total": total,
+                }),
+            };
+            done.insert(
+                guess,
+                Outcome {
+                    line: line.to_string(),
+                    result,
+                    total,
+                },
+            );
+        }
+        file.set_len(valid as u64)?;
+        Ok(Self {
+            done,
+            file: Mutex::new(file),
+        })
+    }
+
+    fn record(&self, alphabet: &Alphabet, guess: [u8; 5], outcome: &Outcome) -> io::Result<()> {
+        let record = json!({
+            "guess": alphabet.decode(guess),
+            "total": outcome.total,
+            "line": outcome.line,
+            "result": outcome.result,
+        });
+        self.file
+            .lock()
+            .unwrap()
+            .write_all(format!("{}\n", record).as_bytes())
+    }
+}
+
+// runs go on the --guess opener or on every guess, one opener at a time or with
+// --parallel-openers all at once, skipping openers the --resume file already has. fails with
+// NoSolution when no opener it ran has a solution
+fn scan<F>(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    go: F,
+) -> Result<(), Box<dyn Error>>
+where
+    F: Fn([u8; 5]) -> Outcome + Sync,
+{
+    let resume = conf
+        .resume
+        .as_deref()
+        .map(|path| ResumeFile::open(path, &conf.alphabet, resume_header(conf, guesses, answers)))
+        .transpose()?;
+    let solved = AtomicBool::new(false);
+    let go = |guess| {
+        let outcome = match &resume {
+            Some(resume) => match resume.done.get(&guess) {
+                Some(outcome) => outcome.clone(),
+                None => {
+                    let outcome = go(guess);
+                    resume.record(&conf.alphabet, guess, &outcome)?;
+                    outcome
+                }
+            },
+            None => go(guess),
+        };
+        if outcome.total.is_some() {
+            solved.store(true, Ordering::Relaxed);
+        }
+        Ok::<_, io::Error>(outcome)
+    };
+    if let Some(guess) = conf.first_guess {
+        go(guess)?.show(conf);
+    } else {
+        scan_openers(conf, guesses, answers, go)?;
+    }
+    if !solved.into_inner() {
+        return Err(NoSolution(conf.depth).into());
+    }
+    Ok(())
+}
+
+fn scan_openers<F>(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    go: F,
+) -> Result<(), Box<dyn Error>>
+where
+    F: Fn([u8; 5]) -> io::Result<Outcome> + Sync,
+{
+    let optimal = conf
+        .stop_at_optimal
+        .then(|| optimal_lower_bound(guesses, answers));
+    let winner = Mutex::new(None);
+    if conf.parallel_openers {
+        let output = Mutex::new(OrderedOutput {
+            next: 0,
+            pending: BTreeMap::new(),
+        });
+        (0..guesses.len()).into_par_iter().try_for_each(|idx| {
+            if winner.lock().unwrap().is_some() {
+                output.lock().unwrap().emit(conf, idx, None);
+                return Ok(());
+            }
+            let guess = guesses.word(idx);
+            let outcome = go(guess)?;
+            if optimal.is_some() && outcome.total == optimal {
+                winner.lock().unwrap().get_or_insert(guess);
+            }
+            output.lock().unwrap().emit(conf, idx, Some(outcome));
+            Ok::<_, io::Error>(())
+        })?;
+    } else {
+        for idx in 0..guesses.len() {
+            let guess = guesses.word(idx);
+            let outcome = go(guess)?;
+            outcome.show(conf);
+            if optimal.is_some() && outcome.total == optimal {
+                *winner.lock().unwrap() = Some(guess);
+                break;
+            }
+        }
+    }
+    if let Some(guess) = winner.into_inner().unwrap() {
+        if conf.json {
+            schema::print("optimal", json!({ "guess": conf.alphabet.decode(guess) }));
+        } else {
+            eprintln!("optimal: {}", conf.alphabet.decode(guess));
+        }
+    }
+    Ok(())
+}
+
+pub fn run(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<(), Box<dyn Error>> {
+    let max_total = max_total(conf, answers);
+    if conf.hard && conf.limit_guesses {
+        let table = MatchTable::for_lists(answers, answers, conf.rules);
+        let dict = &OffsetDictionary::new(&table);
+        // the table holds every answer, so --forbidden-file and --common-file narrow the guesses
+        // each bucket of it may play too
+        let allowed: Vec<bool> = (0..answers.len())
+            .map(|idx| guesses.contains(answers.word(idx)))
+            .collect();
+        let allowed = |idx: u16| allowed[idx as usize];
+        let sets = trap_sets(answers, conf.depth);
+        scan(conf, guesses, answers, |guess| {
+            if !hard_mode_feasible(guess, answers, &sets, conf.depth - 1) {
+                return infeasible(conf, guess);
+            }
+            let metrics = if conf.transpositions {
+                SearchMetrics::with_transposition_table(conf.transposition_capacity, conf.eviction)
+            } else {
+                SearchMetrics::default()
+            };
+            let start = Instant::now();
+            let idx = answers.index_of(guess).unwrap();
+            let total = solve_hard_limited_allowed_within(
+                idx as u16,
+                dict,
+                &allowed,
+                conf.depth - 1,
+                max_total,
+                &metrics,
+                &CancellationToken::default(),
+            );
+            report(conf, guess, total, answers, &metrics, start)
+        })?;
+    } else if conf.hard {
+        let sets = trap_sets(answers, conf.depth);
+        scan(conf, guesses, answers, |guess| {
+            if !hard_mode_feasible(guess, answers, &sets, conf.depth - 1) {
+                return infeasible(conf, guess);
+            }
+            let metrics = SearchMetrics::default();
+            let start = Instant::now();
+            let total = solve_hard_within(
+                guess,
+                guesses,
+                answers,
+                conf.depth - 1,
+                max_total,
+                &metrics,
+                &CancellationToken::default(),
+            );
+            report(conf, guess, total, answers, &metrics, start)
+        })?;
+    } else {
+        scan(conf, guesses, answers, |guess| {
+            let metrics = SearchMetrics::default();
+            let start = Instant::now();
+            let total = solve_easy_within(
+                guess,
+                guesses,
+                answers,
+                conf.depth - 1,
+                max_total,
+                &metrics,
+                &CancellationToken::default(),
+            );
+            report(conf, guess, total, answers, &metrics, start)
+        })?;
+    }
+    Ok(())
+}
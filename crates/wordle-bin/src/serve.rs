@@ -0,0 +1,246 @@
+use crate::protocol;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::error::Error;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Mutex;
+use std::thread;
+use wordle_lib::cli::Config;
+use wordle_lib::{parse_history, CancellationToken, Dictionary, GameState, WordDictionary};
+
+// the most suggestion replies kept, which the first couple of guesses of most games fill
+const CACHE_ENTRIES: usize = 1 << 16;
+
+type Cache = Mutex<HashMap<Vec<([u8; 5], u8)>, Value>>;
+
+// serve answers http on --listen, one thread per connection:
+//
+//   GET /suggest?history=salet:aapac,...   the suggestions for the answers left, as json,
+//                                          kept in memory by the normalized history
+//   GET /solve?history=salet:aapac,...     server-sent events while the rest of the game is
+//                                          solved: progress, and considering with stream=true,
+//                                          then result, or error
+//
+// the history is in the form --history reads, and may be left out for a new game. with
+// --tolerate-typos a history no answer fits is repaired as the stdin protocol does, and the
+// reply, or a repaired event ahead of the rest, says which feedback was corrected
+//
+// there's no shutdown: the server runs until the process is killed, and a client that goes away
+// only cancels its own search
+pub fn run(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<(), Box<dyn Error>> {
+    let listener = TcpListener::bind(&conf.listen)?;
+    eprintln!("listening on {}", listener.local_addr()?);
+    let cache = &Cache::default();
+    thread::scope(|scope| {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    scope.spawn(move || {
+                        if let Err(err) = handle(conf, guesses, answers, cache, stream) {
+                            eprintln!("connection: {}", err);
+                        }
+                    });
+                }
+                Err(err) => eprintln!("accept: {}", err),
+            }
+        }
+    });
+    Ok(())
+}
+
+fn handle(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    cache: &Cache,
+    mut stream: TcpStream,
+) -> Result<(), Box<dyn Error>> {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    // the headers don't matter, but have to be read past
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        header.clear();
+    }
+    let (path, query) = match request_line.split_whitespace().collect::<Vec<_>>()[..] {
+        ["GET", target, _] => target.split_once('?').unwrap_or((target, "")),
+        _ => return respond(&mut stream, "405 Method Not Allowed", &error("only GET")),
+    };
+    let param = |name: &str| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| percent_decode(value))
+    };
+    // the history is only turned away once a repair has had its chance to fit it
+    let history = param("history").unwrap_or_default();
+    let moves = match parse_history(&history, &conf.alphabet) {
+        Ok(moves) => moves,
+        Err(err) => return respond(&mut stream, "400 Bad Request", &error(&err)),
+    };
+    let mut state = GameState::new(answers);
+    for (guess, wm) in moves {
+        state.guess(guess, wm);
+    }
+    let repaired = protocol::repair(conf, &mut state, answers);
+    if state.answers.is_empty() {
+        let err = format!("no answer fits the history: {}", history);
+        return respond(&mut stream, "400 Bad Request", &error(&err));
+    }
+    match path {
+        "/suggest" => {
+            let key = protocol::history_key(&state);
+            let cached = cache.lock().unwrap().get(&key).cloned();
+            // computed outside the lock, so a slow miss doesn't hold up the hits
+            let mut reply = cached.unwrap_or_else(|| {
+                let reply = protocol::suggest(conf, guesses, &state);
+                let mut cache = cache.lock().unwrap();
+                if cache.len() < CACHE_ENTRIES {
+                    cache.insert(key, reply.clone());
+                }
+                reply
+            });
+            protocol::add_repaired(&mut reply, repaired);
+            respond(&mut stream, "200 OK", &reply)
+        }
+        "/solve" => {
+            let considering = param("stream").as_deref() == Some("true");
+            stream_solve(conf, guesses, &state, repaired, considering, stream)
+        }
+        _ => respond(&mut stream, "404 Not Found", &error("no such path")),
+    }
+}
+
+fn error(message: &str) -> Value {
+    json!({ "error": message })
+}
+
+fn respond(stream: &mut TcpStream, status: &str, body: &Value) -> Result<(), Box<dyn Error>> {
+    let body = body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )?;
+    Ok(())
+}
+
+// each event is named for the key of its one field, and a client that goes away cancels the
+// search
+fn stream_solve(
+    conf: &Config,
+    guesses: &WordDictionary,
+    state: &GameState,
+    repaired: Option<Value>,
+    considering: bool,
+    mut stream: TcpStream,
+) -> Result<(), Box<dyn Error>> {
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\n\
+         Access-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n"
+    )?;
+    let stream = Mutex::new(stream);
+    let cancel = CancellationToken::default();
+    let send = |name: &str, data: &Value| {
+        let mut stream = stream.lock().unwrap();
+        if write!(stream, "event: {}\ndata: {}\n\n", name, data).is_err() {
+            cancel.cancel();
+        }
+    };
+    let emit = |event: Value| {
+        if let Some((name, data)) = event.as_object().and_then(|event| event.iter().next()) {
+            send(name, data);
+        }
+    };
+    if let Some(repaired) = &repaired {
+        send("repaired", repaired);
+    }
+    match protocol::solve(conf, guesses, state, considering, &cancel, &emit) {
+        Ok(result) => send("result", &result),
+        Err(err) => send("error", &error(&err)),
+    }
+    Ok(())
+}
+
+// the query's %XX escapes and + for space
+fn percent_decode(s: &str) -> String {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        let hex = tail
+            .get(..2)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (b, hex) {
+            (b'%', Some(byte)) => {
+                bytes.push(byte);
+                rest = &tail[2..];
+                continue;
+            }
+            (b'+', _) => bytes.push(b' '),
+            _ => bytes.push(b),
+        }
+        rest = tail;
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::serve::{handle, percent_decode, Cache};
+    use serde_json::Value;
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use wordle_lib::cli::Config;
+    use wordle_lib::WordDictionary;
+
+    #[test]
+    fn decode() {
+        assert_eq!(
+            percent_decode("salet%3Aaapac%2Ccrony:aaaaa"),
+            "salet:aapac,crony:aaaaa"
+        );
+        assert_eq!(percent_decode("a+b%"), "a b%");
+        assert_eq!(percent_decode("%C3%B1andu"), "ñandu");
+    }
+
+    #[test]
+    fn suggest_repaired() {
+        let answers = WordDictionary::new(b"lightmightsight");
+        let mut conf = Config::default();
+        conf.tolerate_typos = true;
+        let cache = Cache::default();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        // no answer misses every letter of light
+        write!(client, "GET /suggest?history=light:aaaaa HTTP/1.1\r\n\r\n").unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        handle(&conf, &answers, &answers, &cache, stream).unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        let reply: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(reply["repaired"]["guess"], "light");
+        assert_eq!(reply["repaired"]["feedback"], "aaaaa");
+        assert!(reply["candidates"].as_u64().unwrap() > 0);
+        conf.tolerate_typos = false;
+        let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        write!(client, "GET /suggest?history=light:aaaaa HTTP/1.1\r\n\r\n").unwrap();
+        let (stream, _) = listener.accept().unwrap();
+        handle(&conf, &answers, &answers, &cache, stream).unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 400"));
+    }
+}
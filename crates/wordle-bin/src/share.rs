@@ -0,0 +1,54 @@
+use crate::schema;
+use serde_json::json;
+use std::error::Error;
+use std::io::Read;
+use wordle_lib::analysis::{consistent_answers, consistent_guesses, parse_share_grid};
+use wordle_lib::cli::Config;
+use wordle_lib::{Dictionary, WordDictionary};
+
+pub fn run(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<(), Box<dyn Error>> {
+    let mut text = String::new();
+    std::io::stdin().read_to_string(&mut text)?;
+    let grid = parse_share_grid(&text)?;
+    match conf.answer {
+        Some(answer) if conf.json => {
+            let rows: Vec<_> = grid
+                .iter()
+                .map(|wm| {
+                    let mut words = Vec::new();
+                    consistent_guesses(*wm, answer, guesses)
+                        .for_each(|guess| words.push(conf.alphabet.decode(guess)));
+                    json!({ "feedback": wm.to_string(), "guesses": words })
+                })
+                .collect();
+            schema::print("share", json!({ "rows": rows }));
+        }
+        Some(answer) => {
+            for wm in grid.iter() {
+                let row = consistent_guesses(*wm, answer, guesses);
+                let mut line = format!("{}: {}", wm, row.len());
+                row.for_each(|guess| {
+                    line.push(' ');
+                    line.push_str(&conf.alphabet.decode(guess));
+                });
+                println!("{}", line);
+            }
+        }
+        None => {
+            let answers = consistent_answers(&grid, guesses, answers);
+            if conf.json {
+                let mut words = Vec::new();
+                answers.for_each(|answer| words.push(conf.alphabet.decode(answer)));
+                schema::print("share", json!({ "answers": words }));
+                return Ok(());
+            }
+            answers.for_each(|answer| println!("{}", conf.alphabet.decode(answer)));
+            eprintln!("answers: {}", answers.len());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,263 @@
+use crate::analyze::read_transcripts;
+use crate::{make_strategy, read_tree, schema, NoSolution};
+use serde_json::json;
+use std::error::Error;
+use wordle_lib::analysis::{
+    evaluate_trap_set, game_stats, leaderboard, letter_stats, rate_difficulty, trap_sets,
+};
+use wordle_lib::cli::Config;
+use wordle_lib::{solve, CancellationToken, Rng, WordDictionary};
+
+pub fn run(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<(), Box<dyn Error>> {
+    let stats = letter_stats(answers);
+    let coverage = stats.rank_by_coverage(guesses);
+    let coverage = &coverage[..conf.top_k.min(coverage.len())];
+    if conf.json {
+        let letters: Vec<_> = (0..conf.alphabet.len())
+            .map(|c| {
+                let by_position: Vec<_> = (0..5).map(|pos| stats.by_position[pos][c]).collect();
+                json!({
+                    "letter": conf.alphabet.letter(c).to_string(),
+                    "words": stats.words[c],
+                    "by_position": by_position,
+                })
+            })
+            .collect();
+        let coverage: Vec<_> = coverage
+            .iter()
+            .map(|(guess, coverage)| {
+                json!({ "guess": conf.alphabet.decode(*guess), "coverage": coverage })
+            })
+            .collect();
+        schema::print(
+            "letter_stats",
+            json!({ "letters": letters, "coverage": coverage }),
+        );
+        return Ok(());
+    }
+    println!("letter  words     1     2     3     4     5");
+    for c in 0..conf.alphabet.len() {
+        print!("{:<6} {:>6}", conf.alphabet.letter(c), stats.words[c]);
+        for pos in 0..5 {
+            print!(" {:>5}", stats.by_position[pos][c]);
+        }
+        println!();
+    }
+    println!();
+    for (guess, coverage) in coverage.iter() {
+        println!("{}: {}", conf.alphabet.decode(*guess), coverage);
+    }
+    Ok(())
+}
+
+// the widest histogram bar
+const BAR_WIDTH: usize = 40;
+
+pub fn games(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<(), Box<dyn Error>> {
+    let games = read_transcripts(conf)?;
+    let stats = game_stats(guesses, answers, &games)?;
+    if conf.json {
+        schema::print(
+            "game_stats",
+            json!({
+                "played": stats.games,
+                "wins": stats.wins,
+                "win_rate": stats.win_rate(),
+                "current_streak": stats.current_streak,
+                "max_streak": stats.max_streak,
+                "skill": stats.skill,
+                "distribution": stats.distribution,
+            }),
+        );
+        return Ok(());
+    }
+    println!("played         {}", stats.games);
+    println!("win rate       {:.0}%", stats.win_rate());
+    println!("current streak {}", stats.current_streak);
+    println!("max streak     {}", stats.max_streak);
+    println!("skill          {:.0}", stats.skill);
+    println!();
+    let most = stats.distribution.iter().copied().max().unwrap_or(0).max(1);
+    for idx in 0..conf.depth.max(stats.distribution.len()) {
+        let count = stats.distribution.get(idx).copied().unwrap_or(0);
+        // any win gets at least a sliver of bar
+        let width = (count * BAR_WIDTH).div_ceil(most);
+        println!("{} {} {}", idx + 1, "#".repeat(width), count);
+    }
+    Ok(())
+}
+
+pub fn openers(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<(), Box<dyn Error>> {
+    let stats = letter_stats(answers);
+    let mut openers = Vec::new();
+    for (words, coverage) in stats.disjoint_openers(guesses, conf.words, conf.top_k) {
+        let words: Vec<_> = words.iter().map(|w| conf.alphabet.decode(*w)).collect();
+        if conf.json {
+            openers.push(json!({ "words": words, "coverage": coverage }));
+        } else {
+            println!("{}: {}", words.join(" "), coverage);
+        }
+    }
+    if conf.json {
+        schema::print("openers", json!({ "openers": openers }));
+    }
+    Ok(())
+}
+
+pub fn run_leaderboard(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<(), Box<dyn Error>> {
+    let board = leaderboard(
+        guesses,
+        answers,
+        conf.metric,
+        conf.top_k,
+        conf.breadth,
+        conf.depth,
+        conf.hard,
+    );
+    if conf.json {
+        let scores: Vec<_> = board
+            .iter()
+            .map(|score| {
+                json!({
+                    "guess": conf.alphabet.decode(score.word),
+                    "entropy": score.entropy,
+                    "remaining": score.remaining,
+                    "two_ply": score.two_ply,
+                    "tree": score.tree,
+                })
+            })
+            .collect();
+        schema::print("leaderboard", json!({ "guesses": scores }));
+        return Ok(());
+    }
+    println!(
+        "{:<6} {:>8} {:>9} {:>8} {:>8}",
+        "guess", "entropy", "remaining", "2-ply", "tree"
+    );
+    for score in board.iter() {
+        let tree = score
+            .tree
+            .map_or_else(|| "-".to_string(), |tree| format!("{:.4}", tree));
+        println!(
+            "{:<6} {:>8.4} {:>9.2} {:>8.4} {:>8}",
+            conf.alphabet.decode(score.word),
+            score.entropy,
+            score.remaining,
+            score.two_ply,
+            tree
+        );
+    }
+    Ok(())
+}
+
+pub fn difficulty(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<(), Box<dyn Error>> {
+    let solution = match &conf.tree {
+        Some(path) => read_tree(path)?,
+        None => solve(
+            guesses,
+            answers,
+            conf.breadth,
+            conf.depth,
+            conf.hard,
+            &CancellationToken::default(),
+        )
+        .ok_or(NoSolution(conf.depth))?,
+    };
+    let ratings = rate_difficulty(&solution, guesses, answers, conf.depth);
+    if conf.json {
+        let ratings: Vec<_> = ratings
+            .iter()
+            .map(|rating| {
+                json!({
+                    "answer": conf.alphabet.decode(rating.answer),
+                    "optimal": rating.optimal,
+                    "neighbors": rating.neighbors,
+                    "greedy": rating.greedy,
+                })
+            })
+            .collect();
+        schema::print("difficulty", json!({ "answers": ratings }));
+        return Ok(());
+    }
+    println!("answer,optimal,neighbors,greedy");
+    for rating in ratings {
+        let greedy = rating.greedy.map_or_else(String::new, |n| n.to_string());
+        println!(
+            "{},{},{},{}",
+            conf.alphabet.decode(rating.answer),
+            rating.optimal,
+            rating.neighbors,
+            greedy
+        );
+    }
+    Ok(())
+}
+
+pub fn traps(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<(), Box<dyn Error>> {
+    let seed = conf.seed.unwrap_or_else(Rng::random_seed);
+    let strategy = make_strategy(conf.strategy, conf, seed, guesses, answers)?;
+    let mut reports = Vec::new();
+    for set in trap_sets(answers, conf.trap_size) {
+        let report = evaluate_trap_set(&strategy, answers, &set);
+        if conf.json {
+            let words: Vec<_> = set.words.iter().map(|w| conf.alphabet.decode(*w)).collect();
+            reports.push(json!({
+                "pattern": set.pattern(),
+                "words": words,
+                "mean": report.mean,
+                "worst": report.worst,
+                "worst_game": schema::moves(&conf.alphabet, &report.worst_game),
+            }));
+            continue;
+        }
+        println!(
+            "{} ({}): mean {:.2} worst {}",
+            set.pattern(),
+            set.words.len(),
+            report.mean,
+            report.worst
+        );
+        if report.worst > conf.depth {
+            let game: Vec<_> = report
+                .worst_game
+                .iter()
+                .map(|(guess, wm)| format!("{} {}", conf.alphabet.decode(*guess), wm))
+                .collect();
+            let mode = if conf.hard { "hard mode " } else { "" };
+            println!(
+                "  {}worst case above {}: {}",
+                mode,
+                conf.depth,
+                game.join(", ")
+            );
+        }
+    }
+    if conf.json {
+        schema::print("traps", json!({ "seed": seed, "traps": reports }));
+    }
+    Ok(())
+}
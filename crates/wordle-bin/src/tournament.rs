@@ -0,0 +1,104 @@
+use crate::{make_strategy, schema};
+use serde_json::{json, Map, Value};
+use std::error::Error;
+use std::fs;
+use wordle_lib::analysis::{run_tournament, NoisyStrategy};
+use wordle_lib::cli::Config;
+use wordle_lib::{Alphabet, Dictionary, Rng, Strategy, WordDictionary};
+
+// answers left out keep a weight of 1
+fn read_weights(
+    path: &str,
+    alphabet: &Alphabet,
+    answers: &WordDictionary,
+) -> Result<Vec<f64>, Box<dyn Error>> {
+    let mut weights = vec![1.0; answers.len()];
+    for line in fs::read_to_string(path)?.lines() {
+        let mut fields = line.split_whitespace();
+        if let (Some(word), Some(weight)) = (fields.next(), fields.next()) {
+            let encoded = alphabet
+                .encode(word)
+                .map_err(|err| format!("{}: {}", path, err))?;
+            let idx = answers
+                .index_of(encoded)
+                .ok_or_else(|| format!("{}: not an answer: {}", path, word))?;
+            weights[idx] = weight
+                .parse()
+                .map_err(|err| format!("{}: {}: {}", path, word, err))?;
+        }
+    }
+    Ok(weights)
+}
+
+pub fn run(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<(), Box<dyn Error>> {
+    let seed = conf.seed.unwrap_or_else(Rng::random_seed);
+    let mut entrants = Vec::with_capacity(conf.strategies.len());
+    for &kind in conf.strategies.iter() {
+        let strategy = make_strategy(kind, conf, seed, guesses, answers)?;
+        let strategy: Box<dyn Strategy> = if conf.noise > 0.0 {
+            Box::new(NoisyStrategy::new(strategy, conf.noise, seed))
+        } else {
+            strategy
+        };
+        entrants.push((kind.to_string(), strategy));
+    }
+    let weights = conf
+        .weights
+        .as_deref()
+        .map(|path| read_weights(path, &conf.alphabet, answers))
+        .transpose()?;
+    let standings = run_tournament(&entrants, answers, weights.as_deref(), conf.depth);
+    if conf.json {
+        // wins are keyed by the opponent's name
+        let standings: Vec<_> = standings
+            .iter()
+            .map(|standing| {
+                let wins: Map<String, Value> = standings
+                    .iter()
+                    .zip(standing.wins.iter())
+                    .map(|(other, wins)| (other.name.clone(), json!(wins)))
+                    .collect();
+                json!({
+                    "name": standing.name,
+                    "mean": standing.mean,
+                    "variance": standing.variance,
+                    "failure_rate": standing.failure_rate,
+                    "wins": wins,
+                })
+            })
+            .collect();
+        schema::print(
+            "tournament",
+            json!({ "seed": seed, "standings": standings }),
+        );
+        return Ok(());
+    }
+    print!(
+        "{:<10} {:>8} {:>8} {:>8}",
+        "strategy", "mean", "var", "fail%"
+    );
+    for standing in standings.iter() {
+        print!(" {:>8}", standing.name);
+    }
+    println!();
+    let stat = |stat: Option<f64>| stat.map_or("n/a".to_string(), |stat| format!("{:.4}", stat));
+    for standing in standings.iter() {
+        print!(
+            "{:<10} {:>8} {:>8} {:>8.2}",
+            standing.name,
+            stat(standing.mean),
+            stat(standing.variance),
+            100.0 * standing.failure_rate
+        );
+        for wins in standing.wins.iter() {
+            print!(" {:>8}", wins);
+        }
+        println!();
+    }
+    eprintln!("seed: {}", seed);
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use crate::{read_tree, NoSolution};
+use std::error::Error;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::{BufRead, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+use wordle_lib::analysis::{parse_quiz_results, quiz_position, QuizResult};
+use wordle_lib::cli::Config;
+use wordle_lib::{
+    solve_seeded, solve_with_progress, CancellationToken, Constraints, Dictionary, Rng, Word,
+    WordDictionary,
+};
+
+// quizzes positions from the middle of the tree's games, grading each guess against the tree's
+// by how many more guesses it takes on average. an empty line ends the session
+pub fn run(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<(), Box<dyn Error>> {
+    let solution = match &conf.tree {
+        Some(path) => read_tree(path)?,
+        None => solve_with_progress(
+            guesses,
+            answers,
+            conf.breadth,
+            conf.depth,
+            conf.hard,
+            conf.tiebreak,
+            conf.guess_policy,
+            &CancellationToken::default(),
+            |_| {},
+        )
+        .ok_or(NoSolution(conf.depth))?,
+    };
+    let mut rng = Rng::new(conf.seed.unwrap_or_else(Rng::random_seed));
+    let mut session = Vec::new();
+    let mut lines = io::stdin().lock().lines();
+    'quiz: while let Some(state) = quiz_position(&solution, answers, &mut rng) {
+        let node = solution.follow(&state.history).unwrap();
+        println!();
+        for (guess, wm) in state.history.iter() {
+            println!("{} {}", conf.alphabet.decode(*guess), wm);
+        }
+        println!("{} answers left", state.answers.len());
+        let guess = loop {
+            print!("guess: ");
+            io::stdout().flush()?;
+            let Some(line) = lines.next().transpose()? else {
+                break 'quiz;
+            };
+            let line = line.trim();
+            if line.is_empty() {
+                break 'quiz;
+            }
+            let guess = conf
+                .alphabet
+                .encode(line)
+                .and_then(|guess| Word(guess).check_in(guesses).map(|_| guess));
+            match guess {
+                Ok(guess) => break guess,
+                Err(err) => println!("{}", err),
+            }
+        };
+        let n = state.answers.len() as f64;
+        let extra = if guess == node.guess() {
+            Some(0.0)
+        } else {
+            solve_seeded(
+                guesses,
+                &state.answers,
+                guess,
+                &Constraints::from_history(&state.history)?,
+                conf.breadth,
+                conf.depth - state.history.len(),
+                conf.hard,
+                conf.tiebreak,
+                conf.guess_policy,
+                &CancellationToken::default(),
+            )
+            .map(|tree| (tree.size() as f64 - node.size() as f64) / n)
+        };
+        let result = QuizResult {
+            time: now(),
+            guess,
+            best: node.guess(),
+            extra,
+            history: state.history,
+        };
+        let best = conf.alphabet.decode(result.best);
+        match result.extra {
+            _ if result.correct() => println!("optimal"),
+            Some(extra) => println!("{} is better by {:.3} guesses", best, extra),
+            None => println!(
+                "{} can't finish within {} guesses, {} can",
+                conf.alphabet.decode(guess),
+                conf.depth,
+                best
+            ),
+        }
+        if let Some(path) = &conf.train_file {
+            append_result(path, &result, conf)?;
+        }
+        session.push(result);
+    }
+    eprintln!("session: {}", accuracy(&session));
+    if let Some(path) = &conf.train_file {
+        let text = fs::read_to_string(path).unwrap_or_default();
+        let all = parse_quiz_results(&text, &conf.alphabet)
+            .map_err(|err| format!("{}: {}", path, err))?;
+        eprintln!("all time: {}", accuracy(&all));
+    }
+    Ok(())
+}
+
+fn accuracy(results: &[QuizResult]) -> String {
+    let correct = results.iter().filter(|result| result.correct()).count();
+    if results.is_empty() {
+        return "no positions".to_string();
+    }
+    format!(
+        "{}/{} optimal ({:.1}%)",
+        correct,
+        results.len(),
+        100.0 * correct as f64 / results.len() as f64
+    )
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn append_result(path: &str, result: &QuizResult, conf: &Config) -> io::Result<()> {
+    let mut text = Vec::new();
+    result.write(&conf.alphabet, &mut text)?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(&text)
+}
@@ -0,0 +1,601 @@
+use crate::read_tree;
+use ratatui::crossterm::event;
+use ratatui::crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::error::Error;
+use std::fs;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::Write;
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use wordle_lib::analysis::Transcript;
+use wordle_lib::cli::Config;
+use wordle_lib::io::ColorScheme;
+use wordle_lib::{
+    hint, rank_guesses, spawn_refinement, CancellationToken, Dictionary, GameState, Hint,
+    KeyboardLayout, RankedGuess, RecentAnswers, Repair, Rng, Solution, Suggestion, WordDictionary,
+    WordMatch, MAX_LETTERS,
+};
+
+// how often the screen picks up refined suggestions while waiting for a key
+const REFRESH: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Copy, Eq, Ord, PartialEq, PartialOrd)]
+enum LetterState {
+    Unknown,
+    Absent,
+    Present,
+    Correct,
+}
+
+impl LetterState {
+    fn style(self, scheme: ColorScheme) -> Style {
+        let (correct, present) = match scheme {
+            ColorScheme::HighContrast => (Color::Indexed(208), Color::Indexed(39)),
+            _ => (Color::Green, Color::Yellow),
+        };
+        match self {
+            LetterState::Unknown => Style::new().fg(Color::White),
+            LetterState::Absent => Style::new().fg(Color::White).bg(Color::DarkGray),
+            LetterState::Present => Style::new().fg(Color::Black).bg(present),
+            LetterState::Correct => Style::new().fg(Color::Black).bg(correct),
+        }
+    }
+
+    // ascii marks greens with brackets, yellows with parentheses and grays in lowercase
+    fn tile(self, c: char, scheme: ColorScheme) -> Span<'static> {
+        let upper = c.to_uppercase();
+        if scheme != ColorScheme::Ascii {
+            return Span::styled(format!(" {} ", upper), self.style(scheme));
+        }
+        Span::raw(match self {
+            LetterState::Unknown => format!(" {} ", upper),
+            LetterState::Absent => format!(" {} ", c.to_lowercase()),
+            LetterState::Present => format!("({})", upper),
+            LetterState::Correct => format!("[{}]", upper),
+        })
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum Mode {
+    // the answer is hidden and feedback is computed
+    Play,
+    // feedback from another game is typed in after each guess
+    Assist,
+}
+
+struct App<'a> {
+    conf: &'a Config,
+    guesses: &'a WordDictionary,
+    answers: &'a WordDictionary,
+    rng: Rng,
+    recent: RecentAnswers,
+    mode: Mode,
+    answer: [u8; 5],
+    state: GameState,
+    input: String,
+    pending: Option<[u8; 5]>,
+    suggestions: Vec<RankedGuess>,
+    // the tree assist follows, and its guess for the current game along with where it came
+    // from. off the tree, a background search keeps improving the guess
+    book: Option<Solution>,
+    best: Option<([u8; 5], String)>,
+    refining: Option<(Receiver<Suggestion>, CancellationToken)>,
+    hint_level: u8,
+    message: String,
+    // the game so far, added to --transcript-file once it ends
+    transcript: Transcript,
+}
+
+impl<'a> App<'a> {
+    fn new(
+        conf: &'a Config,
+        guesses: &'a WordDictionary,
+        answers: &'a WordDictionary,
+        seed: u64,
+        recent: RecentAnswers,
+        book: Option<Solution>,
+    ) -> Self {
+        let mut app = Self {
+            conf,
+            guesses,
+            answers,
+            rng: Rng::new(seed),
+            recent,
+            mode: Mode::Play,
+            answer: [0; 5],
+            state: GameState::new(answers),
+            input: String::new(),
+            pending: None,
+            suggestions: Vec::new(),
+            book,
+            best: None,
+            refining: None,
+            hint_level: 0,
+            message: String::new(),
+            transcript: Transcript::default(),
+        };
+        app.reset();
+        app
+    }
+
+    fn reset(&mut self) {
+        self.message = match self.mode {
+            Mode::Play => "guess the word".to_string(),
+            Mode::Assist => "type a guess, then its feedback as a/p/c".to_string(),
+        };
+        self.answer = match self.conf.answer {
+            // only the first game uses --answer
+            Some(answer) if self.answer == [0; 5] => answer,
+            // assist never reveals its answer, so it doesn't use one up
+            _ if self.mode == Mode::Assist => self.answer,
+            _ => self.pick_answer(),
+        };
+        self.state = GameState::new(self.answers);
+        self.transcript = Transcript {
+            answer: Some(self.answer),
+            started: Some(now()),
+            guesses: Vec::new(),
+        };
+        self.input.clear();
+        self.pending = None;
+        self.hint_level = 0;
+        self.suggest();
+    }
+
+    fn pick_answer(&mut self) -> [u8; 5] {
+        let answer = self.recent.pick(&mut self.rng, self.answers);
+        if let Some(path) = &self.conf.recent_file {
+            if let Err(err) = write_recent(path, &self.recent, self.conf) {
+                self.message = format!("can't save recent answers: {}", err);
+            }
+        }
+        answer
+    }
+
+    fn suggest(&mut self) {
+        let mut ranked = rank_guesses(self.guesses, &self.state.answers);
+        ranked.truncate(self.conf.top_k);
+        self.suggestions = ranked;
+        self.best = None;
+        if let Some((_, cancel)) = self.refining.take() {
+            cancel.cancel();
+        }
+        if self.mode != Mode::Assist {
+            return;
+        }
+        let book = self.book.as_ref();
+        match book.and_then(|book| book.follow(&self.state.history)) {
+            Some(node) => self.best = Some((node.guess(), "book".to_string())),
+            // searching the whole game from the start takes too long to be worth it
+            None if !self.state.history.is_empty() => {
+                self.refining = Some(spawn_refinement(
+                    self.state.clone(),
+                    self.guesses.clone(),
+                    self.conf.breadth,
+                    self.conf.depth,
+                    self.conf.hard,
+                ));
+            }
+            None => {}
+        }
+    }
+
+    // picks up whatever the background search has found since the last frame
+    fn refine(&mut self) {
+        let Some((rx, _)) = &self.refining else {
+            return;
+        };
+        while let Ok(suggestion) = rx.try_recv() {
+            let source = match suggestion.breadth {
+                Some(breadth) => format!("search, breadth {}", breadth),
+                None => "entropy".to_string(),
+            };
+            self.best = Some((suggestion.word, source));
+        }
+    }
+
+    fn hint(&mut self) {
+        if self.finished() {
+            return;
+        }
+        self.hint_level = (self.hint_level + 1).min(4);
+        // the book already holds the optimal guess, which saves solving for it
+        let book = self.book.as_ref();
+        let optimal = book.and_then(|book| book.follow(&self.state.history));
+        let hint = match optimal {
+            Some(node) if self.hint_level == 4 => Ok(Hint::Guess(node.guess())),
+            _ => hint(
+                self.hint_level,
+                self.guesses,
+                &self.state,
+                self.conf.breadth,
+                self.conf.depth,
+                self.conf.hard,
+            ),
+        };
+        self.message = match hint {
+            Ok(hint) => format!("hint {}: {}", self.hint_level, hint),
+            Err(err) => err,
+        };
+    }
+
+    fn finished(&self) -> bool {
+        self.state.answers.is_empty()
+            || self.state.history.len() >= self.conf.depth
+            || self
+                .state
+                .history
+                .last()
+                .is_some_and(|(_, wm)| *wm == WordMatch::CORRECT)
+    }
+
+    fn submit(&mut self) {
+        if self.finished() {
+            return;
+        }
+        let text = std::mem::take(&mut self.input);
+        if let Some(guess) = self.pending {
+            match text.parse::<WordMatch>() {
+                Ok(wm) => {
+                    self.pending = None;
+                    self.play(guess, wm);
+                }
+                Err(err) => self.message = err,
+            }
+            return;
+        }
+        let guess = match self.conf.alphabet.encode(&text) {
+            Ok(guess) if self.guesses.index_of(guess).is_some() => guess,
+            Ok(_) => {
+                self.message = format!("not in word list: {}", text);
+                return;
+            }
+            Err(err) => {
+                self.message = err;
+                return;
+            }
+        };
+        match self.mode {
+            Mode::Play => self.play(guess, self.answers.word_match(guess, self.answer)),
+            Mode::Assist => {
+                self.pending = Some(guess);
+                self.message = format!("feedback for {}", text);
+            }
+        }
+    }
+
+    fn play(&mut self, guess: [u8; 5], wm: WordMatch) {
+        self.state.guess(guess, wm);
+        self.transcript.guesses.push((guess, wm, Some(now())));
+        self.hint_level = 0;
+        if self.mode == Mode::Assist && self.conf.tolerate_typos {
+            if let Some(repair) = self.state.repair(self.answers) {
+                self.repair(repair);
+                return;
+            }
+        }
+        self.message = if wm == WordMatch::CORRECT {
+            format!("solved in {}", self.state.history.len())
+        } else if self.state.answers.is_empty() {
+            "no answers match that feedback".to_string()
+        } else if self.state.history.len() >= self.conf.depth {
+            format!("the answer was {}", self.conf.alphabet.decode(self.answer))
+        } else {
+            String::new()
+        };
+        if !self.finished() {
+            self.suggest();
+        } else if self.mode == Mode::Play {
+            if let Some(path) = &self.conf.transcript_file {
+                if let Err(err) = append_transcript(path, &self.transcript, self.conf) {
+                    self.message = format!("can't save transcript: {}", err);
+                }
+            }
+        }
+    }
+
+    // carries on from the corrected history, warning which feedback was likely mistyped
+    fn repair(&mut self, repair: Repair) {
+        let (guess, wm) = self.state.history[repair.entry];
+        self.transcript.guesses[repair.entry].1 = repair.feedback;
+        self.state = repair.state;
+        self.message = format!(
+            "no answers match; guess {} ({}) was likely {}, not {}",
+            repair.entry + 1,
+            self.conf.alphabet.decode(guess),
+            repair.feedback,
+            wm
+        );
+        if !self.finished() {
+            self.suggest();
+        }
+    }
+
+    fn key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        match code {
+            KeyCode::Esc => return false,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Char('n') if modifiers.contains(KeyModifiers::CONTROL) => self.reset(),
+            KeyCode::Tab => {
+                self.mode = match self.mode {
+                    Mode::Play => Mode::Assist,
+                    Mode::Assist => Mode::Play,
+                };
+                self.reset();
+            }
+            KeyCode::Enter => self.submit(),
+            KeyCode::Char('?') => self.hint(),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Char(c) if self.input.chars().count() < 5 => {
+                self.input.extend(c.to_lowercase());
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn letter_states(&self) -> [LetterState; MAX_LETTERS] {
+        let mut states = [LetterState::Unknown; MAX_LETTERS];
+        for (guess, wm) in self.state.history.iter() {
+            for (&c, m) in guess.iter().zip(wm.to_string().chars()) {
+                let state = match m {
+                    'c' => LetterState::Correct,
+                    'p' => LetterState::Present,
+                    _ => LetterState::Absent,
+                };
+                let idx = (c - b'a') as usize;
+                states[idx] = states[idx].max(state);
+            }
+        }
+        states
+    }
+
+    fn board(&self) -> Vec<Line<'static>> {
+        let alphabet = &self.conf.alphabet;
+        let mut lines = Vec::with_capacity(self.conf.depth);
+        for (guess, wm) in self.state.history.iter() {
+            let spans = alphabet
+                .decode(*guess)
+                .chars()
+                .zip(wm.to_string().chars())
+                .map(|(c, m)| {
+                    let state = match m {
+                        'c' => LetterState::Correct,
+                        'p' => LetterState::Present,
+                        _ => LetterState::Absent,
+                    };
+                    state.tile(c, self.conf.color_scheme)
+                })
+                .collect::<Vec<_>>();
+            lines.push(Line::from(spans));
+        }
+        if lines.len() < self.conf.depth && !self.finished() {
+            let row = match self.pending {
+                Some(guess) => alphabet.decode(guess),
+                None => self.input.clone(),
+            };
+            let mut spans: Vec<_> = row
+                .chars()
+                .map(|c| {
+                    Span::styled(
+                        format!(" {} ", c.to_uppercase()),
+                        Style::new().add_modifier(Modifier::BOLD),
+                    )
+                })
+                .collect();
+            spans.resize(5, Span::raw(" _ "));
+            lines.push(Line::from(spans));
+            if self.pending.is_some() {
+                lines.push(Line::from(format!(" {}", self.input)));
+            }
+        }
+        while lines.len() < self.conf.depth {
+            lines.push(Line::from(" _  _  _  _  _ "));
+        }
+        lines
+    }
+
+    fn keyboard(&self) -> Vec<Line<'static>> {
+        let states = self.letter_states();
+        let alphabet = &self.conf.alphabet;
+        let layout = self
+            .conf
+            .keyboard
+            .unwrap_or_else(|| KeyboardLayout::for_alphabet(alphabet));
+        layout
+            .rows(alphabet)
+            .iter()
+            .map(|row| {
+                Line::from(
+                    row.chars()
+                        .map(|c| {
+                            let state = (0..alphabet.len())
+                                .find(|&idx| alphabet.letter(idx) == c)
+                                .map_or(LetterState::Unknown, |idx| states[idx]);
+                            state.tile(c, self.conf.color_scheme)
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect()
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [left, right] =
+            Layout::horizontal([Constraint::Length(36), Constraint::Min(0)]).areas(main);
+        let [board, keyboard] = Layout::vertical([
+            Constraint::Length(self.conf.depth as u16 + 3),
+            Constraint::Min(0),
+        ])
+        .areas(left);
+        let title = match self.mode {
+            Mode::Play => " play ",
+            Mode::Assist => " assist ",
+        };
+        frame.render_widget(
+            Paragraph::new(self.board()).block(Block::bordered().title(title)),
+            board,
+        );
+        frame.render_widget(
+            Paragraph::new(self.keyboard()).block(Block::bordered().title(" keyboard ")),
+            keyboard,
+        );
+        let mut lines = vec![Line::from(format!(
+            "candidates: {}",
+            self.state.answers.len()
+        ))];
+        if let Some((guess, source)) = &self.best {
+            lines.push(Line::from(format!(
+                "best: {} ({})",
+                self.conf.alphabet.decode(*guess),
+                source
+            )));
+        }
+        lines.push(Line::from(""));
+        if !self.suggestions.is_empty() {
+            lines.push(Line::from("guess  bits   solve  ≤2"));
+        }
+        for guess in self.suggestions.iter() {
+            let marker = if guess.candidate { " *" } else { "" };
+            lines.push(Line::from(format!(
+                "{}  {:.3}  {:>3.0}%  {:>3.0}%{}",
+                self.conf.alphabet.decode(guess.word),
+                guess.entropy,
+                guess.solve_chance * 100.0,
+                guess.narrow_chance * 100.0,
+                marker
+            )));
+        }
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title(" suggestions ")),
+            right,
+        );
+        frame.render_widget(
+            Paragraph::new(format!(
+                "{}  [enter] submit  [?] hint  [tab] play/assist  [ctrl-n] new game  [esc] quit",
+                self.message
+            )),
+            status,
+        );
+    }
+}
+
+fn run_app(terminal: &mut DefaultTerminal, mut app: App) -> Result<(), Box<dyn Error>> {
+    loop {
+        app.refine();
+        terminal.draw(|frame| app.draw(frame))?;
+        if !event::poll(REFRESH)? {
+            continue;
+        }
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !app.key(key.code, key.modifiers) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+fn append_transcript(path: &str, transcript: &Transcript, conf: &Config) -> io::Result<()> {
+    // one write, so a crash can't leave half a game
+    let mut text = Vec::new();
+    transcript.write(&conf.alphabet, &mut text)?;
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(&text)
+}
+
+// one answer per line, oldest first
+fn read_recent(conf: &Config) -> Result<RecentAnswers, Box<dyn Error>> {
+    let text = match &conf.recent_file {
+        Some(path) => match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err.into()),
+        },
+        None => String::new(),
+    };
+    let words = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| conf.alphabet.encode(line.trim()))
+        .collect::<Result<_, _>>()?;
+    Ok(RecentAnswers::new(words, conf.avoid_recent))
+}
+
+fn write_recent(path: &str, recent: &RecentAnswers, conf: &Config) -> io::Result<()> {
+    let mut text = String::new();
+    for word in recent.words() {
+        text.push_str(&conf.alphabet.decode(word));
+        text.push('\n');
+    }
+    fs::write(path, text)
+}
+
+// --tree, or else the embedded tree when the game uses the lists and rules it was solved for
+fn book(conf: &Config, answers: &WordDictionary) -> Result<Option<Solution>, Box<dyn Error>> {
+    match &conf.tree {
+        Some(path) => Ok(Some(read_tree(path)?)),
+        None => embedded_tree(conf, answers),
+    }
+}
+
+#[cfg(feature = "embedded-tree")]
+fn embedded_tree(
+    conf: &Config,
+    answers: &WordDictionary,
+) -> Result<Option<Solution>, Box<dyn Error>> {
+    if conf.answers_file.is_some()
+        || conf.guesses_file.is_some()
+        || conf.forbidden_file.is_some()
+        || conf.common_file.is_some()
+        || conf.limit_guesses
+        || conf.hard
+        || conf.rules != wordle_lib::MatchRules::Nyt
+    {
+        return Ok(None);
+    }
+    let solution = Solution::read_dag(wordle_lib::DEFAULT_TREE)?;
+    Ok(Some(solution).filter(|solution| solution.failures(answers, conf.depth) == 0))
+}
+
+#[cfg(not(feature = "embedded-tree"))]
+fn embedded_tree(
+    _conf: &Config,
+    _answers: &WordDictionary,
+) -> Result<Option<Solution>, Box<dyn Error>> {
+    Ok(None)
+}
+
+pub fn run(
+    conf: &Config,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> Result<(), Box<dyn Error>> {
+    let seed = conf.seed.unwrap_or_else(Rng::random_seed);
+    let recent = read_recent(conf)?;
+    let app = App::new(conf, guesses, answers, seed, recent, book(conf, answers)?);
+    let mut terminal = ratatui::init();
+    let result = run_app(&mut terminal, app);
+    ratatui::restore();
+    eprintln!("seed: {}", seed);
+    result
+}
@@ -0,0 +1,70 @@
+use crate::schema;
+use serde_json::json;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use wordle_lib::cli::Config;
+use wordle_lib::io::{extract_word_lists, to_lines};
+use wordle_lib::{Dictionary, WordDictionary};
+
+const WORDLE_URL: &str = "https://www.nytimes.com/games/wordle/index.html";
+
+fn fetch(url: &str) -> Result<String, Box<dyn Error>> {
+    Ok(ureq::get(url).call()?.body_mut().read_to_string()?)
+}
+
+fn script_urls(base: &str, html: &str) -> Vec<String> {
+    html.split("src=\"")
+        .skip(1)
+        .filter_map(|rest| rest.split('"').next())
+        .filter(|src| src.ends_with(".js"))
+        .map(|src| {
+            if src.starts_with("http") {
+                src.to_string()
+            } else if let Some(path) = src.strip_prefix('/') {
+                let origin = base.splitn(4, '/').take(3).collect::<Vec<_>>().join("/");
+                format!("{}/{}", origin, path)
+            } else {
+                format!("{}/{}", &base[..base.rfind('/').unwrap_or(base.len())], src)
+            }
+        })
+        .collect()
+}
+
+fn download(url: &str) -> Result<(WordDictionary, WordDictionary), Box<dyn Error>> {
+    let text = fetch(url)?;
+    if let Ok(lists) = extract_word_lists(&text) {
+        return Ok(lists);
+    }
+    for script in script_urls(url, &text) {
+        if let Ok(lists) = extract_word_lists(&fetch(&script)?) {
+            eprintln!("found lists in {}", script);
+            return Ok(lists);
+        }
+    }
+    Err(format!("no word lists found at {}", url).into())
+}
+
+pub fn run(conf: &Config) -> Result<(), Box<dyn Error>> {
+    let (answers, guesses) = download(conf.url.as_deref().unwrap_or(WORDLE_URL))?;
+    // validate makes sure --out was given
+    let out = Path::new(conf.out.as_deref().unwrap_or_default());
+    fs::create_dir_all(out)?;
+    let answers_file = out.join("answers-lines.txt").display().to_string();
+    let guesses_file = out.join("guesses-lines.txt").display().to_string();
+    fs::write(&answers_file, to_lines(&answers))?;
+    fs::write(&guesses_file, to_lines(&guesses))?;
+    if conf.json {
+        schema::print(
+            "lists",
+            json!({
+                "answers": { "count": answers.len(), "file": answers_file },
+                "guesses": { "count": guesses.len(), "file": guesses_file },
+            }),
+        );
+        return Ok(());
+    }
+    eprintln!("answers: {} ({})", answers.len(), answers_file);
+    eprintln!("guesses: {} ({})", guesses.len(), guesses_file);
+    Ok(())
+}
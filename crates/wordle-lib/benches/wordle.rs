@@ -1,7 +1,8 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use std::fs::File;
-use std::io::BufReader;
-use wordle_lib::{Dictionary, OffsetDictionary, WordDictionary, WordMatch, LEGAL_GUESSES};
+use wordle_lib::{
+    solve, solve_hard_limited, CancellationToken, Dictionary, MatchTable, OffsetDictionary,
+    SearchMetrics, WordDictionary, WordMatch, LEGAL_ANSWERS, LEGAL_GUESSES,
+};
 
 fn word_guesses_partition(c: &mut Criterion) {
     let dict = &WordDictionary::new(LEGAL_GUESSES);
@@ -17,19 +18,84 @@ fn word_guesses_partition(c: &mut Criterion) {
 }
 
 fn offset_guesses_partition(c: &mut Criterion) {
-    let dict = &OffsetDictionary {
-        words: (0..12972).collect(),
-    };
-    let guess = 9622;
+    let table = MatchTable::embedded();
+    let dict = &OffsetDictionary::new(&table);
+    let guess = 1655;
     c.bench_function("offset_guesses_partition_large", |b| {
         b.iter(|| dict.partition(black_box(guess)))
     });
     let dict = &dict.partition(guess).remove(&WordMatch::ABSENT).unwrap();
-    let guess = 4341;
+    let guess = 850;
     c.bench_function("offset_guesses_partition_small", |b| {
         b.iter(|| dict.partition(black_box(guess)))
     });
 }
 
-criterion_group!(benches, word_guesses_partition, offset_guesses_partition);
+fn solve_reduced(c: &mut Criterion) {
+    let answers = &WordDictionary::new(LEGAL_ANSWERS)
+        .partition(*b"raise")
+        .remove(&WordMatch::ABSENT)
+        .unwrap();
+    let mut group = c.benchmark_group("solve");
+    group.sample_size(10);
+    for depth in [3, 4] {
+        group.bench_function(format!("solve_depth_{}", depth), |b| {
+            b.iter(|| {
+                solve(
+                    answers,
+                    answers,
+                    10.into(),
+                    black_box(depth),
+                    false,
+                    &CancellationToken::default(),
+                )
+            })
+        });
+    }
+    let guesses = &WordDictionary::new(LEGAL_GUESSES);
+    group.bench_function("solve_hard_depth_3", |b| {
+        b.iter(|| {
+            solve(
+                guesses,
+                answers,
+                10.into(),
+                black_box(3),
+                true,
+                &CancellationToken::default(),
+            )
+        })
+    });
+    group.finish();
+}
+
+fn solve_hard_limited_table(c: &mut Criterion) {
+    let table = MatchTable::embedded();
+    let dict = &OffsetDictionary::new(&table)
+        .partition(1655)
+        .remove(&WordMatch::ABSENT)
+        .unwrap();
+    let guess = dict.words[0];
+    let mut group = c.benchmark_group("solve");
+    group.sample_size(10);
+    group.bench_function("solve_hard_limited", |b| {
+        b.iter(|| {
+            solve_hard_limited(
+                black_box(guess),
+                dict,
+                4,
+                &SearchMetrics::default(),
+                &CancellationToken::default(),
+            )
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    word_guesses_partition,
+    offset_guesses_partition,
+    solve_reduced,
+    solve_hard_limited_table
+);
 criterion_main!(benches);
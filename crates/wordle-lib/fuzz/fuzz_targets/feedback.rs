@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wordle_lib::analysis::parse_share_grid;
+use wordle_lib::WordMatch;
+
+fuzz_target!(|text: &str| {
+    if let Ok(wm) = text.parse::<WordMatch>() {
+        assert_eq!(wm.to_string(), text);
+    }
+    let _ = parse_share_grid(text);
+});
@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wordle_lib::Solution;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(solution) = Solution::read_dag(data) {
+        let mut dag = Vec::new();
+        solution.write_dag(&mut dag).unwrap();
+        Solution::read_dag(&dag[..]).unwrap();
+    }
+});
@@ -0,0 +1,17 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use wordle_lib::io::extract_word_lists;
+use wordle_lib::{Alphabet, Dictionary, WordDictionary};
+
+fuzz_target!(|text: &str| {
+    for alphabet in ["english", "spanish", "german"] {
+        let alphabet: Alphabet = alphabet.parse().unwrap();
+        if let Ok(dict) = WordDictionary::from_lines_in(text, &alphabet) {
+            dict.for_each(|word| {
+                alphabet.decode(word);
+            });
+        }
+    }
+    let _ = extract_word_lists(text);
+});
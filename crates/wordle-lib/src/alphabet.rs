@@ -0,0 +1,204 @@
+use std::fmt;
+use std::fmt::Display;
+use std::str::FromStr;
+
+// letters are stored as b'a' plus their index in the alphabet, one byte each. 31 letters keep
+// those bytes ascii, which the tree formats write as they are, and fit the u32 masks of letters
+// ruled out per position in Constraints. WordMatch counts each letter in 3 bits of a u128, which
+// has room to spare
+pub const MAX_LETTERS: usize = 31;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Alphabet {
+    letters: Vec<char>,
+}
+
+impl Alphabet {
+    pub fn english() -> Self {
+        Self {
+            letters: ('a'..='z').collect(),
+        }
+    }
+
+    pub fn with_extra_letters(extra: &str) -> Result<Self, String> {
+        let mut this = Self::english();
+        for c in extra.chars().flat_map(char::to_lowercase) {
+            if this.letters.contains(&c) {
+                return Err(format!("duplicate letter: {}", c));
+            }
+            this.letters.push(c);
+        }
+        if this.letters.len() > MAX_LETTERS {
+            return Err(format!("at most {} letters are supported", MAX_LETTERS));
+        }
+        Ok(this)
+    }
+
+    pub fn len(&self) -> usize {
+        self.letters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    pub fn letter(&self, idx: usize) -> char {
+        self.letters[idx]
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        self.letters.contains(&c)
+    }
+
+    pub fn encode(&self, word: &str) -> Result<[u8; 5], String> {
+        let mut encoded = [0; 5];
+        let mut chars = word.chars().flat_map(char::to_lowercase);
+        for byte in encoded.iter_mut() {
+            let c = chars
+                .next()
+                .ok_or_else(|| format!("word must have 5 letters: {}", word))?;
+            let idx = self
+                .letters
+                .iter()
+                .position(|&letter| letter == c)
+                .ok_or_else(|| format!("letter not in alphabet: {}", c))?;
+            *byte = b'a' + idx as u8;
+        }
+        if chars.next().is_some() {
+            return Err(format!("word must have 5 letters: {}", word));
+        }
+        Ok(encoded)
+    }
+
+    pub fn decode(&self, word: [u8; 5]) -> String {
+        word.iter()
+            .map(|&c| self.letters[(c - b'a') as usize])
+            .collect()
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+impl FromStr for Alphabet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "english" => Ok(Self::english()),
+            "spanish" => Self::with_extra_letters("ñ"),
+            "german" => Self::with_extra_letters("äöüß"),
+            extra => Self::with_extra_letters(extra),
+        }
+    }
+}
+
+// writes the extra letters so the result parses back to the same alphabet
+impl Display for Alphabet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.letters.len() == 26 {
+            return f.write_str("english");
+        }
+        self.letters[26..]
+            .iter()
+            .try_for_each(|c| write!(f, "{}", c))
+    }
+}
+
+// the keys of a physical keyboard, with each language's extra letters where its own layout
+// puts them
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum KeyboardLayout {
+    Qwerty,
+    Azerty,
+    Qwertz,
+}
+
+impl KeyboardLayout {
+    // the layout speakers of the alphabet's language usually type on
+    pub fn for_alphabet(alphabet: &Alphabet) -> Self {
+        if alphabet.contains('ß') || alphabet.contains('ä') {
+            Self::Qwertz
+        } else {
+            Self::Qwerty
+        }
+    }
+
+    // the rows holding the alphabet's letters, with letters the layout has no key for in a row
+    // of their own at the bottom
+    pub fn rows(self, alphabet: &Alphabet) -> Vec<String> {
+        let keys: &[&str] = match self {
+            Self::Qwerty => &["qwertyuiop", "asdfghjklñ", "zxcvbnm"],
+            Self::Azerty => &["azertyuiop", "qsdfghjklm", "wxcvbn"],
+            Self::Qwertz => &["qwertzuiopü", "asdfghjklöä", "yxcvbnmß"],
+        };
+        let mut rows: Vec<String> = keys
+            .iter()
+            .map(|row| row.chars().filter(|&c| alphabet.contains(c)).collect())
+            .collect();
+        rows.push(
+            alphabet
+                .letters
+                .iter()
+                .filter(|&&c| !keys.iter().any(|row| row.contains(c)))
+                .collect(),
+        );
+        rows.retain(|row| !row.is_empty());
+        rows
+    }
+}
+
+impl FromStr for KeyboardLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "qwerty" => Ok(Self::Qwerty),
+            "azerty" => Ok(Self::Azerty),
+            "qwertz" => Ok(Self::Qwertz),
+            _ => Err(format!("unknown keyboard layout: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Alphabet, KeyboardLayout, WordMatch};
+
+    #[test]
+    fn spanish() {
+        let alphabet: Alphabet = "spanish".parse().unwrap();
+        let guess = alphabet.encode("ÑANDU").unwrap();
+        let answer = alphabet.encode("caña!").err();
+        assert_eq!(answer, Some("letter not in alphabet: !".to_string()));
+        let answer = alphabet.encode("cañas").unwrap();
+        assert_eq!(alphabet.decode(guess), "ñandu");
+        assert_eq!(WordMatch::from(guess, answer).to_string(), "pcaaa");
+        assert_eq!(alphabet.to_string().parse(), Ok(alphabet));
+    }
+
+    #[test]
+    fn keyboards() {
+        let german: Alphabet = "german".parse().unwrap();
+        let layout = KeyboardLayout::for_alphabet(&german);
+        assert_eq!(layout, KeyboardLayout::Qwertz);
+        assert_eq!(
+            layout.rows(&german),
+            ["qwertzuiopü", "asdfghjklöä", "yxcvbnmß"]
+        );
+        let spanish: Alphabet = "spanish".parse().unwrap();
+        assert_eq!(
+            KeyboardLayout::for_alphabet(&spanish).rows(&spanish)[1],
+            "asdfghjklñ"
+        );
+        // azerty has no key for é, so it goes below
+        let french: Alphabet = "é".parse().unwrap();
+        assert_eq!(
+            KeyboardLayout::Azerty.rows(&french),
+            ["azertyuiop", "qsdfghjklm", "wxcvbn", "é"]
+        );
+    }
+}
@@ -0,0 +1,348 @@
+use crate::{
+    entropy, expected_remaining, Alphabet, Constraints, Dictionary, GameState, Violation,
+    WordDictionary, WordMatch,
+};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::io;
+use std::io::Write;
+
+pub struct GuessAnalysis {
+    pub guess: [u8; 5],
+    pub wm: WordMatch,
+    // candidates before and after the guess
+    pub before: usize,
+    pub after: usize,
+    pub violations: Vec<Violation>,
+    // how many more candidates the guess leaves on average than the best guess that respects
+    // the feedback so far, or zero when it respects the feedback itself
+    pub missed: f64,
+    // the guess's expected information as a percentage of the best guess's
+    pub skill: f64,
+    // bits the feedback actually revealed beyond what the guess was expected to reveal
+    pub luck: f64,
+}
+
+// a played game, as play mode records it: an "answer <word> <time>" line, then one
+// "<guess> <feedback> <time>" line per guess, with times in unix seconds. hand written games
+// may leave out the answer line and the times, and the feedback too when the answer is known.
+// games are separated by blank lines
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Transcript {
+    pub answer: Option<[u8; 5]>,
+    pub started: Option<u64>,
+    pub guesses: Vec<([u8; 5], WordMatch, Option<u64>)>,
+}
+
+impl Transcript {
+    pub fn game(&self) -> Vec<([u8; 5], WordMatch)> {
+        self.guesses
+            .iter()
+            .map(|(guess, wm, _)| (*guess, *wm))
+            .collect()
+    }
+
+    pub fn solved(&self) -> bool {
+        self.guesses
+            .last()
+            .is_some_and(|(_, wm, _)| *wm == WordMatch::CORRECT)
+    }
+
+    pub fn write<W: Write>(&self, alphabet: &Alphabet, w: &mut W) -> io::Result<()> {
+        if let Some(answer) = self.answer {
+            write!(w, "answer {}", alphabet.decode(answer))?;
+            if let Some(started) = self.started {
+                write!(w, " {}", started)?;
+            }
+            writeln!(w)?;
+        }
+        for (guess, wm, time) in self.guesses.iter() {
+            write!(w, "{} {}", alphabet.decode(*guess), wm)?;
+            if let Some(time) = time {
+                write!(w, " {}", time)?;
+            }
+            writeln!(w)?;
+        }
+        writeln!(w)
+    }
+}
+
+fn parse_time(field: Option<&str>, line: &str) -> Result<Option<u64>, String> {
+    field
+        .map(|time| time.parse().map_err(|_| format!("bad time: {}", line)))
+        .transpose()
+}
+
+// the answer, when the transcript doesn't name one, fills in missing feedback
+pub fn parse_transcripts(
+    text: &str,
+    alphabet: &Alphabet,
+    answer: Option<[u8; 5]>,
+) -> Result<Vec<Transcript>, String> {
+    let mut games = Vec::new();
+    let mut game = Transcript::default();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() {
+            if game != Transcript::default() {
+                games.push(std::mem::take(&mut game));
+            }
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let first = fields.next().unwrap();
+        if first == "answer" {
+            if game != Transcript::default() {
+                games.push(std::mem::take(&mut game));
+            }
+            let word = fields
+                .next()
+                .ok_or_else(|| format!("missing answer: {}", line))?;
+            game.answer = Some(alphabet.encode(word)?);
+            game.started = parse_time(fields.next(), line)?;
+            continue;
+        }
+        let guess = alphabet.encode(first)?;
+        let mut field = fields.next();
+        let wm = match (field, game.answer.or(answer)) {
+            // a number is the time, with the feedback left out
+            (Some(feedback), _) if !feedback.starts_with(|c: char| c.is_ascii_digit()) => {
+                field = fields.next();
+                feedback.parse()?
+            }
+            (_, Some(answer)) => WordMatch::from(guess, answer),
+            (_, None) => return Err(format!("missing feedback: {}", line)),
+        };
+        game.guesses.push((guess, wm, parse_time(field, line)?));
+    }
+    if game != Transcript::default() {
+        games.push(game);
+    }
+    Ok(games)
+}
+
+// one game, ignoring any blank lines between its guesses
+pub fn parse_transcript(
+    text: &str,
+    alphabet: &Alphabet,
+    answer: Option<[u8; 5]>,
+) -> Result<Vec<([u8; 5], WordMatch)>, String> {
+    Ok(parse_transcripts(text, alphabet, answer)?
+        .iter()
+        .flat_map(Transcript::game)
+        .collect())
+}
+
+pub fn analyze_game(
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    game: &[([u8; 5], WordMatch)],
+) -> Result<Vec<GuessAnalysis>, String> {
+    let mut state = GameState::new(answers);
+    let mut analysis = Vec::with_capacity(game.len());
+    for &(guess, wm) in game.iter() {
+        let constraints = Constraints::from_history(&state.history)?;
+        let violations = constraints.violations(guess);
+        let missed = if violations.is_empty() {
+            0.0
+        } else {
+            let consistent = constraints.filter(guesses);
+            let consistent = if consistent.is_empty() {
+                &state.answers
+            } else {
+                &consistent
+            };
+            let best = (0..consistent.len())
+                .into_par_iter()
+                .map(|idx| expected_remaining(consistent.word(idx), &state.answers))
+                .reduce(|| f64::INFINITY, f64::min);
+            (expected_remaining(guess, &state.answers) - best).max(0.0)
+        };
+        let expected = entropy(guess, &state.answers);
+        let best = (0..guesses.len())
+            .into_par_iter()
+            .map(|idx| entropy(guesses.word(idx), &state.answers))
+            .reduce(|| 0.0, f64::max);
+        let skill = if best > 0.0 {
+            100.0 * expected / best
+        } else {
+            100.0
+        };
+        let before = state.answers.len();
+        state.guess(guess, wm);
+        let revealed = if state.answers.is_empty() {
+            0.0
+        } else {
+            (before as f64 / state.answers.len() as f64).log2()
+        };
+        analysis.push(GuessAnalysis {
+            guess,
+            wm,
+            before,
+            after: state.answers.len(),
+            violations,
+            missed,
+            skill,
+            luck: revealed - expected,
+        });
+    }
+    Ok(analysis)
+}
+
+// totals over played games, taken in the order they were played
+#[derive(Debug, PartialEq)]
+pub struct GameStats {
+    pub games: usize,
+    pub wins: usize,
+    pub current_streak: usize,
+    pub max_streak: usize,
+    // wins by the number of guesses they took, starting at one guess
+    pub distribution: Vec<usize>,
+    // the mean skill over every guess of every game
+    pub skill: f64,
+}
+
+impl GameStats {
+    pub fn win_rate(&self) -> f64 {
+        if self.games == 0 {
+            0.0
+        } else {
+            100.0 * self.wins as f64 / self.games as f64
+        }
+    }
+}
+
+pub fn game_stats(
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    games: &[Transcript],
+) -> Result<GameStats, String> {
+    let mut stats = GameStats {
+        games: games.len(),
+        wins: 0,
+        current_streak: 0,
+        max_streak: 0,
+        distribution: Vec::new(),
+        skill: 0.0,
+    };
+    let mut skill = 0.0;
+    let mut moves = 0;
+    for game in games.iter() {
+        if game.solved() {
+            stats.wins += 1;
+            stats.current_streak += 1;
+            stats.max_streak = stats.max_streak.max(stats.current_streak);
+            let len = game.guesses.len();
+            if stats.distribution.len() < len {
+                stats.distribution.resize(len, 0);
+            }
+            stats.distribution[len - 1] += 1;
+        } else {
+            stats.current_streak = 0;
+        }
+        let analyses = analyze_game(guesses, answers, &game.game())?;
+        skill += analyses.iter().map(|analysis| analysis.skill).sum::<f64>();
+        moves += analyses.len();
+    }
+    if moves > 0 {
+        stats.skill = skill / moves as f64;
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::analysis::{
+        analyze_game, game_stats, parse_transcript, parse_transcripts, Transcript,
+    };
+    use crate::{Alphabet, Violation, WordDictionary, WordMatch};
+
+    #[test]
+    fn repeated_guess() {
+        let answers = WordDictionary::new(b"lightmightnightsighttightfight");
+        let alphabet = Alphabet::english();
+        assert!(parse_transcript("tight\n", &alphabet, None).is_err());
+        let game =
+            parse_transcript("tight\ntight\nlight ccccc\n", &alphabet, Some(*b"light")).unwrap();
+        let analysis = analyze_game(&answers, &answers, &game).unwrap();
+        assert_eq!((analysis[0].before, analysis[0].after), (6, 5));
+        assert!(analysis[0].violations.is_empty());
+        assert_eq!(analysis[1].violations, [Violation::Gray(b't')]);
+        // repeating the guess leaves all 5, where a family member splits them 1 and 4
+        assert!((analysis[1].missed - 1.6).abs() < 1e-9);
+        assert!(analysis[2].violations.is_empty());
+        // tight splits the six answers 5 and 1, and the 5 came up
+        assert_eq!(analysis[0].skill, 100.0);
+        let expected = -(5.0 / 6.0) * (5.0f64 / 6.0).log2() + (6.0f64).log2() / 6.0;
+        assert!((analysis[0].luck - ((6.0f64 / 5.0).log2() - expected)).abs() < 1e-9);
+        assert_eq!(analysis[1].skill, 0.0);
+    }
+
+    #[test]
+    fn transcripts() {
+        let alphabet = Alphabet::english();
+        let game = Transcript {
+            answer: Some(*b"light"),
+            started: Some(1700000000),
+            guesses: vec![
+                (
+                    *b"tight",
+                    WordMatch::from(*b"tight", *b"light"),
+                    Some(1700000004),
+                ),
+                (*b"light", WordMatch::CORRECT, Some(1700000009)),
+            ],
+        };
+        let mut text = Vec::new();
+        game.write(&alphabet, &mut text).unwrap();
+        let text = String::from_utf8(text).unwrap();
+        assert_eq!(
+            text,
+            "answer light 1700000000\ntight acccc 1700000004\nlight ccccc 1700000009\n\n"
+        );
+        let games = parse_transcripts(
+            &format!("{}sight\nlight 1700000100\n", text),
+            &alphabet,
+            None,
+        );
+        assert!(games.is_err());
+        let games = parse_transcripts(
+            &format!("{}answer night\nsight\nnight 1700000100\n", text),
+            &alphabet,
+            None,
+        )
+        .unwrap();
+        assert_eq!(games.len(), 2);
+        assert_eq!(games[0], game);
+        assert!(games[0].solved());
+        assert_eq!(games[1].started, None);
+        assert_eq!(
+            games[1].guesses[1],
+            (*b"night", WordMatch::CORRECT, Some(1700000100))
+        );
+        assert_eq!(
+            parse_transcript(&text, &alphabet, None).unwrap(),
+            game.game()
+        );
+    }
+
+    #[test]
+    fn stats() {
+        let answers = WordDictionary::new(b"lightmightnightsighttightfight");
+        let alphabet = Alphabet::english();
+        let games = parse_transcripts(
+            "answer light\nlight\n\n\
+             answer fight\ntight\nlight\nmight\nnight\nsight\n\n\
+             answer might\ntight\nlight\nmight\n\n\
+             answer sight\nsight\n\n",
+            &alphabet,
+            None,
+        )
+        .unwrap();
+        let stats = game_stats(&answers, &answers, &games).unwrap();
+        assert_eq!(stats.games, 4);
+        assert_eq!(stats.wins, 3);
+        assert_eq!(stats.win_rate(), 75.0);
+        assert_eq!((stats.current_streak, stats.max_streak), (2, 2));
+        assert_eq!(stats.distribution, [2, 0, 1]);
+        assert!(stats.skill > 0.0 && stats.skill <= 100.0);
+    }
+}
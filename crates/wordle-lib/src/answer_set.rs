@@ -0,0 +1,182 @@
+use std::ops::{BitAnd, BitOr};
+
+// enough 64 bit blocks for the 2309 embedded answers
+const BLOCKS: usize = 2309_usize.div_ceil(64);
+
+// answer indices as a fixed size bitset, so sets reached along different branches compare and
+// hash without sorting
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct AnswerSet([u64; BLOCKS]);
+
+impl AnswerSet {
+    pub const CAPACITY: usize = 64 * BLOCKS;
+
+    pub fn new() -> Self {
+        Self([0; BLOCKS])
+    }
+
+    // None if any index is past the capacity
+    pub fn from_indices<I: IntoIterator<Item = usize>>(indices: I) -> Option<Self> {
+        let mut set = Self::new();
+        for idx in indices {
+            if idx >= Self::CAPACITY {
+                return None;
+            }
+            set.insert(idx);
+        }
+        Some(set)
+    }
+
+    pub fn insert(&mut self, idx: usize) {
+        self.0[idx / 64] |= 1 << (idx % 64);
+    }
+
+    pub fn remove(&mut self, idx: usize) {
+        self.0[idx / 64] &= !(1 << (idx % 64));
+    }
+
+    pub fn contains(&self, idx: usize) -> bool {
+        idx < Self::CAPACITY && self.0[idx / 64] & (1 << (idx % 64)) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.iter().map(|block| block.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|&block| block == 0)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] & other.0[i]))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] | other.0[i]))
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.0.iter().zip(other.0.iter()).all(|(a, b)| a & !b == 0)
+    }
+
+    // indices in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(i, &block)| {
+            let mut block = block;
+            std::iter::from_fn(move || {
+                if block == 0 {
+                    return None;
+                }
+                let bit = block.trailing_zeros() as usize;
+                block &= block - 1;
+                Some(64 * i + bit)
+            })
+        })
+    }
+}
+
+// the words with their letters renamed in order of a rename-invariant signature, so sets that
+// differ only by a consistent renaming of letters usually share a key. renaming keeps every
+// match pattern, which makes such sets play out identically
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CanonicalKey(Vec<[u8; 5]>);
+
+impl CanonicalKey {
+    pub fn new<I: IntoIterator<Item = [u8; 5]>>(words: I) -> Self {
+        let words: Vec<_> = words.into_iter().collect();
+        // how many words hold the letter at each position; ties fall back to the letter itself,
+        // which only costs cache hits, never correctness
+        let mut signatures = [[0u16; 5]; 256];
+        for word in words.iter() {
+            for (pos, &c) in word.iter().enumerate() {
+                signatures[c as usize][pos] += 1;
+            }
+        }
+        let mut letters: Vec<u8> = (0..=u8::MAX)
+            .filter(|&c| signatures[c as usize] != [0; 5])
+            .collect();
+        letters.sort_by(|&a, &b| {
+            signatures[b as usize]
+                .cmp(&signatures[a as usize])
+                .then_with(|| a.cmp(&b))
+        });
+        let mut rename = [0u8; 256];
+        for (rank, &c) in letters.iter().enumerate() {
+            rename[c as usize] = b'a' + rank as u8;
+        }
+        let mut renamed: Vec<_> = words
+            .into_iter()
+            .map(|word| word.map(|c| rename[c as usize]))
+            .collect();
+        renamed.sort_unstable();
+        Self(renamed)
+    }
+}
+
+impl Default for AnswerSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BitAnd for &AnswerSet {
+    type Output = AnswerSet;
+
+    fn bitand(self, other: Self) -> AnswerSet {
+        self.intersection(other)
+    }
+}
+
+impl BitOr for &AnswerSet {
+    type Output = AnswerSet;
+
+    fn bitor(self, other: Self) -> AnswerSet {
+        self.union(other)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{AnswerSet, CanonicalKey};
+    use std::collections::HashSet;
+
+    #[test]
+    fn set_ops() {
+        let a = AnswerSet::from_indices([0, 63, 64, 2308]).unwrap();
+        let b = AnswerSet::from_indices([63, 2308, 100]).unwrap();
+        assert_eq!(a.len(), 4);
+        assert!(a.contains(2308));
+        assert!(!a.contains(100));
+        assert!(!a.contains(AnswerSet::CAPACITY));
+        assert_eq!((&a & &b).iter().collect::<Vec<_>>(), vec![63, 2308]);
+        assert_eq!((&a | &b).len(), 5);
+        assert!((&a & &b).is_subset(&b));
+        assert!(!a.is_subset(&b));
+        let mut c = b.clone();
+        c.remove(100);
+        c.insert(0);
+        c.insert(64);
+        let sets: HashSet<_> = [a, c].into_iter().collect();
+        assert_eq!(sets.len(), 1);
+        assert!(AnswerSet::new().is_empty());
+        assert_eq!(AnswerSet::from_indices([AnswerSet::CAPACITY]), None);
+    }
+
+    #[test]
+    fn canonical_key() {
+        let key = |words: [&[u8; 5]; 3]| CanonicalKey::new(words.map(|word| *word));
+        assert_eq!(
+            key([b"light", b"might", b"night"]),
+            key([b"sight", b"fight", b"right"])
+        );
+        // crane, crate and grate with c->b, r->l, a->o, n->m, e->s, t->d, g->p
+        assert_eq!(
+            key([b"crane", b"crate", b"grate"]),
+            key([b"bloms", b"blods", b"plods"])
+        );
+        assert_ne!(
+            key([b"light", b"might", b"night"]),
+            key([b"light", b"might", b"fiber"])
+        );
+    }
+}
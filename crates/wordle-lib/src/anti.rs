@@ -0,0 +1,156 @@
+use crate::{Breadth, CancellationToken, Dictionary, WordDictionary, WordMatch};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+pub struct StallLine {
+    // guesses the player is guaranteed to make, including the one that finally hits the answer
+    pub guesses: usize,
+    // the game when both sides play their best
+    pub line: Vec<([u8; 5], WordMatch)>,
+}
+
+// anti-wordle: every guess must be consistent with the feedback so far, the player wants to
+// avoid the answer for as long as possible, and the answer is picked adversarially. lines are
+// only followed for depth guesses, considering the breadth most promising guesses at each turn.
+// None if cancelled, since the line found so far may not be the best
+pub fn anti_solve(
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    breadth: Breadth,
+    depth: usize,
+    cancel: &CancellationToken,
+) -> Option<StallLine> {
+    Some(stall(guesses, answers, breadth, depth, usize::MAX, cancel))
+        .filter(|_| !cancel.is_cancelled())
+}
+
+// stops early once the player can make beta guesses, since the caller won't let that happen
+fn stall(
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    breadth: Breadth,
+    depth: usize,
+    beta: usize,
+    cancel: &CancellationToken,
+) -> StallLine {
+    // guessing a possible answer ends the game, since the adversary will make it the answer.
+    // failing that any guess gets the feedback of an answer still left, and the line stops there
+    let hit = (0..guesses.len())
+        .map(|idx| guesses.word(idx))
+        .find(|&guess| answers.index_of(guess).is_some());
+    let mut best = match (hit, guesses.get(0), answers.get(0)) {
+        (Some(guess), _, _) => StallLine {
+            guesses: 1,
+            line: vec![(guess, WordMatch::CORRECT)],
+        },
+        (None, Some(guess), Some(answer)) => StallLine {
+            guesses: 1,
+            line: vec![(guess, WordMatch::from(guess, answer))],
+        },
+        _ => {
+            return StallLine {
+                guesses: 0,
+                line: Vec::new(),
+            }
+        }
+    };
+    if depth <= 1 || cancel.is_cancelled() {
+        return best;
+    }
+    // the adversary wants to leave the player few consistent guesses, so prefer guesses whose
+    // feedback always leaves many
+    let mut options: Vec<_> = (0..guesses.len())
+        .into_par_iter()
+        .filter_map(|idx| {
+            let guess = guesses.word(idx);
+            let patterns: Vec<_> = answers.partition_iter(guess).map(|(wm, _)| wm).collect();
+            if patterns.contains(&WordMatch::CORRECT) {
+                return None;
+            }
+            let mut counts = [0; 243];
+            guesses.for_each(|other| counts[WordMatch::from(guess, other).0 as usize] += 1);
+            let fewest = patterns.iter().map(|wm| counts[wm.0 as usize]).min();
+            Some((fewest, guess))
+        })
+        .collect();
+    options.sort_unstable_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+    options.truncate(breadth.get());
+    for (_, guess) in options {
+        let partition = answers.partition(guess);
+        let mut guess_partition = guesses.partition(guess);
+        let mut buckets: Vec<_> = partition.into_iter().collect();
+        buckets.sort_unstable_by_key(|(wm, answers)| (answers.len(), wm.0));
+        // the adversary picks the feedback that leaves the player the fewest guesses
+        let mut worst: Option<StallLine> = None;
+        for (wm, answers) in buckets {
+            let bound = worst.as_ref().map_or(usize::MAX, |worst| worst.guesses - 1);
+            let guesses = guess_partition
+                .remove(&wm)
+                .unwrap_or_else(|| WordDictionary::with_capacity(0));
+            let mut next = stall(
+                &guesses,
+                &answers,
+                breadth.deeper(),
+                depth - 1,
+                bound,
+                cancel,
+            );
+            next.guesses += 1;
+            next.line.insert(0, (guess, wm));
+            if worst
+                .as_ref()
+                .is_none_or(|worst| next.guesses < worst.guesses)
+            {
+                worst = Some(next);
+            }
+            if worst.as_ref().unwrap().guesses <= best.guesses {
+                break;
+            }
+        }
+        let worst = worst.unwrap();
+        if worst.guesses > best.guesses {
+            best = worst;
+            if best.guesses >= beta {
+                break;
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod test {
+    use crate::analysis::anti_solve;
+    use crate::{CancellationToken, WordDictionary, WordMatch};
+
+    #[test]
+    fn stall() {
+        let answers = WordDictionary::new(b"lightmightnightsight");
+        // bight keeps the four answers together, so the player can then only guess an answer
+        let guesses = WordDictionary::new(b"lightmightnightsightbightcrane");
+        let line = anti_solve(
+            &guesses,
+            &answers,
+            10.into(),
+            6,
+            &CancellationToken::default(),
+        )
+        .unwrap();
+        assert_eq!(line.guesses, 2);
+        assert_eq!(line.line[0], (*b"bight", "acccc".parse().unwrap()));
+        assert_eq!(line.line[1].1, WordMatch::CORRECT);
+        let cancel = CancellationToken::default();
+        cancel.cancel();
+        assert!(anti_solve(&guesses, &answers, 10.into(), 6, &cancel).is_none());
+        // with no answer to guess, the line shows the feedback the guess really gets
+        let guesses = WordDictionary::new(b"bight");
+        let line = anti_solve(
+            &guesses,
+            &answers,
+            10.into(),
+            1,
+            &CancellationToken::default(),
+        )
+        .unwrap();
+        assert_eq!(line.line, [(*b"bight", "acccc".parse().unwrap())]);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::dag::invalid;
+use crate::{Alphabet, Solution, SolutionNode, WordMatch};
+use std::collections::BTreeMap;
+use std::io;
+use std::io::{BufRead, Write};
+
+// precomputed trees for several openers, so the reply to any of them is a lookup instead of a
+// search. the file holds each tree in the dag format under an "opener <word>" line, the word
+// spelled in the alphabet
+#[derive(Clone, Default)]
+pub struct OpeningBook {
+    trees: BTreeMap<[u8; 5], Solution>,
+}
+
+impl OpeningBook {
+    pub fn len(&self) -> usize {
+        self.trees.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.trees.is_empty()
+    }
+
+    pub fn contains(&self, opener: [u8; 5]) -> bool {
+        self.trees.contains_key(&opener)
+    }
+
+    // replaces any tree the book already has for the same opener
+    pub fn insert(&mut self, solution: Solution) {
+        self.trees.insert(solution.guess(), solution);
+    }
+
+    pub fn get(&self, opener: [u8; 5]) -> Option<&Solution> {
+        self.trees.get(&opener)
+    }
+
+    // the openers, best first
+    pub fn openers(&self) -> Vec<[u8; 5]> {
+        let mut openers: Vec<_> = self.trees.keys().copied().collect();
+        openers.sort_by_key(|opener| (self.trees[opener].size(), *opener));
+        openers
+    }
+
+    // where the opener's tree goes after the opener got feedback wm
+    pub fn response(&self, opener: [u8; 5], wm: WordMatch) -> Option<SolutionNode<'_>> {
+        self.get(opener)?.lookup(&[wm])
+    }
+
+    pub fn write<W: Write>(&self, alphabet: &Alphabet, w: &mut W) -> io::Result<()> {
+        for (opener, solution) in self.trees.iter() {
+            writeln!(w, "opener {}", alphabet.decode(*opener))?;
+            solution.write_dag(w)?;
+        }
+        Ok(())
+    }
+
+    pub fn read<R: BufRead>(r: R, alphabet: &Alphabet) -> io::Result<Self> {
+        let mut book = Self::default();
+        let mut section: Option<([u8; 5], Vec<u8>)> = None;
+        for line in r.lines() {
+            let line = line?;
+            if let Some(opener) = line.strip_prefix("opener ") {
+                if let Some((opener, dag)) = section.take() {
+                    book.add_section(alphabet, opener, &dag)?;
+                }
+                let opener = alphabet
+                    .encode(opener)
+                    .map_err(|err| invalid(format!("bad opener: {}", err)))?;
+                section = Some((opener, Vec::new()));
+            } else {
+                let (_, dag) = section
+                    .as_mut()
+                    .ok_or_else(|| invalid(format!("tree before any opener: {}", line)))?;
+                dag.extend_from_slice(line.as_bytes());
+                dag.push(b'\n');
+            }
+        }
+        if let Some((opener, dag)) = section {
+            book.add_section(alphabet, opener, &dag)?;
+        }
+        Ok(book)
+    }
+
+    fn add_section(&mut self, alphabet: &Alphabet, opener: [u8; 5], dag: &[u8]) -> io::Result<()> {
+        let solution = Solution::read_dag(dag)?;
+        if solution.guess() != opener {
+            return Err(invalid(format!(
+                "tree for {} opens with {}",
+                alphabet.decode(opener),
+                alphabet.decode(solution.guess())
+            )));
+        }
+        self.insert(solution);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::io::OpeningBook;
+    use crate::{
+        solve_seeded, Alphabet, CancellationToken, Constraints, GuessPolicy, Tiebreak,
+        WordDictionary, WordMatch,
+    };
+
+    #[test]
+    fn round_trip() {
+        let english = &Alphabet::english();
+        let answers = WordDictionary::new(b"lightmightnightsighttightfight");
+        let mut book = OpeningBook::default();
+        for opener in [*b"night", *b"fight"] {
+            let solution = solve_seeded(
+                &answers,
+                &answers,
+                opener,
+                &Constraints::default(),
+                10.into(),
+                6,
+                false,
+                Tiebreak::Word,
+                GuessPolicy::Full,
+                &CancellationToken::default(),
+            )
+            .unwrap();
+            book.insert(solution);
+        }
+        let mut file = Vec::new();
+        book.write(english, &mut file).unwrap();
+        let copy = OpeningBook::read(&file[..], english).unwrap();
+        assert_eq!(copy.len(), 2);
+        assert_eq!(copy.openers(), book.openers());
+        let wm = WordMatch::from(*b"night", *b"light");
+        let response = copy.response(*b"night", wm).unwrap();
+        assert_eq!(
+            response.guess(),
+            book.response(*b"night", wm).unwrap().guess()
+        );
+        assert!(copy.response(*b"sight", wm).is_none());
+        let mut again = Vec::new();
+        copy.write(english, &mut again).unwrap();
+        assert_eq!(file, again);
+        assert!(OpeningBook::read(&b"0 night 1\n"[..], english).is_err());
+        assert!(OpeningBook::read(&b"opener nigh\n"[..], english).is_err());
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+use std::fmt::Display;
+use std::str::FromStr;
+
+const MAX_LEVELS: usize = 8;
+
+// how many candidate guesses to search at each level of the tree, from the root down, since
+// wide search matters most near the root. levels past the schedule reuse its last entry
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Breadth {
+    levels: [usize; MAX_LEVELS],
+    len: usize,
+}
+
+impl Breadth {
+    // the breadth at this level
+    pub fn get(&self) -> usize {
+        self.levels[0]
+    }
+
+    pub fn levels(&self) -> &[usize] {
+        &self.levels[..self.len]
+    }
+
+    // the schedule for the level below
+    pub fn deeper(self) -> Self {
+        if self.len == 1 {
+            return self;
+        }
+        let mut levels = [0; MAX_LEVELS];
+        levels[..self.len - 1].copy_from_slice(&self.levels[1..self.len]);
+        Self {
+            levels,
+            len: self.len - 1,
+        }
+    }
+}
+
+impl From<usize> for Breadth {
+    fn from(breadth: usize) -> Self {
+        let mut levels = [0; MAX_LEVELS];
+        levels[0] = breadth;
+        Self { levels, len: 1 }
+    }
+}
+
+impl FromStr for Breadth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut levels = [0; MAX_LEVELS];
+        let mut len = 0;
+        for level in s.split(',') {
+            if len == MAX_LEVELS {
+                return Err(format!(
+                    "breadth has more than {} levels: {}",
+                    MAX_LEVELS, s
+                ));
+            }
+            levels[len] = level
+                .trim()
+                .parse()
+                .map_err(|_| format!("invalid breadth: {}", s))?;
+            len += 1;
+        }
+        Ok(Self { levels, len })
+    }
+}
+
+impl Display for Breadth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, breadth) in self.levels().iter().enumerate() {
+            if idx > 0 {
+                f.write_str(",")?;
+            }
+            write!(f, "{}", breadth)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Breadth;
+
+    #[test]
+    fn schedule() {
+        let breadth: Breadth = "50,20,8".parse().unwrap();
+        assert_eq!(breadth.get(), 50);
+        assert_eq!(breadth.deeper().get(), 20);
+        assert_eq!(breadth.deeper().deeper().deeper().get(), 8);
+        assert_eq!(breadth.to_string(), "50,20,8");
+        assert_eq!(Breadth::from(10), "10".parse().unwrap());
+        assert_eq!(Breadth::from(10).deeper(), Breadth::from(10));
+        assert!("50,x".parse::<Breadth>().is_err());
+        assert!("1,2,3,4,5,6,7,8,9".parse::<Breadth>().is_err());
+    }
+}
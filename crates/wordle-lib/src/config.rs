@@ -1,45 +1,828 @@
+use crate::analysis::LeaderboardMetric;
+use crate::io::{parse_memory_size, ColorScheme, TreeFormat};
+use crate::{
+    Alphabet, Breadth, Eviction, GuessPolicy, KeyboardLayout, MatchRules, StrategyKind, Tiebreak,
+};
+use std::ffi::OsString;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::{env, fs, iter};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Command {
+    Solve,
+    Tournament,
+    Share,
+    Pattern,
+    Stats,
+    GameStats,
+    Openers,
+    UpdateLists,
+    Tui,
+    Leaderboard,
+    Difficulty,
+    Traps,
+    Anti,
+    Analyze,
+    Diff,
+    Book,
+    Train,
+    Serve,
+}
+
 pub struct Config {
+    pub command: Command,
     pub hard: bool,
-    pub breadth: usize,
+    pub tiebreak: Tiebreak,
+    pub guess_policy: GuessPolicy,
+    pub breadth: Breadth,
     pub depth: usize,
     pub limit_guesses: bool,
+    pub alphabet: Alphabet,
+    pub rules: MatchRules,
+    pub answers_file: Option<String>,
+    pub guesses_file: Option<String>,
+    pub forbidden_file: Option<String>,
+    pub common_file: Option<String>,
+    pub common_count: usize,
+    pub url: Option<String>,
+    // the directory update-lists writes its lists into
+    pub out: Option<String>,
     pub first_guess: Option<[u8; 5]>,
+    // --guess and --answer as typed, encoded once the alphabet is known
+    guess_text: Option<String>,
+    answer_text: Option<String>,
+    // moves already made, like "salet:apaca,crony:acaaa", so only the rest of the game is solved
+    pub history: Option<String>,
+    // a file of histories, one per line, each solved in turn
+    pub positions_file: Option<String>,
+    pub answer: Option<[u8; 5]>,
+    pub green: Option<String>,
+    pub yellow: Option<String>,
+    pub gray: Option<String>,
+    pub pattern: Option<String>,
+    pub other_tree: Option<String>,
+    pub book: Option<String>,
+    pub include: Option<String>,
+    pub exclude: Option<String>,
     pub search: bool,
+    pub parallel_openers: bool,
+    pub stop_at_optimal: bool,
+    pub max_mean: Option<f64>,
+    pub max_total: Option<u32>,
+    pub resume: Option<String>,
+    pub trace: bool,
+    pub tree: Option<String>,
+    pub export: Option<String>,
+    pub simulate: bool,
+    pub seed: Option<u64>,
+    pub avoid_recent: usize,
+    pub recent_file: Option<String>,
+    pub transcript_file: Option<String>,
+    // where train mode appends each graded answer, so accuracy is tracked across sessions
+    pub train_file: Option<String>,
+    pub strategy: StrategyKind,
+    pub strategies: Vec<StrategyKind>,
+    pub noise: f64,
+    pub weights: Option<String>,
+    pub top_k: usize,
+    pub words: usize,
+    pub temperature: f64,
+    pub threads: Option<usize>,
+    // the share of each core the solvers may use, so long searches can run in the background
+    pub max_cpu_percent: Option<u32>,
+    pub nice: bool,
+    // the heap a search may use before it drops its caches, in bytes; setting it makes every
+    // allocation on every thread update one shared atomic counter, which slows parallel searches
+    pub max_memory: Option<usize>,
+    pub log_level: Option<String>,
+    pub metrics: bool,
+    pub transpositions: bool,
+    // the most entries the transposition table holds, and which it drops to stay within that
+    pub transposition_capacity: usize,
+    pub eviction: Eviction,
+    pub format: TreeFormat,
+    pub color_scheme: ColorScheme,
+    // picked from the alphabet unless given
+    pub keyboard: Option<KeyboardLayout>,
+    pub protocol: bool,
+    // the address serve listens on
+    pub listen: String,
+    // assist corrects one mistyped feedback when no answer fits, instead of giving up
+    pub tolerate_typos: bool,
+    // reports go to stdout as versioned json instead of text
+    pub json: bool,
+    // only the summary line, for scripts
+    pub quiet: bool,
+    pub metric: LeaderboardMetric,
+    pub hint: Option<u8>,
+    pub trap_size: usize,
+    pub merge_answers: bool,
+    pub dedupe_lists: bool,
+    pub sort_lists: bool,
+    pub manifest: Option<String>,
+    pub from_manifest: Option<String>,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            command: Command::Solve,
             hard: false,
-            breadth: 10,
+            tiebreak: Tiebreak::Word,
+            guess_policy: GuessPolicy::Full,
+            breadth: Breadth::from(10),
             depth: 6,
             limit_guesses: false,
+            alphabet: Alphabet::english(),
+            rules: MatchRules::Nyt,
+            answers_file: None,
+            guesses_file: None,
+            forbidden_file: None,
+            common_file: None,
+            common_count: usize::MAX,
+            url: None,
+            out: None,
             first_guess: None,
+            guess_text: None,
+            answer_text: None,
+            history: None,
+            positions_file: None,
+            answer: None,
+            green: None,
+            yellow: None,
+            gray: None,
+            pattern: None,
+            other_tree: None,
+            book: None,
+            include: None,
+            exclude: None,
             search: false,
+            parallel_openers: false,
+            stop_at_optimal: false,
+            max_mean: None,
+            max_total: None,
+            resume: None,
+            trace: false,
+            tree: None,
+            export: None,
+            simulate: false,
+            seed: None,
+            avoid_recent: 0,
+            recent_file: None,
+            transcript_file: None,
+            train_file: None,
+            strategy: StrategyKind::Human,
+            strategies: vec![
+                StrategyKind::Human,
+                StrategyKind::EntropyGreedy,
+                StrategyKind::MinimaxBucket,
+                StrategyKind::Random,
+            ],
+            noise: 0.0,
+            weights: None,
+            top_k: 10,
+            words: 3,
+            temperature: 1.0,
+            threads: None,
+            max_cpu_percent: None,
+            nice: false,
+            max_memory: None,
+            log_level: None,
+            metrics: false,
+            transpositions: false,
+            transposition_capacity: usize::MAX,
+            eviction: Eviction::Lru,
+            format: TreeFormat::Flat,
+            color_scheme: ColorScheme::Standard,
+            keyboard: None,
+            protocol: false,
+            listen: "127.0.0.1:8080".to_string(),
+            tolerate_typos: false,
+            json: false,
+            quiet: false,
+            metric: LeaderboardMetric::Entropy,
+            hint: None,
+            trap_size: 8,
+            merge_answers: false,
+            dedupe_lists: false,
+            sort_lists: false,
+            manifest: None,
+            from_manifest: None,
         }
     }
 }
 
+fn value(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("{} needs a value", flag))
+}
+
+fn parse<T: FromStr>(flag: &str, value: Option<String>) -> Result<T, String>
+where
+    T::Err: Display,
+{
+    let value = self::value(flag, value)?;
+    value
+        .parse()
+        .map_err(|err| format!("invalid {} {}: {}", flag, value, err))
+}
+
+fn is_switch(flag: &str) -> bool {
+    matches!(
+        flag,
+        "--hard"
+            | "--limit-guesses"
+            | "--search"
+            | "--parallel-openers"
+            | "--stop-at-optimal"
+            | "--trace"
+            | "--simulate"
+            | "--metrics"
+            | "--transpositions"
+            | "--nice"
+            | "--protocol"
+            | "--tolerate-typos"
+            | "--json"
+            | "--quiet"
+            | "--merge-answers"
+            | "--dedupe-lists"
+            | "--sort-lists"
+    )
+}
+
 impl Config {
-    pub fn from_args(mut args: std::env::Args) -> Self {
+    // defaults are overridden by the config file, then WORDLE_* environment variables, then
+    // command line arguments
+    pub fn from_args<I: Iterator<Item = String>>(args: I) -> Result<Self, String> {
+        let args: Vec<String> = args.collect();
         let mut this = Self::default();
+        let path = args
+            .iter()
+            .position(|arg| arg == "--config")
+            .and_then(|idx| args.get(idx + 1).cloned())
+            .or_else(|| env::var("WORDLE_CONFIG").ok());
+        if let Some(path) = path {
+            let text = fs::read_to_string(&path).map_err(|err| format!("{}: {}", path, err))?;
+            this.apply_toml(&text)
+                .map_err(|err| format!("{}: {}", path, err))?;
+        }
+        this.apply_env(env::vars_os())?;
+        this.apply_args(args.into_iter())?;
+        Ok(this)
+    }
+
+    // every WORDLE_* variable but WORDLE_CONFIG names a setting, as a config file key would
+    fn apply_env<I: Iterator<Item = (OsString, OsString)>>(
+        &mut self,
+        vars: I,
+    ) -> Result<(), String> {
+        for (key, value) in vars {
+            let Some(key) = key.to_str().and_then(|key| key.strip_prefix("WORDLE_")) else {
+                continue;
+            };
+            if key != "CONFIG" {
+                let value = value
+                    .to_str()
+                    .ok_or_else(|| format!("WORDLE_{} isn't valid unicode", key))?;
+                self.apply_setting(&key.to_lowercase(), value)
+                    .map_err(|err| format!("WORDLE_{}: {}", key, err))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.depth == 0 {
+            return Err("--max-guesses must be at least 1".to_string());
+        }
+        if self.breadth.levels().contains(&0) {
+            return Err("--breadth must be at least 1".to_string());
+        }
+        if self.json && self.command == Command::Tui {
+            return Err("--json doesn't apply to the tui".to_string());
+        }
+        if self
+            .max_cpu_percent
+            .is_some_and(|percent| percent == 0 || percent > 100)
+        {
+            return Err("--max-cpu-percent must be between 1 and 100".to_string());
+        }
+        if self.max_memory == Some(0) {
+            return Err("--max-memory must be more than 0".to_string());
+        }
+        if self.json && self.command == Command::Serve {
+            return Err("--json doesn't apply to serve, which always replies in json".to_string());
+        }
+        // so a download never lands on lists somebody is using without being asked to
+        if self.command == Command::UpdateLists && self.out.is_none() {
+            return Err("update-lists needs --out for the directory to write to".to_string());
+        }
+        if self.json && self.command == Command::Train {
+            return Err("--json doesn't apply to train".to_string());
+        }
+        if (self.history.is_some() || self.positions_file.is_some())
+            && (self.first_guess.is_some() || self.tree.is_some())
+        {
+            return Err(
+                "--history and --positions-file can't be combined with --guess or --tree"
+                    .to_string(),
+            );
+        }
+        // hard mode's constraints and the analysis commands score feedback the nyt way
+        if self.rules != MatchRules::Nyt {
+            if self.hard {
+                return Err(format!("--rules {} doesn't apply to --hard", self.rules));
+            }
+            if !matches!(
+                self.command,
+                Command::Solve | Command::Tournament | Command::Tui | Command::Serve
+            ) {
+                return Err(format!(
+                    "--rules {} only applies to solving, simulating, assisting, the tui, \
+                     tournaments and serve",
+                    self.rules
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // --nice keeps to half of each core unless --max-cpu-percent says otherwise
+    pub fn cpu_percent(&self) -> Option<u32> {
+        self.max_cpu_percent.or(self.nice.then_some(50))
+    }
+
+    // the flags that decide which tree gets solved, recorded in run manifests so that replaying
+    // them reproduces the same tree
+    pub fn solve_args(&self) -> Vec<String> {
+        let mut args = vec![
+            "--breadth".to_string(),
+            self.breadth.to_string(),
+            "--max-guesses".to_string(),
+            self.depth.to_string(),
+            "--alphabet".to_string(),
+            self.alphabet.to_string(),
+            "--rules".to_string(),
+            self.rules.to_string(),
+        ];
+        let switches = [
+            ("--hard", self.hard),
+            ("--limit-guesses", self.limit_guesses),
+            ("--merge-answers", self.merge_answers),
+            ("--dedupe-lists", self.dedupe_lists),
+            ("--sort-lists", self.sort_lists),
+        ];
+        for (flag, set) in switches {
+            if set {
+                args.push(flag.to_string());
+            }
+        }
+        let files = [
+            ("--answers-file", &self.answers_file),
+            ("--guesses-file", &self.guesses_file),
+            ("--forbidden-file", &self.forbidden_file),
+            ("--common-file", &self.common_file),
+        ];
+        for (flag, path) in files {
+            if let Some(path) = path {
+                args.push(flag.to_string());
+                args.push(path.clone());
+            }
+        }
+        if self.tiebreak != Tiebreak::Word {
+            args.push("--tiebreak".to_string());
+            args.push(self.tiebreak.to_string());
+        }
+        if self.guess_policy != GuessPolicy::Full {
+            args.push("--guess-policy".to_string());
+            args.push(self.guess_policy.to_string());
+        }
+        if self.common_file.is_some() {
+            args.push("--common-count".to_string());
+            args.push(self.common_count.to_string());
+        }
+        if let Some(guess) = self.first_guess {
+            args.push("--guess".to_string());
+            args.push(self.alphabet.decode(guess));
+        }
+        if let Some(history) = &self.history {
+            args.push("--history".to_string());
+            args.push(history.clone());
+        }
+        args
+    }
+
+    // accepts flat `key = value` lines where keys are flag names and values are integers,
+    // floats, booleans, or quoted strings
+    pub fn apply_toml(&mut self, text: &str) -> Result<(), String> {
+        for (idx, line) in text.lines().map(str::trim).enumerate() {
+            if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+                continue;
+            }
+            let invalid = |err: String| format!("line {}: {}", idx + 1, err);
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("expected key = value: {}", line)))?;
+            let value = value.trim();
+            let value = match value.strip_prefix('"') {
+                Some(quoted) => quoted
+                    .find('"')
+                    .map(|end| &quoted[..end])
+                    .ok_or_else(|| invalid(format!("unterminated string: {}", line)))?,
+                None => value.split('#').next().unwrap_or_default().trim(),
+            };
+            self.apply_setting(key.trim(), value).map_err(invalid)?;
+        }
+        Ok(())
+    }
+
+    // a setting is only ever a flag, so a value can't be taken for a subcommand
+    fn apply_setting(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let flag = format!("--{}", key.replace('_', "-"));
+        let known = if is_switch(&flag) {
+            match value {
+                "true" | "1" => self.apply_flag(&flag, &mut iter::empty())?,
+                "false" | "0" => true,
+                _ => return Err(format!("{} is true or false, not {}", key, value)),
+            }
+        } else {
+            self.apply_flag(&flag, &mut iter::once(value.to_string()))?
+        };
+        if !known {
+            return Err(format!("unknown setting: {}", key));
+        }
+        Ok(())
+    }
+
+    fn apply_args<I: Iterator<Item = String>>(&mut self, mut args: I) -> Result<(), String> {
         while let Some(arg) = args.next() {
-            if arg == "--hard" {
-                this.hard = true;
-            } else if arg == "--breadth" {
-                this.breadth = args.next().unwrap().parse().unwrap();
-            } else if arg == "--depth" {
-                this.depth = args.next().unwrap().parse().unwrap();
-            } else if arg == "--limit-guesses" {
-                this.limit_guesses = true;
-            } else if arg == "--guess" {
-                this.first_guess = args
-                    .next()
-                    .and_then(|guess| guess.as_bytes().try_into().ok());
-            } else if arg == "--search" {
-                this.search = true;
-            }
-        }
-        this
+            if arg == "tournament" {
+                self.command = Command::Tournament;
+            } else if arg == "share" {
+                self.command = Command::Share;
+            } else if arg == "pattern" {
+                self.command = Command::Pattern;
+                self.pattern = args.next();
+            } else if arg == "stats" {
+                self.command = Command::Stats;
+            } else if arg == "games" && self.command == Command::Stats {
+                self.command = Command::GameStats;
+            } else if arg == "openers" {
+                self.command = Command::Openers;
+            } else if arg == "update-lists" {
+                self.command = Command::UpdateLists;
+            } else if arg == "tui" {
+                self.command = Command::Tui;
+            } else if arg == "leaderboard" {
+                self.command = Command::Leaderboard;
+            } else if arg == "difficulty" {
+                self.command = Command::Difficulty;
+            } else if arg == "traps" {
+                self.command = Command::Traps;
+            } else if arg == "anti" {
+                self.command = Command::Anti;
+            } else if arg == "analyze" {
+                self.command = Command::Analyze;
+            } else if arg == "diff" {
+                self.command = Command::Diff;
+                self.other_tree = args.next();
+            } else if arg == "book" {
+                self.command = Command::Book;
+                self.book = args.next();
+            } else if arg == "train" {
+                self.command = Command::Train;
+            } else if arg == "serve" {
+                self.command = Command::Serve;
+            } else {
+                // anything else that isn't a flag, like the program name, is passed over
+                self.apply_flag(&arg, &mut args)?;
+            }
+        }
+        self.encode_words()
+    }
+
+    // the flag arg with any value it takes from args, or false when arg isn't a flag
+    fn apply_flag<I: Iterator<Item = String>>(
+        &mut self,
+        arg: &str,
+        args: &mut I,
+    ) -> Result<bool, String> {
+        if arg == "--hard" {
+            self.hard = true;
+        } else if arg == "--tiebreak" {
+            self.tiebreak = parse(arg, args.next())?;
+        } else if arg == "--guess-policy" {
+            self.guess_policy = parse(arg, args.next())?;
+        } else if arg == "--breadth" {
+            self.breadth = parse(arg, args.next())?;
+        } else if arg == "--depth" || arg == "--max-guesses" {
+            // the tree depth counts the first guess, so it is the most guesses any answer takes
+            self.depth = parse(arg, args.next())?;
+        } else if arg == "--limit-guesses" {
+            self.limit_guesses = true;
+        } else if arg == "--alphabet" {
+            self.alphabet = parse(arg, args.next())?;
+        } else if arg == "--rules" {
+            self.rules = parse(arg, args.next())?;
+        } else if arg == "--answers-file" {
+            self.answers_file = Some(value(arg, args.next())?);
+        } else if arg == "--guesses-file" {
+            self.guesses_file = Some(value(arg, args.next())?);
+        } else if arg == "--forbidden-file" {
+            self.forbidden_file = Some(value(arg, args.next())?);
+        } else if arg == "--common-file" {
+            self.common_file = Some(value(arg, args.next())?);
+        } else if arg == "--common-count" {
+            self.common_count = parse(arg, args.next())?;
+        } else if arg == "--url" {
+            self.url = Some(value(arg, args.next())?);
+        } else if arg == "--out" {
+            self.out = Some(value(arg, args.next())?);
+        } else if arg == "--guess" {
+            self.guess_text = Some(value(arg, args.next())?);
+        } else if arg == "--history" {
+            self.history = Some(value(arg, args.next())?);
+        } else if arg == "--positions-file" {
+            self.positions_file = Some(value(arg, args.next())?);
+        } else if arg == "--answer" {
+            self.answer_text = Some(value(arg, args.next())?);
+        } else if arg == "--green" {
+            self.green = Some(value(arg, args.next())?);
+        } else if arg == "--yellow" {
+            self.yellow = Some(value(arg, args.next())?);
+        } else if arg == "--gray" {
+            self.gray = Some(value(arg, args.next())?);
+        } else if arg == "--include" {
+            self.include = Some(value(arg, args.next())?);
+        } else if arg == "--exclude" {
+            self.exclude = Some(value(arg, args.next())?);
+        } else if arg == "--search" {
+            self.search = true;
+        } else if arg == "--parallel-openers" {
+            self.parallel_openers = true;
+        } else if arg == "--stop-at-optimal" {
+            self.stop_at_optimal = true;
+        } else if arg == "--max-mean" {
+            self.max_mean = Some(parse(arg, args.next())?);
+        } else if arg == "--max-total" {
+            self.max_total = Some(parse(arg, args.next())?);
+        } else if arg == "--resume" {
+            self.resume = Some(value(arg, args.next())?);
+        } else if arg == "--trace" {
+            self.trace = true;
+        } else if arg == "--tree" {
+            self.tree = Some(value(arg, args.next())?);
+        } else if arg == "--export" {
+            self.export = Some(value(arg, args.next())?);
+        } else if arg == "--simulate" {
+            self.simulate = true;
+        } else if arg == "--seed" {
+            self.seed = Some(parse(arg, args.next())?);
+        } else if arg == "--avoid-recent" {
+            self.avoid_recent = parse(arg, args.next())?;
+        } else if arg == "--recent-file" {
+            self.recent_file = Some(value(arg, args.next())?);
+        } else if arg == "--transcript-file" {
+            self.transcript_file = Some(value(arg, args.next())?);
+        } else if arg == "--train-file" {
+            self.train_file = Some(value(arg, args.next())?);
+        } else if arg == "--strategy" {
+            self.strategy = parse(arg, args.next())?;
+        } else if arg == "--strategies" {
+            self.strategies = value(arg, args.next())?
+                .split(',')
+                .map(|kind| parse(arg, Some(kind.to_string())))
+                .collect::<Result<_, _>>()?;
+        } else if arg == "--noise" {
+            self.noise = parse(arg, args.next())?;
+        } else if arg == "--weights" {
+            self.weights = Some(value(arg, args.next())?);
+        } else if arg == "--words" {
+            self.words = parse(arg, args.next())?;
+        } else if arg == "--top-k" {
+            self.top_k = parse(arg, args.next())?;
+        } else if arg == "--temperature" {
+            self.temperature = parse(arg, args.next())?;
+        } else if arg == "--threads" {
+            self.threads = Some(parse(arg, args.next())?);
+        } else if arg == "--max-cpu-percent" {
+            self.max_cpu_percent = Some(parse(arg, args.next())?);
+        } else if arg == "--nice" {
+            self.nice = true;
+        } else if arg == "--max-memory" {
+            self.max_memory = Some(parse_memory_size(&value(arg, args.next())?)?);
+        } else if arg == "--log-level" {
+            self.log_level = Some(value(arg, args.next())?);
+        } else if arg == "--metrics" {
+            self.metrics = true;
+        } else if arg == "--transpositions" {
+            self.transpositions = true;
+        } else if arg == "--transposition-capacity" {
+            self.transposition_capacity = parse(arg, args.next())?;
+        } else if arg == "--eviction" {
+            self.eviction = parse(arg, args.next())?;
+        } else if arg == "--format" {
+            self.format = parse(arg, args.next())?;
+        } else if arg == "--color-scheme" {
+            self.color_scheme = parse(arg, args.next())?;
+        } else if arg == "--keyboard" {
+            self.keyboard = Some(parse(arg, args.next())?);
+        } else if arg == "--protocol" {
+            self.protocol = true;
+        } else if arg == "--listen" {
+            self.listen = value(arg, args.next())?;
+        } else if arg == "--tolerate-typos" {
+            self.tolerate_typos = true;
+        } else if arg == "--json" {
+            self.json = true;
+        } else if arg == "--quiet" {
+            self.quiet = true;
+        } else if arg == "--metric" {
+            self.metric = parse(arg, args.next())?;
+        } else if arg == "--hint" {
+            self.hint = Some(parse(arg, args.next())?);
+        } else if arg == "--trap-size" {
+            self.trap_size = parse(arg, args.next())?;
+        } else if arg == "--merge-answers" {
+            self.merge_answers = true;
+        } else if arg == "--dedupe-lists" {
+            self.dedupe_lists = true;
+        } else if arg == "--sort-lists" {
+            self.sort_lists = true;
+        } else if arg == "--manifest" {
+            self.manifest = Some(value(arg, args.next())?);
+        } else if arg == "--from-manifest" {
+            self.from_manifest = Some(value(arg, args.next())?);
+        } else if arg == "--config" {
+            args.next();
+        } else {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    // a later --alphabet changes how an earlier --guess or --answer reads
+    fn encode_words(&mut self) -> Result<(), String> {
+        self.first_guess = self
+            .guess_text
+            .as_deref()
+            .map(|word| self.alphabet.encode(word))
+            .transpose()
+            .map_err(|err| format!("invalid --guess: {}", err))?;
+        self.answer = self
+            .answer_text
+            .as_deref()
+            .map(|word| self.alphabet.encode(word))
+            .transpose()
+            .map_err(|err| format!("invalid --answer: {}", err))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cli::{Command, Config};
+    use crate::{Breadth, GuessPolicy, MatchRules, Tiebreak};
+    use std::ffi::OsString;
+
+    #[test]
+    fn toml() {
+        let mut conf = Config::default();
+        conf.apply_toml(
+            r#"
+            # sweep defaults
+            breadth = 20
+            depth = 5 # comment
+            hard = true
+            limit_guesses = false
+            answers_file = "lists/answers.txt"
+            threads = 4
+            json = 1
+            "#,
+        )
+        .unwrap();
+        assert_eq!(conf.breadth, Breadth::from(20));
+        assert_eq!(conf.depth, 5);
+        assert!(conf.hard);
+        assert!(!conf.limit_guesses);
+        assert_eq!(conf.answers_file.as_deref(), Some("lists/answers.txt"));
+        assert_eq!(conf.threads, Some(4));
+        assert!(conf.json);
+    }
+
+    #[test]
+    fn update_lists() {
+        let mut conf = Config::default();
+        conf.apply_args(["update-lists"].into_iter().map(String::from))
+            .unwrap();
+        assert!(conf.validate().is_err());
+        conf.apply_args(["--out", "lists"].into_iter().map(String::from))
+            .unwrap();
+        assert_eq!(conf.out.as_deref(), Some("lists"));
+        assert!(conf.validate().is_ok());
+    }
+
+    #[test]
+    fn solve_args() {
+        let mut conf = Config::default();
+        conf.apply_args(
+            [
+                "--hard",
+                "--breadth",
+                "20",
+                "--alphabet",
+                "spanish",
+                "--rules",
+                "every",
+                "--common-file",
+                "freq.txt",
+                "--guess",
+                "crane",
+                "--tiebreak",
+                "answers",
+                "--guess-policy",
+                "answers-first",
+                "--history",
+                "salet:apaca",
+            ]
+            .into_iter()
+            .map(String::from),
+        )
+        .unwrap();
+        let mut replayed = Config::default();
+        replayed.apply_args(conf.solve_args().into_iter()).unwrap();
+        assert_eq!(replayed.solve_args(), conf.solve_args());
+        assert!(replayed.hard);
+        assert_eq!(replayed.breadth, Breadth::from(20));
+        assert_eq!(replayed.alphabet, conf.alphabet);
+        assert_eq!(replayed.rules, MatchRules::EveryOccurrence);
+        assert_eq!(replayed.common_file.as_deref(), Some("freq.txt"));
+        assert_eq!(replayed.first_guess, conf.first_guess);
+        assert_eq!(replayed.tiebreak, Tiebreak::PreferAnswers);
+        assert_eq!(replayed.guess_policy, GuessPolicy::AnswersFirst);
+        assert_eq!(replayed.history.as_deref(), Some("salet:apaca"));
+    }
+
+    #[test]
+    fn cpu_percent() {
+        let mut conf = Config::default();
+        assert_eq!(conf.cpu_percent(), None);
+        conf.apply_toml("nice = true").unwrap();
+        assert_eq!(conf.cpu_percent(), Some(50));
+        conf.apply_args(["--max-cpu-percent", "25"].into_iter().map(String::from))
+            .unwrap();
+        assert_eq!(conf.cpu_percent(), Some(25));
+        assert!(conf.validate().is_ok());
+        conf.max_cpu_percent = Some(0);
+        assert!(conf.validate().is_err());
+    }
+
+    #[test]
+    fn invalid() {
+        let args = |args: &[&str]| {
+            let mut conf = Config::default();
+            conf.apply_args(args.iter().map(|arg| arg.to_string()))
+                .map(|()| conf)
+        };
+        assert!(args(&["--depth", "x"]).is_err());
+        assert!(args(&["--guess", "toolong"]).is_err());
+        assert!(args(&["--max-memory"]).is_err());
+        assert!(args(&["--eviction", "never"]).is_err());
+        assert!(args(&["--strategies", "entropy,nope"]).is_err());
+        let conf = args(&["--guess", "ñandu", "--alphabet", "spanish"]).unwrap();
+        assert_eq!(
+            conf.first_guess.map(|guess| conf.alphabet.decode(guess)),
+            Some("ñandu".to_string())
+        );
+        let mut conf = Config::default();
+        assert_eq!(
+            conf.apply_toml("depth = 5\nbreadth"),
+            Err("line 2: expected key = value: breadth".to_string())
+        );
+        assert!(conf.apply_toml("answers_file = \"lists").is_err());
+    }
+
+    #[test]
+    fn unknown_settings() {
+        let mut conf = Config::default();
+        assert_eq!(
+            conf.apply_toml("depth = 5\nmax_gueses = 4"),
+            Err("line 2: unknown setting: max_gueses".to_string())
+        );
+        let vars = [(OsString::from("WORDLE_X"), OsString::from("serve"))];
+        assert_eq!(
+            conf.apply_env(vars.into_iter()),
+            Err("WORDLE_X: unknown setting: x".to_string())
+        );
+        // a value is never read as a subcommand
+        conf.apply_toml("answers_file = \"serve\"").unwrap();
+        assert_eq!(conf.command, Command::Solve);
+        assert!(conf.apply_toml("hard = yes").is_err());
+        let vars = [
+            (OsString::from("HOME"), OsString::from("serve")),
+            (OsString::from("WORDLE_HARD"), OsString::from("true")),
+        ];
+        conf.apply_env(vars.into_iter()).unwrap();
+        assert!(conf.hard);
     }
 }
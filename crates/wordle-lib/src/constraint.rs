@@ -0,0 +1,242 @@
+use crate::{CharMatch, WordDictionary, WordMatch, MAX_LETTERS};
+use std::fmt;
+use std::fmt::Display;
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Constraints {
+    greens: [Option<u8>; 5],
+    excluded: [u32; 5],
+    min_counts: [u8; MAX_LETTERS],
+    max_counts: [u8; MAX_LETTERS],
+}
+
+impl Default for Constraints {
+    fn default() -> Self {
+        Self {
+            greens: [None; 5],
+            excluded: [0; 5],
+            min_counts: [0; MAX_LETTERS],
+            max_counts: [5; MAX_LETTERS],
+        }
+    }
+}
+
+fn letter(c: u8) -> Result<u8, String> {
+    if c.is_ascii_lowercase() {
+        Ok(c - b'a')
+    } else {
+        Err(format!("invalid letter: {}", c as char))
+    }
+}
+
+// ways a guess can ignore what earlier feedback revealed
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Violation {
+    Gray(u8),
+    Misplaced(u8, usize),
+    MissingGreen(u8, usize),
+    MissingLetter(u8),
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Violation::Gray(c) => write!(f, "reuses gray {}", c as char),
+            Violation::Misplaced(c, pos) => {
+                write!(f, "tries {} in position {} again", c as char, pos + 1)
+            }
+            Violation::MissingGreen(c, pos) => {
+                write!(f, "drops green {} in position {}", c as char, pos + 1)
+            }
+            Violation::MissingLetter(c) => write!(f, "leaves out {}", c as char),
+        }
+    }
+}
+
+impl Constraints {
+    // everything the feedback so far reveals about the answer
+    pub fn from_history(history: &[([u8; 5], WordMatch)]) -> Result<Self, String> {
+        let mut this = Self::default();
+        for (guess, wm) in history {
+            for &c in guess.iter() {
+                letter(c)?;
+            }
+            this.apply(*guess, *wm);
+        }
+        Ok(this)
+    }
+
+    // narrows the state by one more guess, whose letters must already be encoded. a word meets
+    // the result exactly when it would have produced this feedback, so filtering a list that
+    // meets the earlier state by the new one matches partitioning it on the guess
+    pub fn apply(&mut self, guess: [u8; 5], wm: WordMatch) {
+        let mut found = [0u8; MAX_LETTERS];
+        for (pos, &c) in guess.iter().enumerate() {
+            let c = c - b'a';
+            match wm.get(pos) {
+                CharMatch::Correct => self.greens[pos] = Some(c),
+                _ => self.excluded[pos] |= 1 << c,
+            }
+            if wm.get(pos) != CharMatch::Absent {
+                found[c as usize] += 1;
+            }
+        }
+        for (pos, &c) in guess.iter().enumerate() {
+            let c = (c - b'a') as usize;
+            if wm.get(pos) == CharMatch::Absent {
+                self.max_counts[c] = self.max_counts[c].min(found[c]);
+            }
+            self.min_counts[c] = self.min_counts[c].max(found[c]);
+        }
+    }
+
+    pub fn violations(&self, word: [u8; 5]) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        let mut counts = [0u8; MAX_LETTERS];
+        for &c in word.iter() {
+            counts[c.wrapping_sub(b'a') as usize % MAX_LETTERS] += 1;
+        }
+        for (pos, &c) in word.iter().enumerate() {
+            let idx = c.wrapping_sub(b'a') % MAX_LETTERS as u8;
+            if let Some(green) = self.greens[pos].filter(|&green| green != idx) {
+                violations.push(Violation::MissingGreen(b'a' + green, pos));
+            } else if self.excluded[pos] & (1 << idx) != 0
+                && counts[idx as usize] <= self.max_counts[idx as usize]
+            {
+                violations.push(Violation::Misplaced(c, pos));
+            }
+        }
+        for (c, &count) in counts.iter().enumerate() {
+            let letter = b'a' + c as u8;
+            if count > self.max_counts[c] {
+                violations.push(Violation::Gray(letter));
+            } else if count < self.min_counts[c]
+                && !(0..5).any(|pos| violations.contains(&Violation::MissingGreen(letter, pos)))
+            {
+                violations.push(Violation::MissingLetter(letter));
+            }
+        }
+        violations
+    }
+
+    pub fn from_flags(
+        green: Option<&str>,
+        yellow: Option<&str>,
+        gray: Option<&str>,
+    ) -> Result<Self, String> {
+        let mut this = Self::default();
+        let mut required = [0u8; MAX_LETTERS];
+        if let Some(green) = green {
+            if green.len() != 5 {
+                return Err(format!("green pattern must have 5 letters: {}", green));
+            }
+            for (pos, c) in green.bytes().enumerate() {
+                if !matches!(c, b'_' | b'.' | b'?') {
+                    let c = letter(c)?;
+                    this.greens[pos] = Some(c);
+                    required[c as usize] += 1;
+                }
+            }
+        }
+        for entry in yellow.iter().flat_map(|yellow| yellow.split(',')) {
+            let (c, pos) = match entry.split_once('@') {
+                Some((c, pos)) => (c, Some(pos)),
+                None => (entry, None),
+            };
+            let c = match c.as_bytes() {
+                [c] => letter(*c)?,
+                _ => return Err(format!("invalid yellow: {}", entry)),
+            };
+            if let Some(pos) = pos {
+                let pos: usize = pos
+                    .parse()
+                    .ok()
+                    .filter(|pos| (1..=5).contains(pos))
+                    .ok_or_else(|| format!("invalid yellow position: {}", entry))?;
+                this.excluded[pos - 1] |= 1 << c;
+            }
+            required[c as usize] += 1;
+        }
+        for c in gray.iter().flat_map(|gray| gray.bytes()) {
+            let c = letter(c)?;
+            this.max_counts[c as usize] = required[c as usize];
+        }
+        this.min_counts = required;
+        Ok(this)
+    }
+
+    pub fn matches(&self, word: [u8; 5]) -> bool {
+        let mut counts = [0u8; MAX_LETTERS];
+        for (pos, &c) in word.iter().enumerate() {
+            let c = c.wrapping_sub(b'a');
+            if c as usize >= MAX_LETTERS || self.excluded[pos] & (1 << c) != 0 {
+                return false;
+            }
+            if self.greens[pos].is_some_and(|green| green != c) {
+                return false;
+            }
+            counts[c as usize] += 1;
+        }
+        (0..MAX_LETTERS).all(|c| self.min_counts[c] <= counts[c] && counts[c] <= self.max_counts[c])
+    }
+
+    pub fn filter(&self, dict: &WordDictionary) -> WordDictionary {
+        dict.filter(|word| self.matches(word))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Constraints, Violation, WordMatch};
+    use proptest::array::uniform5;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn apply_matches_feedback(
+            guess in uniform5(b'a'..=b'c'),
+            answer in uniform5(b'a'..=b'c'),
+            word in uniform5(b'a'..=b'c'),
+        ) {
+            let wm = WordMatch::from(guess, answer);
+            let mut constraints = Constraints::default();
+            constraints.apply(guess, wm);
+            prop_assert_eq!(constraints.matches(word), WordMatch::from(guess, word) == wm);
+        }
+    }
+
+    #[test]
+    fn flags() {
+        let constraints =
+            Constraints::from_flags(Some("s___p"), Some("a@2,r"), Some("tln")).unwrap();
+        assert!(constraints.matches(*b"scarp"));
+        assert!(!constraints.matches(*b"saurp"));
+        assert!(!constraints.matches(*b"stamp"));
+        assert!(!constraints.matches(*b"scamp"));
+    }
+
+    #[test]
+    fn history() {
+        let history = [(*b"crane", WordMatch::from(*b"crane", *b"scarp"))];
+        let constraints = Constraints::from_history(&history).unwrap();
+        assert!(constraints.matches(*b"scarp"));
+        assert!(constraints.violations(*b"scarp").is_empty());
+        assert_eq!(
+            constraints.violations(*b"crane"),
+            [
+                Violation::Misplaced(b'c', 0),
+                Violation::Misplaced(b'r', 1),
+                Violation::Gray(b'e'),
+                Violation::Gray(b'n'),
+            ]
+        );
+        assert_eq!(
+            constraints.violations(*b"spoil"),
+            [
+                Violation::MissingGreen(b'a', 2),
+                Violation::MissingLetter(b'c'),
+                Violation::MissingLetter(b'r'),
+            ]
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use crate::solve::Node;
+use crate::{Solution, SolutionNode, WordDictionary, WordMatch, MAX_LETTERS};
+use std::collections::HashMap;
+use std::io;
+use std::io::{BufRead, Write};
+
+type NodeKey = ([u8; 5], u16, Vec<(WordMatch, usize)>);
+
+pub(crate) fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+impl SolutionNode<'_> {
+    fn write_node<W: Write>(
+        &self,
+        w: &mut W,
+        ids: &mut HashMap<NodeKey, usize>,
+    ) -> io::Result<usize> {
+        let mut children = Vec::new();
+        for (wm, sub) in self.children() {
+            children.push((wm, sub.write_node(w, ids)?));
+        }
+        let key = (self.guess(), self.size(), children);
+        if let Some(&id) = ids.get(&key) {
+            return Ok(id);
+        }
+        let id = ids.len();
+        write!(
+            w,
+            "{} {} {}",
+            id,
+            std::str::from_utf8(&key.0).unwrap(),
+            key.1
+        )?;
+        for (wm, child) in key.2.iter() {
+            write!(w, " {}:{}", wm, child)?;
+        }
+        writeln!(w)?;
+        ids.insert(key, id);
+        Ok(id)
+    }
+}
+
+impl Solution {
+    pub fn write_dag<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        let mut ids = HashMap::new();
+        self.root().write_node(w, &mut ids)?;
+        Ok(())
+    }
+
+    pub fn read_dag<R: BufRead>(r: R) -> io::Result<Solution> {
+        let mut solution = Solution {
+            words: WordDictionary::with_capacity(0),
+            nodes: Vec::new(),
+        };
+        // the root comes last, but belongs first
+        solution.nodes.push(Node {
+            guess: 0,
+            size: 0,
+            wm: WordMatch::CORRECT,
+            children: 0,
+            first_child: 0,
+        });
+        let mut guesses = HashMap::new();
+        // each node read so far, as its parent's entry for it points at its children, so a
+        // subtree several parents share is stored once
+        let mut entries: Vec<Node> = Vec::new();
+        for line in r.lines() {
+            let line = line?;
+            let mut fields = line.split_whitespace();
+            let id: usize = fields
+                .next()
+                .and_then(|id| id.parse().ok())
+                .ok_or_else(|| invalid(format!("bad node id: {}", line)))?;
+            if id != entries.len() {
+                return Err(invalid(format!("node out of order: {}", line)));
+            }
+            let guess = fields
+                .next()
+                .and_then(|guess| guess.as_bytes().try_into().ok())
+                .filter(|guess: &[u8; 5]| {
+                    guess
+                        .iter()
+                        .all(|c| (b'a'..b'a' + MAX_LETTERS as u8).contains(c))
+                })
+                .ok_or_else(|| invalid(format!("bad guess: {}", line)))?;
+            // the packed tree indexes guesses with a u16
+            let next = guesses.len();
+            let guess = *guesses.entry(guess).or_insert_with(|| {
+                solution.words.push(guess);
+                next
+            });
+            let guess = u16::try_from(guess)
+                .map_err(|_| invalid("too many distinct guesses".to_string()))?;
+            let size = fields
+                .next()
+                .and_then(|size| size.parse().ok())
+                .ok_or_else(|| invalid(format!("bad size: {}", line)))?;
+            let first_child = solution.nodes.len() as u32;
+            let mut children = 0;
+            for child in fields {
+                let (wm, child): (WordMatch, usize) = child
+                    .split_once(':')
+                    .and_then(|(wm, child)| Some((wm.parse().ok()?, child.parse().ok()?)))
+                    .ok_or_else(|| invalid(format!("bad child: {}", line)))?;
+                let entry = entries
+                    .get(child)
+                    .ok_or_else(|| invalid(format!("unknown child: {}", line)))?;
+                solution.nodes.push(Node { wm, ..*entry });
+                children += 1;
+            }
+            if children > 243 {
+                return Err(invalid(format!("too many children: {}", line)));
+            }
+            entries.push(Node {
+                guess,
+                size,
+                wm: WordMatch::CORRECT,
+                children: children as u8,
+                first_child,
+            });
+        }
+        solution.nodes[0] = entries
+            .pop()
+            .ok_or_else(|| invalid("empty tree".to_string()))?;
+        Ok(solution)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{solve, CancellationToken, Solution, WordDictionary};
+
+    #[test]
+    fn round_trip() {
+        let answers = WordDictionary::new(b"lightmightnightsighttightfight");
+        let solution = solve(
+            &answers,
+            &answers,
+            10.into(),
+            6,
+            false,
+            &CancellationToken::default(),
+        )
+        .unwrap();
+        let mut dag = Vec::new();
+        solution.write_dag(&mut dag).unwrap();
+        let mut copy = Vec::new();
+        Solution::read_dag(&dag[..])
+            .unwrap()
+            .write_dag(&mut copy)
+            .unwrap();
+        assert_eq!(dag, copy);
+    }
+
+    #[test]
+    fn shared() {
+        let dag = b"0 sight 1\n1 light 1\n2 night 1\n3 light 3 acccc:0 ccccc:1\n\
+            4 night 9 aaaaa:3 ccccc:2 pcccc:3\n";
+        let solution = Solution::read_dag(&dag[..]).unwrap();
+        assert_eq!(solution.answer_count(), 5);
+        assert_eq!(
+            solution
+                .lookup(&["pcccc".parse().unwrap()])
+                .unwrap()
+                .guess(),
+            *b"light"
+        );
+        // the root, then one entry per child, with both parents of light pointing at one copy
+        // of its children
+        assert_eq!(solution.nodes.len(), 6);
+    }
+
+    #[cfg(feature = "embedded-tree")]
+    #[test]
+    fn default_tree() {
+        use crate::{Dictionary, DEFAULT_TREE, LEGAL_ANSWERS};
+
+        let answers = WordDictionary::new(LEGAL_ANSWERS);
+        let solution = Solution::read_dag(DEFAULT_TREE).unwrap();
+        assert_eq!(solution.answer_count(), answers.len());
+        assert_eq!(solution.failures(&answers, 6), 0);
+    }
+}
@@ -1,19 +1,80 @@
-use crate::WordMatch;
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use crate::{AnswerSet, CanonicalKey, MatchRules, WordMatch};
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use rayon::slice::ParallelSlice;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
 use std::cmp;
-use std::collections::HashMap;
-use std::ops::ControlFlow;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::ops::{ControlFlow, Range};
+use std::sync::Arc;
 
 pub trait Dictionary: Sync {
-    type Word: Copy;
+    type Word: Copy + Debug;
 
     fn len(&self) -> usize;
 
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn word_match(&self, guess: Self::Word, word: Self::Word) -> WordMatch;
+
+    // the words as answer indices, for dictionaries whose words are answer ids
+    fn answer_set(&self) -> Option<AnswerSet> {
+        None
+    }
+
+    // a key shared with sets that only differ by renaming letters, when the words are known
+    fn canonical_key(&self) -> Option<CanonicalKey> {
+        None
+    }
+
     fn partition(&self, guess: Self::Word) -> HashMap<WordMatch, Self>
     where
         Self: Sized;
 
+    fn summarized_partition(
+        &self,
+        guess: Self::Word,
+    ) -> (HashMap<WordMatch, Self>, PartitionSummary)
+    where
+        Self: Sized,
+    {
+        let partition = self.partition(guess);
+        let summary = PartitionSummary::new(&partition);
+        (partition, summary)
+    }
+
+    // the bucket sizes alone, counted without building a dictionary for each bucket
+    fn partition_summary(&self, guess: Self::Word) -> PartitionSummary {
+        let mut counts = [0u32; WordMatch::COUNT];
+        self.for_each(|word| counts[self.word_match(guess, word).index() as usize] += 1);
+        let sizes: Vec<_> = WordMatch::all()
+            .zip(counts)
+            .filter(|(_, size)| *size > 0)
+            .collect();
+        PartitionSummary {
+            has_correct: counts[WordMatch::CORRECT.index() as usize] > 0,
+            largest: sizes.iter().map(|(_, size)| *size).max().unwrap_or(0),
+            sizes,
+        }
+    }
+
+    // buckets in pattern order, streamed from one sorted buffer instead of building a dictionary
+    // for each, for callers that walk the buckets once
+    fn partition_iter(&self, guess: Self::Word) -> PartitionIter<Self::Word> {
+        let mut matches = Vec::with_capacity(self.len());
+        self.for_each(|word| matches.push((self.word_match(guess, word), word)));
+        // stable, so each bucket keeps dictionary order
+        matches.sort_by_key(|(wm, _)| wm.0);
+        PartitionIter {
+            matches: matches.into(),
+            start: 0,
+        }
+    }
+
     fn for_each<F>(&self, f: F)
     where
         F: FnMut(Self::Word);
@@ -27,33 +88,269 @@ pub trait Dictionary: Sync {
         F: Fn(Self::Word) -> Option<u32> + Sync + Send;
 }
 
-pub struct WordDictionary(Vec<u8>);
+// below this many words rayon's scheduling costs more than the searches it spreads out
+const PARALLEL_CUTOFF: usize = 16;
+
+fn seq_process<D, F>(dict: &D, weight: u32, f: F) -> Option<u32>
+where
+    D: Dictionary,
+    F: Fn(D::Word) -> Option<u32>,
+{
+    let mut best: Option<u32> = None;
+    dict.for_each(|word| {
+        if let Some(sub_weight) = f(word) {
+            best = Some(best.map_or(sub_weight, |best| best.min(sub_weight)));
+        }
+    });
+    best.map(|sub_weight| weight + sub_weight)
+}
+
+pub struct PartitionIter<W> {
+    matches: Arc<[(WordMatch, W)]>,
+    start: usize,
+}
+
+impl<W: Copy> Iterator for PartitionIter<W> {
+    type Item = (WordMatch, Bucket<W>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let wm = self.matches.get(self.start)?.0;
+        let len = self.matches[self.start..]
+            .iter()
+            .take_while(|(other, _)| *other == wm)
+            .count();
+        let bucket = Bucket {
+            matches: self.matches.clone(),
+            range: self.start..self.start + len,
+        };
+        self.start += len;
+        Some((wm, bucket))
+    }
+}
+
+pub struct Bucket<W> {
+    matches: Arc<[(WordMatch, W)]>,
+    range: Range<usize>,
+}
+
+impl<W: Copy> Iterator for Bucket<W> {
+    type Item = W;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(|idx| self.matches[idx].1)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<W: Copy> ExactSizeIterator for Bucket<W> {}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PartitionSummary {
+    // bucket sizes ordered by pattern
+    pub sizes: Vec<(WordMatch, u32)>,
+    pub has_correct: bool,
+    pub largest: u32,
+}
+
+impl PartitionSummary {
+    pub fn new<D: Dictionary>(partition: &HashMap<WordMatch, D>) -> Self {
+        let mut sizes: Vec<_> = partition
+            .iter()
+            .map(|(wm, dict)| (*wm, dict.len() as u32))
+            .collect();
+        sizes.sort_unstable_by_key(|(wm, _)| wm.0);
+        Self {
+            has_correct: partition.contains_key(&WordMatch::CORRECT),
+            largest: sizes.iter().map(|(_, size)| *size).max().unwrap_or(0),
+            sizes,
+        }
+    }
+
+    pub fn buckets(&self) -> usize {
+        self.sizes.len()
+    }
+
+    pub fn answers(&self) -> u32 {
+        self.sizes.iter().map(|(_, size)| size).sum()
+    }
+
+    // a rough guess at how long solving under this partition takes, used to start the slowest
+    // parallel jobs first; search cost grows faster than linearly in a bucket's size
+    pub fn difficulty(&self) -> u64 {
+        self.sizes
+            .iter()
+            .map(|(_, size)| bucket_difficulty(*size as usize))
+            .sum()
+    }
+}
+
+pub(crate) fn bucket_difficulty(size: usize) -> u64 {
+    (size as u64).pow(2)
+}
+
+#[derive(Clone)]
+pub struct WordDictionary {
+    words: Vec<u8>,
+    // whether the words are strictly increasing, so lookups can binary search
+    sorted: bool,
+    // how feedback is scored when the words are the answers
+    rules: MatchRules,
+}
 
 impl WordDictionary {
     pub fn new(words: &[u8]) -> Self {
         assert_eq!(words.len() % 5, 0);
-        Self(words.to_vec())
+        let mut dict = Self::with_capacity(words.len() / 5);
+        for word in words.chunks_exact(5) {
+            dict.push(word.try_into().unwrap());
+        }
+        dict
+    }
+
+    // the same words in alphabetical order
+    pub fn sorted(&self) -> Self {
+        let mut words: Vec<_> = self.words().collect();
+        words.sort_unstable();
+        let mut dict = Self::with_capacity(words.len()).with_rules(self.rules);
+        for word in words {
+            dict.push(word);
+        }
+        dict
+    }
+
+    // the same words, scored by rules instead
+    pub fn with_rules(mut self, rules: MatchRules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub fn rules(&self) -> MatchRules {
+        self.rules
+    }
+
+    // hex digest of the words one per line, matching the digest of a plain list file
+    pub fn sha256(&self) -> String {
+        let mut hasher = Sha256::new();
+        self.for_each(|word| {
+            hasher.update(word);
+            hasher.update(b"\n");
+        });
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    pub fn is_sorted(&self) -> bool {
+        self.sorted
     }
 
     pub fn index_of(&self, word: [u8; 5]) -> Option<usize> {
-        for i in 0..self.len() {
-            if word == self.word(i) {
-                return Some(i);
+        if !self.sorted {
+            return self.words().position(|w| w == word);
+        }
+        let (mut lo, mut hi) = (0, self.len());
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            match self.word(mid).cmp(&word) {
+                Ordering::Less => lo = mid + 1,
+                Ordering::Greater => hi = mid,
+                Ordering::Equal => return Some(mid),
             }
         }
         None
     }
 
+    pub fn contains(&self, word: [u8; 5]) -> bool {
+        self.index_of(word).is_some()
+    }
+
     pub fn with_capacity(cap: usize) -> Self {
-        Self(Vec::with_capacity(5 * cap))
+        Self {
+            words: Vec::with_capacity(5 * cap),
+            sorted: true,
+            rules: MatchRules::Nyt,
+        }
+    }
+
+    pub fn get(&self, idx: usize) -> Option<[u8; 5]> {
+        (idx < self.len()).then(|| self.word(idx))
+    }
+
+    #[cfg(not(feature = "unchecked"))]
+    pub fn word(&self, idx: usize) -> [u8; 5] {
+        self.words[5 * idx..5 * idx + 5].try_into().unwrap()
     }
 
+    #[cfg(feature = "unchecked")]
     pub fn word(&self, idx: usize) -> [u8; 5] {
-        unsafe { self.0[5 * idx..5 * idx + 5].try_into().unwrap_unchecked() }
+        debug_assert!(idx < self.len(), "word index out of bounds: {}", idx);
+        unsafe { *(self.words.as_ptr().add(5 * idx) as *const [u8; 5]) }
     }
 
+    // appending keeps the dictionary sorted only while words arrive in increasing order
     pub fn push(&mut self, word: [u8; 5]) {
-        self.0.extend_from_slice(&word);
+        if let Some(last) = self.words.last_chunk::<5>() {
+            self.sorted &= *last < word;
+        }
+        self.words.extend_from_slice(&word);
+    }
+
+    fn words(&self) -> impl Iterator<Item = [u8; 5]> + '_ {
+        self.words
+            .chunks_exact(5)
+            .map(|word| word.try_into().unwrap())
+    }
+
+    // distinct words of other that this dictionary lacks, in the order they appear in other
+    pub fn missing(&self, other: &Self) -> Vec<[u8; 5]> {
+        if self.sorted && other.sorted {
+            return other.words().filter(|&word| !self.contains(word)).collect();
+        }
+        let mut words: HashSet<_> = self.words().collect();
+        other.words().filter(|&word| words.insert(word)).collect()
+    }
+
+    // this dictionary followed by the words of other that it lacks
+    pub fn merged(&self, other: &Self) -> Self {
+        let mut dict = self.clone();
+        for word in self.missing(other) {
+            dict.push(word);
+        }
+        dict
+    }
+
+    pub fn excluding(&self, other: &Self) -> Self {
+        if other.sorted {
+            return self.filter(|word| !other.contains(word));
+        }
+        let words: HashSet<_> = other.words().collect();
+        self.filter(|word| !words.contains(&word))
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        if other.sorted {
+            return self.filter(|word| other.contains(word));
+        }
+        let words: HashSet<_> = other.words().collect();
+        self.filter(|word| words.contains(&word))
+    }
+
+    pub fn filter<F>(&self, mut f: F) -> Self
+    where
+        F: FnMut([u8; 5]) -> bool,
+    {
+        let mut dict = Self::with_capacity(self.len()).with_rules(self.rules);
+        self.for_each(|word| {
+            if f(word) {
+                dict.push(word);
+            }
+        });
+        dict
     }
 }
 
@@ -61,15 +358,19 @@ impl Dictionary for WordDictionary {
     type Word = [u8; 5];
 
     fn len(&self) -> usize {
-        self.0.len() / 5
+        self.words.len() / 5
+    }
+
+    fn word_match(&self, guess: Self::Word, word: Self::Word) -> WordMatch {
+        WordMatch::with_rules(guess, word, self.rules)
     }
 
     fn partition(&self, guess: Self::Word) -> HashMap<WordMatch, Self> {
         let mut partition = HashMap::with_capacity(cmp::min(self.len(), 243));
         self.for_each(|answer| {
             partition
-                .entry(WordMatch::from(guess, answer))
-                .or_insert_with(|| Self::with_capacity(self.len() / 50))
+                .entry(self.word_match(guess, answer))
+                .or_insert_with(|| Self::with_capacity(self.len() / 50).with_rules(self.rules))
                 .push(answer);
         });
         partition
@@ -79,54 +380,212 @@ impl Dictionary for WordDictionary {
     where
         F: FnMut(Self::Word),
     {
-        self.0.array_chunks().copied().for_each(f)
+        self.words().for_each(f)
     }
 
     fn try_for_each<F, R>(&self, f: F) -> ControlFlow<R>
     where
         F: FnMut(Self::Word) -> ControlFlow<R>,
     {
-        self.0.array_chunks().copied().try_for_each(f)
+        self.words().try_for_each(f)
     }
 
     fn par_process<F>(&self, weight: u32, f: F) -> Option<u32>
     where
         F: Fn(Self::Word) -> Option<u32> + Sync + Send,
     {
-        self.0
-            .par_chunks(5)
-            .map(|word| unsafe { word.try_into().unwrap_unchecked() })
+        if self.len() < PARALLEL_CUTOFF {
+            return seq_process(self, weight, f);
+        }
+        self.words
+            .par_chunks_exact(5)
+            .map(|word| word.try_into().unwrap())
             .filter_map(f)
             .min()
             .map(|sub_weight| weight + sub_weight)
     }
 }
 
-const MATCHES: &'static [u8] = include_bytes!("../../../matches.bin");
+#[cfg(feature = "embedded-lists")]
+const MATCHES: &[u8] = include_bytes!("../../../matches.bin");
+// the digest of the answer list, not of matches.bin: the table is every answer guessed against
+// every answer, so this one list is the source of both sides
+#[cfg(feature = "embedded-lists")]
+const ANSWERS_SHA256: &str = "c495f6cbc36475358a89e30ac16bd5c0c05073c8c7647bb6c35dd01f7bae6e32";
+
+// the word lists and rules a match table was generated from
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TableSource {
+    pub guesses: String,
+    pub answers: String,
+    pub rules: MatchRules,
+}
+
+pub struct MatchTable {
+    matches: Cow<'static, [u8]>,
+    answers: usize,
+    source: Option<TableSource>,
+    // the answer list, when known, so searches can recognise renamed copies of a set
+    words: Option<WordDictionary>,
+}
+
+impl MatchTable {
+    #[cfg(feature = "embedded-lists")]
+    pub fn embedded() -> Self {
+        Self {
+            matches: Cow::Borrowed(MATCHES),
+            answers: 2309,
+            source: Some(TableSource {
+                guesses: ANSWERS_SHA256.to_string(),
+                answers: ANSWERS_SHA256.to_string(),
+                rules: MatchRules::Nyt,
+            }),
+            words: Some(WordDictionary::new(crate::LEGAL_ANSWERS)),
+        }
+    }
+
+    pub fn new(matches: Vec<u8>, answers: usize) -> Self {
+        assert_eq!(matches.len() % answers, 0);
+        Self {
+            matches: Cow::Owned(matches),
+            answers,
+            source: None,
+            words: None,
+        }
+    }
+
+    pub fn generate(guesses: &WordDictionary, answers: &WordDictionary, rules: MatchRules) -> Self {
+        let matches = (0..guesses.len())
+            .into_par_iter()
+            .flat_map_iter(|idx| {
+                let guess = guesses.word(idx);
+                (0..answers.len())
+                    .map(move |idx| WordMatch::with_rules(guess, answers.word(idx), rules).0)
+            })
+            .collect();
+        Self {
+            source: Some(TableSource {
+                guesses: guesses.sha256(),
+                answers: answers.sha256(),
+                rules,
+            }),
+            words: Some(answers.clone()),
+            ..Self::new(matches, answers.len())
+        }
+    }
+
+    // the embedded table when it was built from these lists, otherwise a freshly generated one,
+    // so custom lists still get correct partitions
+    pub fn for_lists(
+        guesses: &WordDictionary,
+        answers: &WordDictionary,
+        rules: MatchRules,
+    ) -> Self {
+        #[cfg(feature = "embedded-lists")]
+        {
+            let table = Self::embedded();
+            if table.validate(guesses, answers, rules).is_ok() {
+                return table;
+            }
+        }
+        Self::generate(guesses, answers, rules)
+    }
+
+    pub fn get(&self, guess: u16, answer: u16) -> WordMatch {
+        WordMatch(self.matches[guess as usize * self.answers + answer as usize])
+    }
+
+    pub fn guesses(&self) -> usize {
+        self.matches.len() / self.answers
+    }
+
+    pub fn answers(&self) -> usize {
+        self.answers
+    }
+
+    pub fn source(&self) -> Option<&TableSource> {
+        self.source.as_ref()
+    }
+
+    // a table built for other lists still looks up fine but partitions answers wrongly, so check
+    // before indexing by position; tables without a recorded source can only be checked for size
+    pub fn validate(
+        &self,
+        guesses: &WordDictionary,
+        answers: &WordDictionary,
+        rules: MatchRules,
+    ) -> Result<(), String> {
+        if self.guesses() != guesses.len() || self.answers != answers.len() {
+            return Err(format!(
+                "match table is {}x{} but the word lists are {}x{}",
+                self.guesses(),
+                self.answers,
+                guesses.len(),
+                answers.len()
+            ));
+        }
+        if let Some(source) = &self.source {
+            if source.rules != rules {
+                return Err(format!(
+                    "match table uses {} rules, not {}",
+                    source.rules, rules
+                ));
+            }
+            if source.guesses != guesses.sha256() {
+                return Err("match table was generated from a different guess list".to_string());
+            }
+            if source.answers != answers.sha256() {
+                return Err("match table was generated from a different answer list".to_string());
+            }
+        }
+        Ok(())
+    }
+}
 
-pub struct OffsetDictionary {
+pub struct OffsetDictionary<'a> {
     pub words: Vec<u16>,
+    table: &'a MatchTable,
 }
 
-impl OffsetDictionary {
-    pub fn new() -> Self {
+impl<'a> OffsetDictionary<'a> {
+    pub fn new(table: &'a MatchTable) -> Self {
         Self {
-            words: (0..2309).collect(),
+            words: (0..table.answers as u16).collect(),
+            table,
         }
     }
 
+    pub fn with_words(table: &'a MatchTable, words: Vec<u16>) -> Self {
+        Self { words, table }
+    }
+
     fn push(&mut self, word: u16) {
         self.words.push(word);
     }
 }
 
-impl Dictionary for OffsetDictionary {
+impl Dictionary for OffsetDictionary<'_> {
     type Word = u16;
 
     fn len(&self) -> usize {
         self.words.len()
     }
 
+    fn word_match(&self, guess: Self::Word, word: Self::Word) -> WordMatch {
+        self.table.get(guess, word)
+    }
+
+    fn answer_set(&self) -> Option<AnswerSet> {
+        AnswerSet::from_indices(self.words.iter().map(|&word| word as usize))
+    }
+
+    fn canonical_key(&self) -> Option<CanonicalKey> {
+        let answers = self.table.words.as_ref()?;
+        Some(CanonicalKey::new(
+            self.words.iter().map(|&word| answers.word(word as usize)),
+        ))
+    }
+
     fn partition(&self, guess: Self::Word) -> HashMap<WordMatch, Self>
     where
         Self: Sized,
@@ -134,9 +593,10 @@ impl Dictionary for OffsetDictionary {
         let mut partition = HashMap::with_capacity(cmp::min(self.len(), 243));
         self.for_each(|answer| {
             partition
-                .entry(WordMatch(MATCHES[guess as usize * 2309 + answer as usize]))
+                .entry(self.table.get(guess, answer))
                 .or_insert_with(|| OffsetDictionary {
                     words: Vec::with_capacity(self.len() / 50),
+                    table: self.table,
                 })
                 .push(answer);
         });
@@ -161,6 +621,159 @@ impl Dictionary for OffsetDictionary {
     where
         F: Fn(Self::Word) -> Option<u32> + Sync + Send,
     {
+        if self.len() < PARALLEL_CUTOFF {
+            return seq_process(self, weight, f);
+        }
+        self.words
+            .par_iter()
+            .copied()
+            .filter_map(f)
+            .min()
+            .map(|sub_weight| weight + sub_weight)
+    }
+}
+
+pub struct IndexedTable {
+    guesses: WordDictionary,
+    answers: WordDictionary,
+    rules: MatchRules,
+    matches: MatchTable,
+}
+
+impl IndexedTable {
+    pub fn new(guesses: WordDictionary, answers: WordDictionary, rules: MatchRules) -> Self {
+        assert!(guesses.len() <= u16::MAX as usize && answers.len() <= u16::MAX as usize);
+        let matches = MatchTable::generate(&guesses, &answers, rules);
+        Self {
+            guesses,
+            answers,
+            rules,
+            matches,
+        }
+    }
+
+    pub fn guess(&self, id: u16) -> [u8; 5] {
+        self.guesses.word(id as usize)
+    }
+
+    pub fn answer(&self, id: u16) -> [u8; 5] {
+        self.answers.word(id as usize)
+    }
+
+    pub fn guess_id(&self, word: [u8; 5]) -> Option<u16> {
+        self.guesses.index_of(word).map(|idx| idx as u16)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum IndexSpace {
+    Guesses,
+    Answers,
+}
+
+// words are guess ids or answer ids depending on the space, and are always partitioned by a
+// guess id
+pub struct IndexedDictionary<'a> {
+    pub words: Vec<u16>,
+    space: IndexSpace,
+    table: &'a IndexedTable,
+}
+
+impl<'a> IndexedDictionary<'a> {
+    pub fn guesses(table: &'a IndexedTable) -> Self {
+        Self {
+            words: (0..table.guesses.len() as u16).collect(),
+            space: IndexSpace::Guesses,
+            table,
+        }
+    }
+
+    pub fn answers(table: &'a IndexedTable) -> Self {
+        Self {
+            words: (0..table.answers.len() as u16).collect(),
+            space: IndexSpace::Answers,
+            table,
+        }
+    }
+
+    fn get(&self, guess: u16, word: u16) -> WordMatch {
+        match self.space {
+            IndexSpace::Answers => self.table.matches.get(guess, word),
+            IndexSpace::Guesses => WordMatch::with_rules(
+                self.table.guess(guess),
+                self.table.guess(word),
+                self.table.rules,
+            ),
+        }
+    }
+}
+
+impl Dictionary for IndexedDictionary<'_> {
+    type Word = u16;
+
+    fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    fn word_match(&self, guess: Self::Word, word: Self::Word) -> WordMatch {
+        self.get(guess, word)
+    }
+
+    fn answer_set(&self) -> Option<AnswerSet> {
+        match self.space {
+            IndexSpace::Answers => {
+                AnswerSet::from_indices(self.words.iter().map(|&word| word as usize))
+            }
+            IndexSpace::Guesses => None,
+        }
+    }
+
+    fn canonical_key(&self) -> Option<CanonicalKey> {
+        match self.space {
+            IndexSpace::Answers => Some(CanonicalKey::new(
+                self.words.iter().map(|&word| self.table.answer(word)),
+            )),
+            IndexSpace::Guesses => None,
+        }
+    }
+
+    fn partition(&self, guess: Self::Word) -> HashMap<WordMatch, Self> {
+        let mut partition = HashMap::with_capacity(cmp::min(self.len(), 243));
+        self.for_each(|word| {
+            partition
+                .entry(self.get(guess, word))
+                .or_insert_with(|| IndexedDictionary {
+                    words: Vec::with_capacity(self.len() / 50),
+                    space: self.space,
+                    table: self.table,
+                })
+                .words
+                .push(word);
+        });
+        partition
+    }
+
+    fn for_each<F>(&self, f: F)
+    where
+        F: FnMut(Self::Word),
+    {
+        self.words.iter().copied().for_each(f)
+    }
+
+    fn try_for_each<F, R>(&self, f: F) -> ControlFlow<R>
+    where
+        F: FnMut(Self::Word) -> ControlFlow<R>,
+    {
+        self.words.iter().copied().try_for_each(f)
+    }
+
+    fn par_process<F>(&self, weight: u32, f: F) -> Option<u32>
+    where
+        F: Fn(Self::Word) -> Option<u32> + Sync + Send,
+    {
+        if self.len() < PARALLEL_CUTOFF {
+            return seq_process(self, weight, f);
+        }
         self.words
             .par_iter()
             .copied()
@@ -172,17 +785,226 @@ impl Dictionary for OffsetDictionary {
 
 #[cfg(test)]
 mod test {
-    use crate::{Dictionary, WordDictionary, WordMatch, LEGAL_ANSWERS};
-    use std::fs::File;
-    use std::io::{BufWriter, Write};
+    use crate::{
+        solve_easy, solve_hard, solve_hard_limited, CancellationToken, CanonicalKey, Dictionary,
+        Eviction, IndexedDictionary, IndexedTable, MatchRules, MatchTable, OffsetDictionary,
+        PartitionSummary, SearchMetrics, WordDictionary, WordMatch,
+    };
+
+    #[test]
+    fn get() {
+        let dict = WordDictionary::new(b"lightmight");
+        assert_eq!(dict.get(1), Some(*b"might"));
+        assert_eq!(dict.get(2), None);
+        assert_eq!(dict.get(usize::MAX / 5), None);
+    }
+
+    #[test]
+    fn sorted() {
+        let shuffled = WordDictionary::new(b"nightlightsightmight");
+        assert!(!shuffled.is_sorted());
+        let sorted = shuffled.sorted();
+        assert!(sorted.is_sorted());
+        for dict in [&shuffled, &sorted] {
+            assert_eq!(dict.word(dict.index_of(*b"sight").unwrap()), *b"sight");
+            assert!(dict.contains(*b"light"));
+            assert!(!dict.contains(*b"fight"));
+            assert_eq!(
+                dict.missing(&WordDictionary::new(b"fightlight")),
+                [*b"fight"]
+            );
+        }
+        assert!(sorted.filter(|word| word != *b"might").is_sorted());
+        assert!(!WordDictionary::new(b"lightlight").is_sorted());
+        let key = |dict: &WordDictionary| CanonicalKey::new((0..dict.len()).map(|i| dict.word(i)));
+        assert_eq!(key(&shuffled), key(&sorted));
+    }
+
+    #[test]
+    fn merged() {
+        let guesses = WordDictionary::new(b"lightmight");
+        let answers = WordDictionary::new(b"nightlightnight");
+        assert_eq!(guesses.missing(&answers), vec![*b"night"]);
+        let merged = guesses.merged(&answers);
+        assert_eq!(merged.len(), 3);
+        assert!(merged.missing(&answers).is_empty());
+        assert_eq!(merged.excluding(&guesses).len(), 1);
+        assert_eq!(merged.excluding(&answers).get(0), Some(*b"might"));
+        assert_eq!(merged.intersection(&answers).len(), 2);
+    }
+
+    #[test]
+    fn sha256() {
+        // sha256sum of "light\nmight\n"
+        assert_eq!(
+            WordDictionary::new(b"lightmight").sha256(),
+            "fc38779cee760018e18e64c7d9b6b46c775dbbc38a7454f445d89582b01941d0"
+        );
+    }
+
+    #[test]
+    fn partition_iter() {
+        let answers = WordDictionary::new(b"lightmightcranenightsight");
+        let partition = answers.partition(*b"light");
+        let buckets: Vec<_> = answers
+            .partition_iter(*b"light")
+            .map(|(wm, bucket)| (wm, bucket.collect::<Vec<_>>()))
+            .collect();
+        assert_eq!(buckets.len(), partition.len());
+        for (wm, words) in buckets.iter() {
+            let mut expected = Vec::new();
+            partition[wm].for_each(|word| expected.push(word));
+            assert_eq!(*words, expected);
+        }
+        assert_eq!(buckets[1].1, vec![*b"might", *b"night", *b"sight"]);
+        let summary = answers.partition_summary(*b"light");
+        assert_eq!(summary, PartitionSummary::new(&partition));
+    }
+
+    #[test]
+    fn par_process_cutoff() {
+        let small = WordDictionary::new(b"lightmightcranenightsight");
+        // enough words to take the parallel path
+        let large: Vec<u8> = (0..40u8)
+            .flat_map(|i| b"aaaaa".map(|c| c + i % 26))
+            .collect();
+        let large = WordDictionary::new(&large);
+        for dict in [small, large] {
+            let cost = |word: [u8; 5]| (word[0] != b'l').then_some(u32::from(word[4]));
+            let mut expected: Option<u32> = None;
+            dict.for_each(|word| {
+                if let Some(cost) = cost(word) {
+                    expected = Some(expected.map_or(cost, |best| best.min(cost)));
+                }
+            });
+            assert_eq!(dict.par_process(1, cost), expected.map(|cost| cost + 1));
+        }
+    }
+
+    #[test]
+    fn summary() {
+        let answers = WordDictionary::new(b"lightmightnightsightcrane");
+        let (partition, summary) = answers.summarized_partition(*b"light");
+        assert_eq!(summary, PartitionSummary::new(&partition));
+        assert_eq!(
+            summary.sizes,
+            vec![
+                (WordMatch::ABSENT, 1),
+                ("acccc".parse().unwrap(), 3),
+                (WordMatch::CORRECT, 1)
+            ]
+        );
+        assert!(summary.has_correct);
+        assert_eq!(summary.largest, 3);
+        assert_eq!(summary.buckets(), 3);
+        assert_eq!(summary.answers(), 5);
+        assert_eq!(summary.difficulty(), 11);
+    }
+
+    #[test]
+    fn indexed() {
+        let guesses = WordDictionary::new(b"lightmightnightsighttightfightcrane");
+        let answers = WordDictionary::new(b"lightnightsightfight");
+        let table = IndexedTable::new(guesses.clone(), answers.clone(), MatchRules::Nyt);
+        let indexed_guesses = &IndexedDictionary::guesses(&table);
+        let indexed_answers = &IndexedDictionary::answers(&table);
+        let metrics = &SearchMetrics::default();
+        let cancel = &CancellationToken::default();
+        for word in [*b"light", *b"might", *b"crane"] {
+            let id = table.guess_id(word).unwrap();
+            assert_eq!(table.guess(id), word);
+            assert_eq!(
+                solve_easy(id, indexed_guesses, indexed_answers, 3, metrics, cancel),
+                solve_easy(word, &guesses, &answers, 3, metrics, cancel)
+            );
+            assert_eq!(
+                solve_hard(id, indexed_guesses, indexed_answers, 3, metrics, cancel),
+                solve_hard(word, &guesses, &answers, 3, metrics, cancel)
+            );
+        }
+    }
+
+    #[test]
+    fn transpositions() {
+        let answers = WordDictionary::new(b"lightmightnightsighttightfightcranecrate");
+        let table = MatchTable::generate(&answers, &answers, MatchRules::Nyt);
+        let dict = &OffsetDictionary::new(&table);
+        let memo = &SearchMetrics::with_transpositions();
+        let cancel = &CancellationToken::default();
+        for guess in 0..answers.len() as u16 {
+            assert_eq!(
+                solve_hard_limited(guess, dict, 4, memo, cancel),
+                solve_hard_limited(guess, dict, 4, &SearchMetrics::default(), cancel)
+            );
+        }
+        assert!(memo.transpositions() > 0);
+        let hit_rate = memo.hit_rate().unwrap();
+        assert!(hit_rate > 0.0 && hit_rate <= 100.0);
+        assert!(memo.lookups() >= memo.transpositions());
+        assert_eq!(SearchMetrics::with_transpositions().hit_rate(), None);
+        for eviction in [Eviction::Lru, Eviction::Depth, Eviction::Size] {
+            let small = &SearchMetrics::with_transposition_table(4, eviction);
+            for guess in 0..answers.len() as u16 {
+                assert_eq!(
+                    solve_hard_limited(guess, dict, 4, small, cancel),
+                    solve_hard_limited(guess, dict, 4, memo, cancel)
+                );
+            }
+            assert!(small.evictions() > 0);
+        }
+    }
 
+    #[cfg(feature = "embedded-lists")]
+    #[test]
+    fn embedded_table_source() {
+        use crate::LEGAL_ANSWERS;
+
+        let table = MatchTable::embedded();
+        let answers = WordDictionary::new(LEGAL_ANSWERS);
+        assert_eq!(table.validate(&answers, &answers, MatchRules::Nyt), Ok(()));
+        assert!(table
+            .validate(&answers, &answers, MatchRules::EveryOccurrence)
+            .is_err());
+        let mut shuffled = WordDictionary::new(&LEGAL_ANSWERS[5..]);
+        shuffled.push(answers.word(0));
+        assert_eq!(
+            table.validate(&shuffled, &answers, MatchRules::Nyt),
+            Err("match table was generated from a different guess list".to_string())
+        );
+        assert_eq!(
+            table.validate(&answers, &shuffled, MatchRules::Nyt),
+            Err("match table was generated from a different answer list".to_string())
+        );
+        let table = MatchTable::for_lists(&shuffled, &shuffled, MatchRules::Nyt);
+        assert_eq!(
+            table.validate(&shuffled, &shuffled, MatchRules::Nyt),
+            Ok(())
+        );
+        assert_eq!(table.get(0, 0), WordMatch::CORRECT);
+    }
+
+    // the unchecked feature leaves out the bounds check this relies on
+    #[cfg(not(feature = "unchecked"))]
+    #[test]
+    #[should_panic]
+    fn word_out_of_bounds() {
+        WordDictionary::new(b"lightmight").word(2);
+    }
+
+    #[cfg(feature = "embedded-lists")]
     #[test]
     fn gen_dict() {
+        use crate::{Dictionary, WordMatch, LEGAL_ANSWERS};
+        use std::fs::File;
+        use std::io::{BufWriter, Write};
+
         let guesses = WordDictionary::new(LEGAL_ANSWERS);
         let mut matches = BufWriter::new(File::create("../../matches.bin").unwrap());
         guesses.for_each(|guess| {
             guesses.for_each(|answer| {
-                matches.write(&[WordMatch::from(guess, answer).0]).unwrap();
+                matches
+                    .write_all(&[WordMatch::from(guess, answer).0])
+                    .unwrap();
             })
         });
     }
@@ -0,0 +1,187 @@
+use crate::{
+    play_game, Dictionary, EntropyGreedyStrategy, GameState, Solution, Strategy, WordDictionary,
+    WordMatch,
+};
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use std::collections::HashMap;
+
+pub struct Difficulty {
+    pub answer: [u8; 5],
+    // guesses the solution tree takes, including the final one
+    pub optimal: usize,
+    // other answers that differ from this one in a single position
+    pub neighbors: u32,
+    // guesses an entropy greedy player takes, if it finishes within the guess limit
+    pub greedy: Option<usize>,
+}
+
+pub fn trap_neighbors(answer: [u8; 5], answers: &WordDictionary) -> u32 {
+    let mut count = 0;
+    answers.for_each(|other| {
+        if answer
+            .iter()
+            .zip(other.iter())
+            .filter(|(a, b)| a != b)
+            .count()
+            == 1
+        {
+            count += 1;
+        }
+    });
+    count
+}
+
+pub fn rate_difficulty(
+    solution: &Solution,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    max_guesses: usize,
+) -> Vec<Difficulty> {
+    let greedy = EntropyGreedyStrategy::new(guesses);
+    (0..answers.len())
+        .into_par_iter()
+        .map(|idx| {
+            let answer = answers.word(idx);
+            Difficulty {
+                answer,
+                optimal: solution.path_for_answer(answer, answers.rules()).len(),
+                neighbors: trap_neighbors(answer, answers),
+                greedy: play_game(&greedy, answers, answer, max_guesses),
+            }
+        })
+        .collect()
+}
+
+// answers that agree everywhere except one position, like the _ight family
+pub struct TrapSet {
+    pub position: usize,
+    pub words: Vec<[u8; 5]>,
+}
+
+impl TrapSet {
+    pub fn pattern(&self) -> String {
+        let mut pattern = self.words[0];
+        pattern[self.position] = b'_';
+        String::from_utf8_lossy(&pattern).into_owned()
+    }
+}
+
+pub fn trap_sets(answers: &WordDictionary, min_size: usize) -> Vec<TrapSet> {
+    let mut families: HashMap<(usize, [u8; 5]), Vec<[u8; 5]>> = HashMap::new();
+    answers.for_each(|answer| {
+        for position in 0..5 {
+            let mut key = answer;
+            key[position] = 0;
+            families.entry((position, key)).or_default().push(answer);
+        }
+    });
+    let mut sets: Vec<_> = families
+        .into_iter()
+        .filter(|(_, words)| words.len() >= min_size)
+        .map(|((position, _), words)| TrapSet { position, words })
+        .collect();
+    sets.sort_by(|a, b| {
+        b.words
+            .len()
+            .cmp(&a.words.len())
+            .then_with(|| a.words.cmp(&b.words))
+    });
+    sets
+}
+
+// hard mode keeps greens in place, so each later guess tests at most one family letter per
+// free position. k family members sharing a bucket then need ceil((k - 1) / free) + 1 more
+// guesses in the worst case
+pub fn hard_mode_feasible(
+    guess: [u8; 5],
+    answers: &WordDictionary,
+    sets: &[TrapSet],
+    depth: usize,
+) -> bool {
+    sets.iter().all(|set| {
+        let mut buckets: HashMap<WordMatch, usize> = HashMap::new();
+        for &word in set.words.iter() {
+            if answers.index_of(word).is_some() {
+                *buckets.entry(WordMatch::from(guess, word)).or_default() += 1;
+            }
+        }
+        buckets.into_iter().all(|(wm, k)| {
+            if wm == WordMatch::CORRECT || k < 2 {
+                return true;
+            }
+            let free = 5 - wm.greens() as usize;
+            (k - 1).div_ceil(free) < depth
+        })
+    })
+}
+
+pub struct TrapReport {
+    pub mean: f64,
+    pub worst: usize,
+    // the game for the member that took the most guesses
+    pub worst_game: Vec<([u8; 5], WordMatch)>,
+}
+
+// plays every member of the set to the end, so the worst case may exceed the usual guess limit
+pub fn evaluate_trap_set<S: Strategy + ?Sized>(
+    strategy: &S,
+    answers: &WordDictionary,
+    set: &TrapSet,
+) -> TrapReport {
+    let max_guesses = 5 + set.words.len();
+    let games: Vec<_> = set
+        .words
+        .par_iter()
+        .map(|&answer| {
+            let mut state = GameState::new(answers);
+            while state.history.len() < max_guesses {
+                let guess = strategy.next_guess(&state);
+                let wm = WordMatch::from(guess, answer);
+                state.guess(guess, wm);
+                if wm == WordMatch::CORRECT {
+                    break;
+                }
+            }
+            state.history
+        })
+        .collect();
+    let total: usize = games.iter().map(Vec::len).sum();
+    let worst_game = games.into_iter().max_by_key(Vec::len).unwrap();
+    TrapReport {
+        mean: total as f64 / set.words.len() as f64,
+        worst: worst_game.len(),
+        worst_game,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::analysis::{hard_mode_feasible, trap_neighbors, trap_sets};
+    use crate::WordDictionary;
+
+    #[test]
+    fn neighbors() {
+        let answers = WordDictionary::new(b"lightmightnightsightcranelaity");
+        assert_eq!(trap_neighbors(*b"light", &answers), 3);
+        assert_eq!(trap_neighbors(*b"crane", &answers), 0);
+    }
+
+    #[test]
+    fn families() {
+        let answers = WordDictionary::new(b"lightmightnightsightcranelaity");
+        let sets = trap_sets(&answers, 3);
+        assert_eq!(sets.len(), 1);
+        assert_eq!(sets[0].pattern(), "_ight");
+        assert_eq!(sets[0].words.len(), 4);
+    }
+
+    #[test]
+    fn feasible() {
+        let answers = WordDictionary::new(b"lightmightnightsighttightfight");
+        let sets = trap_sets(&answers, 2);
+        // bight leaves every member with four greens, so they must be guessed one at a time
+        assert!(!hard_mode_feasible(*b"bight", &answers, &sets, 5));
+        assert!(hard_mode_feasible(*b"bight", &answers, &sets, 6));
+        assert!(hard_mode_feasible(*b"crane", &answers, &sets, 3));
+    }
+}
@@ -0,0 +1,240 @@
+use crate::{pattern_counts, Alphabet, Dictionary, Rng, Strategy, WordDictionary, WordMatch};
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+
+#[derive(Clone)]
+pub struct GameState {
+    pub history: Vec<([u8; 5], WordMatch)>,
+    pub answers: WordDictionary,
+}
+
+impl GameState {
+    pub fn new(answers: &WordDictionary) -> Self {
+        Self {
+            history: Vec::new(),
+            answers: answers.clone(),
+        }
+    }
+
+    pub fn guess(&mut self, guess: [u8; 5], wm: WordMatch) {
+        self.answers = self
+            .answers
+            .filter(|answer| self.answers.word_match(guess, answer) == wm);
+        self.history.push((guess, wm));
+    }
+
+    // replays moves written like "salet:apaca,crony:acaaa", each a guess and its feedback
+    pub fn from_history(
+        answers: &WordDictionary,
+        history: &str,
+        alphabet: &Alphabet,
+    ) -> Result<Self, String> {
+        let mut state = Self::new(answers);
+        for (guess, wm) in parse_history(history, alphabet)? {
+            state.guess(guess, wm);
+        }
+        if state.answers.is_empty() {
+            return Err(format!("no answer fits the history: {}", history));
+        }
+        Ok(state)
+    }
+}
+
+// a correction to one mistyped feedback that lets some answer fit the history again
+pub struct Repair {
+    pub entry: usize,
+    pub feedback: WordMatch,
+    pub state: GameState,
+}
+
+impl GameState {
+    // when no answer fits, looks for the one entry whose feedback, changed in as few tiles as
+    // possible, leaves some answer fitting. ties go to the correction leaving more answers, then
+    // to the earlier entry
+    pub fn repair(&self, answers: &WordDictionary) -> Option<Repair> {
+        if !self.answers.is_empty() {
+            return None;
+        }
+        let mut best = None;
+        for (entry, &(guess, wm)) in self.history.iter().enumerate() {
+            let rest = answers.filter(|answer| {
+                self.history
+                    .iter()
+                    .enumerate()
+                    .all(|(idx, &(g, m))| idx == entry || answers.word_match(g, answer) == m)
+            });
+            for (feedback, count) in WordMatch::all().zip(pattern_counts(guess, &rest)) {
+                if count == 0 || feedback == wm {
+                    continue;
+                }
+                let key = (wm.distance(feedback), Reverse(count), entry);
+                if best.as_ref().is_none_or(|(best, _, _)| key < *best) {
+                    best = Some((key, entry, feedback));
+                }
+            }
+        }
+        let (_, entry, feedback) = best?;
+        let mut state = Self::new(answers);
+        for (idx, &(guess, wm)) in self.history.iter().enumerate() {
+            state.guess(guess, if idx == entry { feedback } else { wm });
+        }
+        Some(Repair {
+            entry,
+            feedback,
+            state,
+        })
+    }
+}
+
+pub fn parse_history(
+    history: &str,
+    alphabet: &Alphabet,
+) -> Result<Vec<([u8; 5], WordMatch)>, String> {
+    history
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (guess, feedback) = entry
+                .split_once(':')
+                .ok_or_else(|| format!("expected guess:feedback: {}", entry))?;
+            Ok((alphabet.encode(guess)?, feedback.parse()?))
+        })
+        .collect()
+}
+
+// the moves in the form parse_history reads
+pub fn format_history(history: &[([u8; 5], WordMatch)], alphabet: &Alphabet) -> String {
+    let moves: Vec<_> = history
+        .iter()
+        .map(|(guess, wm)| format!("{}:{}", alphabet.decode(*guess), wm))
+        .collect();
+    moves.join(",")
+}
+
+pub fn play_game<S: Strategy + ?Sized>(
+    strategy: &S,
+    answers: &WordDictionary,
+    answer: [u8; 5],
+    max_guesses: usize,
+) -> Option<usize> {
+    let mut state = GameState::new(answers);
+    for n in 1..=max_guesses {
+        let guess = strategy.next_guess(&state);
+        let wm = answers.word_match(guess, answer);
+        if wm == WordMatch::CORRECT {
+            return Some(n);
+        }
+        state.guess(guess, wm);
+    }
+    None
+}
+
+// the answers of the last few games, oldest first, which new games don't reuse the way the
+// daily game never repeats an answer
+pub struct RecentAnswers {
+    words: VecDeque<[u8; 5]>,
+    limit: usize,
+}
+
+impl RecentAnswers {
+    pub fn new(words: Vec<[u8; 5]>, limit: usize) -> Self {
+        let mut this = Self {
+            words: VecDeque::from(words),
+            limit,
+        };
+        this.trim();
+        this
+    }
+
+    pub fn words(&self) -> impl Iterator<Item = [u8; 5]> + '_ {
+        self.words.iter().copied()
+    }
+
+    // a random answer from outside the recent ones, unless that rules out every answer
+    pub fn pick(&mut self, rng: &mut Rng, answers: &WordDictionary) -> [u8; 5] {
+        let fresh = answers.filter(|answer| !self.words.contains(&answer));
+        let answers = if fresh.is_empty() { answers } else { &fresh };
+        let answer = answers.word(rng.below(answers.len()));
+        self.words.push_back(answer);
+        self.trim();
+        answer
+    }
+
+    fn trim(&mut self) {
+        while self.words.len() > self.limit {
+            self.words.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        format_history, Alphabet, Dictionary, GameState, RecentAnswers, Rng, WordDictionary,
+        WordMatch,
+    };
+
+    #[test]
+    fn from_history() {
+        let answers = WordDictionary::new(b"lightmightnightsightcrane");
+        let alphabet = Alphabet::english();
+        let state = GameState::from_history(&answers, "crane:aaaaa, sight:accc", &alphabet);
+        assert!(state.is_err());
+        let state =
+            GameState::from_history(&answers, "crane:aaaaa,sight:acccc", &alphabet).unwrap();
+        assert_eq!(state.history.len(), 2);
+        assert_eq!(
+            state.history[1],
+            (*b"sight", WordMatch::from(*b"sight", *b"light"))
+        );
+        assert_eq!(state.answers.len(), 2);
+        assert!(GameState::from_history(&answers, "", &alphabet)
+            .is_ok_and(|state| state.answers.len() == 5));
+        assert!(GameState::from_history(&answers, "crane", &alphabet).is_err());
+        assert!(GameState::from_history(&answers, "crane:ccccc,light:ccccc", &alphabet).is_err());
+        assert_eq!(
+            format_history(&state.history, &alphabet),
+            "crane:aaaaa,sight:acccc"
+        );
+    }
+
+    #[test]
+    fn repair() {
+        let answers = WordDictionary::new(b"lightmightnightsightcrane");
+        let alphabet = Alphabet::english();
+        let mut state = GameState::from_history(&answers, "crane:aaaaa", &alphabet).unwrap();
+        assert!(state.repair(&answers).is_none());
+        state.guess(*b"sight", "aaccc".parse().unwrap());
+        assert!(state.answers.is_empty());
+        let repair = state.repair(&answers).unwrap();
+        assert_eq!(repair.entry, 1);
+        assert_eq!(repair.feedback, "acccc".parse().unwrap());
+        assert_eq!(repair.state.answers.len(), 2);
+        assert_eq!(repair.state.history[0], state.history[0]);
+    }
+
+    #[test]
+    fn recent_answers() {
+        let answers = WordDictionary::new(b"lightmightnightsight");
+        let mut recent = RecentAnswers::new(vec![*b"crane", *b"light", *b"might", *b"night"], 3);
+        assert_eq!(
+            recent.words().collect::<Vec<_>>(),
+            [*b"light", *b"might", *b"night"]
+        );
+        let mut rng = Rng::new(7);
+        assert_eq!(recent.pick(&mut rng, &answers), *b"sight");
+        assert_eq!(recent.pick(&mut rng, &answers), *b"light");
+        assert_eq!(
+            recent.words().collect::<Vec<_>>(),
+            [*b"night", *b"sight", *b"light"]
+        );
+        // with every answer recent, any answer will do
+        let mut recent = RecentAnswers::new(Vec::new(), 4);
+        for _ in 0..4 {
+            recent.pick(&mut rng, &answers);
+        }
+        recent.pick(&mut rng, &answers);
+        assert_eq!(recent.words().count(), 4);
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{
+    rank_guesses, solve_from_state, Breadth, CancellationToken, Dictionary, GameState,
+    WordDictionary, MAX_LETTERS,
+};
+use std::fmt;
+use std::fmt::Display;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Hint {
+    Remaining(usize),
+    Letter(u8),
+    Guess([u8; 5]),
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Hint::Remaining(1) => write!(f, "1 candidate remains"),
+            Hint::Remaining(n) => write!(f, "{} candidates remain", n),
+            Hint::Letter(c) => write!(f, "the answer contains {}", *c as char),
+            Hint::Guess(word) => write!(f, "try {}", String::from_utf8_lossy(word)),
+        }
+    }
+}
+
+// level 1 counts the candidates, level 2 reveals a letter in every candidate, level 3 gives the
+// best guess that could still win, and level 4 gives the guess the solver would play, within
+// depth guesses counting those already made
+pub fn hint(
+    level: u8,
+    guesses: &WordDictionary,
+    state: &GameState,
+    breadth: Breadth,
+    depth: usize,
+    hard: bool,
+) -> Result<Hint, String> {
+    let answers = &state.answers;
+    if answers.is_empty() {
+        return Err("no answers match the feedback".to_string());
+    }
+    match level {
+        1 => Ok(Hint::Remaining(answers.len())),
+        2 => {
+            let mut common = [true; MAX_LETTERS];
+            answers.for_each(|answer| {
+                for (c, common) in common.iter_mut().enumerate() {
+                    *common &= answer.contains(&(b'a' + c as u8));
+                }
+            });
+            // prefer letters that earlier feedback hasn't already shown to be in the answer
+            let mut known = [false; MAX_LETTERS];
+            for (guess, wm) in state.history.iter() {
+                for (&c, m) in guess.iter().zip(wm.to_string().chars()) {
+                    known[(c - b'a') as usize] |= m != 'a';
+                }
+            }
+            (0..MAX_LETTERS)
+                .filter(|&c| common[c])
+                .min_by_key(|&c| known[c])
+                .map(|c| Hint::Letter(b'a' + c as u8))
+                .ok_or_else(|| "no letter is in every candidate".to_string())
+        }
+        3 => {
+            let ranked = rank_guesses(guesses, answers);
+            let best = ranked.first().ok_or("no guesses")?.word;
+            let good = ranked
+                .iter()
+                .filter(|guess| guess.candidate && guess.word != best)
+                .chain(ranked.iter().skip(1))
+                .next()
+                .map_or(best, |guess| guess.word);
+            Ok(Hint::Guess(good))
+        }
+        4 => solve_from_state(
+            state,
+            guesses,
+            breadth,
+            depth,
+            hard,
+            &CancellationToken::default(),
+        )
+        .map(|solution| Hint::Guess(solution.guess()))
+        .ok_or_else(|| format!("no solution within {} guesses", depth)),
+        _ => Err(format!("invalid hint level: {}", level)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{hint, Breadth, GameState, Hint, WordDictionary, WordMatch};
+
+    #[test]
+    fn levels() {
+        let answers = WordDictionary::new(b"lightmightnightsightcrane");
+        let guesses = WordDictionary::new(b"lightmightnightsightcranemnsxx");
+        let mut state = GameState::new(&answers);
+        state.guess(*b"brake", WordMatch::ABSENT);
+        let hint = |level| hint(level, &guesses, &state, Breadth::from(4), 6, false);
+        assert_eq!(hint(1), Ok(Hint::Remaining(4)));
+        assert_eq!(hint(2), Ok(Hint::Letter(b'g')));
+        assert_eq!(hint(4), Ok(Hint::Guess(*b"mnsxx")));
+        assert_eq!(hint(3), Ok(Hint::Guess(*b"light")));
+        assert!(hint(5).is_err());
+    }
+}
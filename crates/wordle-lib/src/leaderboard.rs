@@ -0,0 +1,206 @@
+use crate::{
+    entropy, expected_remaining, Breadth, CancellationToken, Dictionary, Guess, GuessPolicy,
+    Tiebreak, WordDictionary,
+};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LeaderboardMetric {
+    #[default]
+    Entropy,
+    Remaining,
+    Tree,
+}
+
+impl FromStr for LeaderboardMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "entropy" => Ok(Self::Entropy),
+            "remaining" => Ok(Self::Remaining),
+            "tree" => Ok(Self::Tree),
+            _ => Err(format!("unknown metric: {}", s)),
+        }
+    }
+}
+
+pub struct OpenerScore {
+    pub word: [u8; 5],
+    pub entropy: f64,
+    pub remaining: f64,
+    // the mean guesses of the best tree after this opener, only built when ranking by tree
+    pub tree: Option<f64>,
+    pub two_ply: f64,
+}
+
+// the entropy of the guess plus the expected entropy of the best follow up guess in each bucket
+pub fn two_ply_entropy(guess: [u8; 5], guesses: &WordDictionary, answers: &WordDictionary) -> f64 {
+    let total = answers.len() as f64;
+    let partition: Vec<_> = answers.partition(guess).into_values().collect();
+    let second: f64 = partition
+        .into_par_iter()
+        .filter(|bucket| bucket.len() > 1)
+        .map(|bucket| {
+            let mut best = 0.0f64;
+            guesses.for_each(|second| best = best.max(entropy(second, &bucket)));
+            bucket.len() as f64 / total * best
+        })
+        .sum();
+    entropy(guess, answers) + second
+}
+
+pub fn tree_mean(
+    guess: [u8; 5],
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    breadth: Breadth,
+    depth: usize,
+    hard: bool,
+    cancel: &CancellationToken,
+) -> Option<f64> {
+    Guess::new(guess, answers)
+        // ties don't change the mean
+        .slow_solution(
+            guesses,
+            answers,
+            breadth.deeper(),
+            depth - 1,
+            hard,
+            Tiebreak::default(),
+            GuessPolicy::default(),
+            cancel,
+        )
+        .map(|solution| solution.size() as f64 / answers.len() as f64)
+}
+
+fn by_tree(a: &OpenerScore, b: &OpenerScore) -> Ordering {
+    match (a.tree, b.tree) {
+        (Some(a), Some(b)) => a.partial_cmp(&b).unwrap(),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+// trees are slow to build, so they are only built when ranking by tree, for the top `breadth`
+// openers by entropy
+pub fn leaderboard(
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    metric: LeaderboardMetric,
+    top: usize,
+    breadth: Breadth,
+    depth: usize,
+    hard: bool,
+) -> Vec<OpenerScore> {
+    let mut scores: Vec<_> = (0..guesses.len())
+        .into_par_iter()
+        .map(|idx| {
+            let word = guesses.word(idx);
+            OpenerScore {
+                word,
+                entropy: entropy(word, answers),
+                remaining: expected_remaining(word, answers),
+                tree: None,
+                two_ply: 0.0,
+            }
+        })
+        .collect();
+    match metric {
+        LeaderboardMetric::Remaining => scores.sort_by(|a, b| {
+            a.remaining
+                .partial_cmp(&b.remaining)
+                .unwrap()
+                .then_with(|| a.word.cmp(&b.word))
+        }),
+        _ => scores.sort_by(|a, b| {
+            b.entropy
+                .partial_cmp(&a.entropy)
+                .unwrap()
+                .then_with(|| a.word.cmp(&b.word))
+        }),
+    }
+    let trees = if metric == LeaderboardMetric::Tree {
+        breadth.get().max(top)
+    } else {
+        top
+    };
+    scores.truncate(trees);
+    for score in scores.iter_mut() {
+        if metric == LeaderboardMetric::Tree {
+            score.tree = tree_mean(
+                score.word,
+                guesses,
+                answers,
+                breadth,
+                depth,
+                hard,
+                &CancellationToken::default(),
+            );
+        }
+        score.two_ply = two_ply_entropy(score.word, guesses, answers);
+    }
+    if metric == LeaderboardMetric::Tree {
+        scores.sort_by(|a, b| by_tree(a, b).then_with(|| a.word.cmp(&b.word)));
+        scores.truncate(top);
+    }
+    scores
+}
+
+#[cfg(test)]
+mod test {
+    use crate::analysis::{leaderboard, two_ply_entropy, LeaderboardMetric};
+    use crate::{entropy, expected_remaining, WordDictionary};
+
+    #[test]
+    fn remaining() {
+        let answers = WordDictionary::new(b"lightmightnightsight");
+        assert_eq!(expected_remaining(*b"light", &answers), 2.5);
+        let guesses = WordDictionary::new(b"lightmnsxx");
+        let board = leaderboard(
+            &guesses,
+            &answers,
+            LeaderboardMetric::Remaining,
+            1,
+            10.into(),
+            6,
+            false,
+        );
+        assert_eq!(board[0].word, *b"mnsxx");
+        assert_eq!(board[0].remaining, 1.0);
+        assert_eq!(board[0].tree, None);
+    }
+
+    #[test]
+    fn tree() {
+        let answers = WordDictionary::new(b"lightmightnightsight");
+        let guesses = WordDictionary::new(b"lightmnsxx");
+        let board = leaderboard(
+            &guesses,
+            &answers,
+            LeaderboardMetric::Tree,
+            1,
+            10.into(),
+            6,
+            false,
+        );
+        // mnsxx tells every answer apart, and then each takes a second guess
+        assert_eq!(board[0].word, *b"mnsxx");
+        assert_eq!(board[0].tree, Some(2.0));
+    }
+
+    #[test]
+    fn two_ply() {
+        let answers = WordDictionary::new(b"lightmightnightsight");
+        let guesses = WordDictionary::new(b"lightmnsxx");
+        // light leaves three answers that mnsxx splits completely
+        assert_eq!(
+            two_ply_entropy(*b"light", &guesses, &answers),
+            entropy(*b"light", &answers) + 0.75 * 3f64.log2()
+        );
+        assert_eq!(two_ply_entropy(*b"mnsxx", &guesses, &answers), 2.0);
+    }
+}
@@ -1,14 +1,110 @@
-#![feature(array_chunks)]
+// the prelude is the stable api, changed only with a major version. everything else at the root,
+// and the io and analysis modules, may change between minor versions, and items hidden from the
+// docs are internal to the solver
+pub mod prelude {
+    pub use crate::{
+        pattern_distribution, play_game, rank_guesses, solve, solve_from_state, solve_seeded,
+        solve_with_progress, Alphabet, Breadth, CancellationToken, Constraints, Dictionary,
+        GameState, GuessPolicy, MatchRules, RankedGuess, Solution, SolutionNode, SolveProgress,
+        Strategy, Tiebreak, Word, WordDictionary, WordMatch,
+    };
+    #[cfg(feature = "embedded-lists")]
+    pub use crate::{LEGAL_ANSWERS, LEGAL_GUESSES};
+}
 
-pub use config::*;
+pub use alphabet::*;
+pub use answer_set::*;
+pub use breadth::*;
+pub use cancel::*;
+pub use constraint::*;
 pub use dict::*;
+pub use game::*;
+pub use hint::*;
+pub use memory::*;
+pub use rank::*;
+pub use refine::*;
+pub use rng::*;
 pub use solve::*;
+pub use strategy::*;
+pub use throttle::*;
+pub use word::*;
 pub use word_match::*;
 
+mod alphabet;
+#[cfg(feature = "analysis")]
+mod analyze;
+mod answer_set;
+#[cfg(feature = "analysis")]
+mod anti;
+#[cfg(feature = "io")]
+mod book;
+mod breadth;
+mod cancel;
+#[cfg(feature = "cli")]
 mod config;
+mod constraint;
+#[cfg(feature = "io")]
+mod dag;
 mod dict;
+#[cfg(feature = "analysis")]
+mod difficulty;
+mod game;
+mod hint;
+#[cfg(feature = "analysis")]
+mod leaderboard;
+#[cfg(feature = "io")]
+mod lists;
+mod memory;
+mod rank;
+mod refine;
+#[cfg(feature = "io")]
+mod render;
+mod rng;
+#[cfg(feature = "analysis")]
+mod share;
 mod solve;
+#[cfg(feature = "analysis")]
+mod stats;
+mod strategy;
+mod throttle;
+#[cfg(feature = "analysis")]
+mod tournament;
+#[cfg(feature = "analysis")]
+mod train;
+mod word;
 mod word_match;
 
-pub const LEGAL_GUESSES: &'static [u8] = include_bytes!("../../../guesses.txt");
-pub const LEGAL_ANSWERS: &'static [u8] = include_bytes!("../../../answers.txt");
+// reading and writing trees, opening books and word lists, and rendering them for people
+#[cfg(feature = "io")]
+pub mod io {
+    pub use crate::book::*;
+    pub use crate::lists::*;
+    pub use crate::render::*;
+}
+
+// the command line config and the commands it selects
+#[cfg(feature = "cli")]
+pub mod cli {
+    pub use crate::config::*;
+}
+
+// studies built on the solver: transcripts, tournaments, leaderboards, traps and training
+#[cfg(feature = "analysis")]
+pub mod analysis {
+    pub use crate::analyze::*;
+    pub use crate::anti::*;
+    pub use crate::difficulty::*;
+    pub use crate::leaderboard::*;
+    pub use crate::share::*;
+    pub use crate::stats::*;
+    pub use crate::tournament::*;
+    pub use crate::train::*;
+}
+
+#[cfg(feature = "embedded-lists")]
+pub const LEGAL_GUESSES: &[u8] = include_bytes!("../../../guesses.txt");
+#[cfg(feature = "embedded-lists")]
+pub const LEGAL_ANSWERS: &[u8] = include_bytes!("../../../answers.txt");
+// an easy mode tree for the embedded lists, in the dag format, so assist needs no search
+#[cfg(feature = "embedded-tree")]
+pub const DEFAULT_TREE: &[u8] = include_bytes!("../../../default-tree.txt");
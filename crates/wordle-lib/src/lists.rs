@@ -0,0 +1,264 @@
+use crate::{Alphabet, Dictionary, WordDictionary};
+#[cfg(feature = "embedded-lists")]
+use crate::{LEGAL_ANSWERS, LEGAL_GUESSES};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ListInfo {
+    pub words: usize,
+    pub sha256: String,
+}
+
+impl ListInfo {
+    pub fn of(dict: &WordDictionary) -> Self {
+        Self {
+            words: dict.len(),
+            sha256: dict.sha256(),
+        }
+    }
+}
+
+#[cfg(feature = "embedded-lists")]
+pub fn embedded_guesses_info() -> ListInfo {
+    ListInfo::of(&WordDictionary::new(LEGAL_GUESSES))
+}
+
+#[cfg(feature = "embedded-lists")]
+pub fn embedded_answers_info() -> ListInfo {
+    ListInfo::of(&WordDictionary::new(LEGAL_ANSWERS))
+}
+
+fn parse_word_array(js: &str) -> Option<Vec<[u8; 5]>> {
+    let mut words = Vec::new();
+    let mut rest = js;
+    loop {
+        let word = rest.strip_prefix('"')?.get(..5)?;
+        rest = rest.get(6..)?.strip_prefix('"')?;
+        words.push(word.as_bytes().try_into().ok()?);
+        match rest.as_bytes().first()? {
+            b',' => rest = &rest[1..],
+            b']' => return Some(words),
+            _ => return None,
+        }
+    }
+}
+
+pub fn extract_word_lists(js: &str) -> Result<(WordDictionary, WordDictionary), String> {
+    let mut arrays: Vec<Vec<[u8; 5]>> = js
+        .match_indices('[')
+        .filter_map(|(idx, _)| parse_word_array(&js[idx + 1..]))
+        .filter(|words| words.len() >= 1000)
+        .collect();
+    if arrays.len() != 2 {
+        return Err(format!("expected 2 word lists, found {}", arrays.len()));
+    }
+    arrays.sort_by_key(|words| words.len());
+    let mut answers = arrays.swap_remove(0);
+    let mut guesses = arrays.swap_remove(0);
+    for words in [&answers, &guesses] {
+        let mut seen = HashSet::with_capacity(words.len());
+        for word in words.iter() {
+            if !word.iter().all(u8::is_ascii_lowercase) {
+                return Err(format!("invalid word: {}", String::from_utf8_lossy(word)));
+            }
+            if !seen.insert(word) {
+                return Err(format!("duplicate word: {}", String::from_utf8_lossy(word)));
+            }
+        }
+    }
+    guesses.extend_from_slice(&answers);
+    guesses.sort_unstable();
+    guesses.dedup();
+    answers.sort_unstable();
+    let to_dict = |words: Vec<[u8; 5]>| {
+        let mut dict = WordDictionary::with_capacity(words.len());
+        words.into_iter().for_each(|word| dict.push(word));
+        dict
+    };
+    Ok((to_dict(answers), to_dict(guesses)))
+}
+
+pub fn to_lines(dict: &WordDictionary) -> String {
+    let mut lines = String::with_capacity(6 * dict.len());
+    dict.for_each(|word| {
+        lines.push_str(std::str::from_utf8(&word).unwrap());
+        lines.push('\n');
+    });
+    lines
+}
+
+// word list loaders, behind io so the core types read no text
+impl WordDictionary {
+    pub fn from_lines(text: &str) -> Result<Self, String> {
+        Self::from_lines_in(text, &Alphabet::english())
+    }
+
+    // rejects duplicates as well as anything that isn't a word, naming the line at fault
+    pub fn from_lines_in(text: &str, alphabet: &Alphabet) -> Result<Self, String> {
+        Self::read_lines(text, alphabet, false).map(|(dict, _)| dict)
+    }
+
+    // keeps the first copy of each duplicate, also returning how many copies were dropped
+    pub fn from_lines_deduped(text: &str, alphabet: &Alphabet) -> Result<(Self, usize), String> {
+        Self::read_lines(text, alphabet, true)
+    }
+
+    fn read_lines(text: &str, alphabet: &Alphabet, dedupe: bool) -> Result<(Self, usize), String> {
+        let mut dict = Self::with_capacity(text.len() / 6);
+        let mut first_line = HashMap::new();
+        let mut dropped = 0;
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let word = alphabet
+                .encode(line)
+                .map_err(|err| format!("line {}: {}", idx + 1, err))?;
+            if let Some(first) = first_line.get(&word) {
+                if !dedupe {
+                    return Err(format!(
+                        "line {}: {} duplicates line {}",
+                        idx + 1,
+                        line,
+                        first
+                    ));
+                }
+                dropped += 1;
+                continue;
+            }
+            first_line.insert(word, idx + 1);
+            dict.push(word);
+        }
+        Ok((dict, dropped))
+    }
+
+    // lines hold a word and optionally its frequency, and the top most frequent words are kept
+    pub fn from_frequencies(text: &str, alphabet: &Alphabet, top: usize) -> Result<Self, String> {
+        let mut words = Vec::new();
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut fields = line.split_whitespace();
+            let word = alphabet
+                .encode(fields.next().unwrap())
+                .map_err(|err| format!("line {}: {}", idx + 1, err))?;
+            let frequency: f64 = match fields.next() {
+                Some(frequency) => frequency
+                    .parse()
+                    .map_err(|_| format!("line {}: invalid frequency: {}", idx + 1, line))?,
+                None => 0.0,
+            };
+            words.push((word, frequency));
+        }
+        // stable, so words without frequencies keep their file order
+        words.sort_by(|a, b| b.1.total_cmp(&a.1));
+        let mut dict = Self::with_capacity(top.min(words.len()));
+        for (word, _) in words.into_iter().take(top) {
+            dict.push(word);
+        }
+        Ok(dict)
+    }
+}
+
+// a byte count like 4096, 512M or 8G, in powers of 1024
+pub fn parse_memory_size(s: &str) -> Result<usize, String> {
+    let s = s.trim();
+    let (digits, shift) = match s.char_indices().last() {
+        Some((idx, 'k' | 'K')) => (&s[..idx], 10),
+        Some((idx, 'm' | 'M')) => (&s[..idx], 20),
+        Some((idx, 'g' | 'G')) => (&s[..idx], 30),
+        Some((idx, 't' | 'T')) => (&s[..idx], 40),
+        _ => (s, 0),
+    };
+    digits
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_mul(1 << shift))
+        .ok_or_else(|| format!("bad memory size: {}", s))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::io::{extract_word_lists, parse_memory_size};
+    use crate::{Alphabet, Dictionary, WordDictionary};
+
+    #[test]
+    fn lines() {
+        let english = &Alphabet::english();
+        let dict = WordDictionary::from_lines_in("might\n\n light \n", english).unwrap();
+        assert_eq!(dict.len(), 2);
+        assert_eq!(dict.sorted().get(0), Some(*b"light"));
+        assert_eq!(
+            WordDictionary::from_lines_in("light\nmight\n\nLight\n", english).err(),
+            Some("line 4: Light duplicates line 1".to_string())
+        );
+        let (deduped, dropped) =
+            WordDictionary::from_lines_deduped("light\nmight\nlight\n", english).unwrap();
+        assert_eq!((deduped.len(), dropped), (2, 1));
+        assert!(WordDictionary::from_lines_in("light\nlights\n", english)
+            .err()
+            .is_some_and(|err| err.starts_with("line 2:")));
+        assert!(WordDictionary::from_lines_in("light\nnaïve\n", english)
+            .err()
+            .is_some_and(|err| err.starts_with("line 2:")));
+    }
+
+    #[test]
+    fn frequencies() {
+        let text = "light 10\nmight 300\nnight 2e3\nsight\n";
+        let dict = WordDictionary::from_frequencies(text, &Alphabet::english(), 2).unwrap();
+        assert_eq!(dict.len(), 2);
+        assert_eq!(dict.get(0), Some(*b"night"));
+        assert_eq!(dict.get(1), Some(*b"might"));
+    }
+
+    // a js array of n distinct words, from "aaaaa" up in base 26
+    fn word_array(n: usize, offset: usize) -> String {
+        let words: Vec<_> = (offset..offset + n)
+            .map(|mut idx| {
+                let mut word = [b'a'; 5];
+                for c in word.iter_mut().rev() {
+                    *c += (idx % 26) as u8;
+                    idx /= 26;
+                }
+                format!("\"{}\"", std::str::from_utf8(&word).unwrap())
+            })
+            .collect();
+        format!("[{}]", words.join(","))
+    }
+
+    #[test]
+    fn word_lists() {
+        let js = format!(
+            "var a={},b=[1,2],c={};",
+            word_array(1200, 0),
+            word_array(1000, 5000)
+        );
+        let (answers, guesses) = extract_word_lists(&js).unwrap();
+        assert_eq!(answers.len(), 1000);
+        assert_eq!(answers.get(0), Some(*b"aahki"));
+        // the guesses take in the answers too
+        assert_eq!(guesses.len(), 2200);
+        assert!(extract_word_lists(&word_array(1200, 0)).is_err());
+        let duplicated = format!("{}{}", word_array(1000, 0), word_array(1001, 0));
+        let duplicated = duplicated.replacen("\"aaaab\"", "\"aaaaa\"", 1);
+        assert_eq!(
+            extract_word_lists(&duplicated).err(),
+            Some("duplicate word: aaaaa".to_string())
+        );
+    }
+
+    #[test]
+    fn memory_size() {
+        assert_eq!(parse_memory_size("4096"), Ok(4096));
+        assert_eq!(parse_memory_size("512M"), Ok(512 << 20));
+        assert_eq!(parse_memory_size("8g"), Ok(8 << 30));
+        assert!(parse_memory_size("").is_err());
+        assert!(parse_memory_size("G").is_err());
+        assert!(parse_memory_size("1.5G").is_err());
+        assert!(parse_memory_size("99999999999T").is_err());
+    }
+}
@@ -0,0 +1,81 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicIsize, AtomicUsize, Ordering};
+
+// the heap the process may use, usize::MAX for no limit
+static LIMIT: AtomicUsize = AtomicUsize::new(usize::MAX);
+// live heap bytes allocated since the limit was set. blocks from before then are freed
+// uncounted, so this can dip below zero
+static USED: AtomicIsize = AtomicIsize::new(0);
+
+// the system allocator, counting live bytes once a limit is set so that the solvers can see
+// when they near it. binaries install it as their #[global_allocator]. the count is one atomic
+// add on a shared counter per allocation from any thread, so --max-memory isn't free
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            count(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            count(layout.size() as isize);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        count(-(layout.size() as isize));
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            count(new_size as isize - layout.size() as isize);
+        }
+        new_ptr
+    }
+}
+
+fn count(bytes: isize) {
+    if LIMIT.load(Ordering::Relaxed) != usize::MAX {
+        USED.fetch_add(bytes, Ordering::Relaxed);
+    }
+}
+
+pub fn set_memory_limit(bytes: usize) {
+    LIMIT.store(bytes, Ordering::Relaxed);
+}
+
+pub fn memory_used() -> usize {
+    USED.load(Ordering::Relaxed).max(0) as usize
+}
+
+// whether caches should give way: past nine tenths of the limit the solvers evict what they
+// have memoized and recompute partitions rather than hold them
+pub(crate) fn memory_pressure() -> bool {
+    near_limit(memory_used(), LIMIT.load(Ordering::Relaxed))
+}
+
+fn near_limit(used: usize, limit: usize) -> bool {
+    limit != usize::MAX && used >= limit / 10 * 9
+}
+
+#[cfg(test)]
+mod test {
+    use crate::memory::near_limit;
+
+    #[test]
+    fn pressure() {
+        assert!(!near_limit(1 << 40, usize::MAX));
+        assert!(!near_limit(800, 1000));
+        assert!(near_limit(900, 1000));
+        assert!(near_limit(2000, 1000));
+    }
+}
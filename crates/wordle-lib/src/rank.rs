@@ -0,0 +1,176 @@
+use crate::{Dictionary, WordDictionary, WordMatch};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+pub struct RankedGuess {
+    pub word: [u8; 5],
+    // the expected bits of information the guess gains
+    pub entropy: f64,
+    pub candidate: bool,
+    pub greens: f64,
+    pub yellows: f64,
+    // the chance the guess is the answer
+    pub solve_chance: f64,
+    // the chance the guess leaves at most two answers, counting solving it
+    pub narrow_chance: f64,
+}
+
+pub fn pattern_counts(guess: [u8; 5], answers: &WordDictionary) -> [u32; 243] {
+    let mut counts = [0; 243];
+    answers.for_each(|answer| counts[answers.word_match(guess, answer).0 as usize] += 1);
+    counts
+}
+
+// the chance of each pattern when the answer is drawn from answers, in proportion to weights
+// indexed like answers if given and uniformly otherwise. weights must be one per answer, and
+// with no answers or no weight to draw by every pattern gets 0
+pub fn pattern_distribution(
+    guess: [u8; 5],
+    answers: &WordDictionary,
+    weights: Option<&[f64]>,
+) -> [f64; 243] {
+    let mut distribution = [0.0; 243];
+    match weights {
+        Some(weights) => {
+            assert_eq!(weights.len(), answers.len(), "one weight per answer");
+            for (idx, &weight) in weights.iter().enumerate() {
+                distribution[answers.word_match(guess, answers.word(idx)).0 as usize] += weight;
+            }
+        }
+        None => {
+            for (p, count) in distribution.iter_mut().zip(pattern_counts(guess, answers)) {
+                *p = count as f64;
+            }
+        }
+    }
+    let total: f64 = distribution.iter().sum();
+    if total == 0.0 {
+        return distribution;
+    }
+    for p in &mut distribution {
+        *p /= total;
+    }
+    distribution
+}
+
+pub fn entropy(guess: [u8; 5], answers: &WordDictionary) -> f64 {
+    entropy_of(&pattern_counts(guess, answers), answers.len())
+}
+
+fn entropy_of(counts: &[u32; 243], total: usize) -> f64 {
+    let total = total as f64;
+    counts
+        .iter()
+        .filter(|&&count| count != 0)
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+// the mean number of answers left after the guess
+pub fn expected_remaining(guess: [u8; 5], answers: &WordDictionary) -> f64 {
+    let total = answers.len() as f64;
+    pattern_counts(guess, answers)
+        .iter()
+        .map(|&count| count as f64 * count as f64 / total)
+        .sum()
+}
+
+// the mean number of green and yellow tiles the guess shows
+pub fn expected_colors(guess: [u8; 5], answers: &WordDictionary) -> (f64, f64) {
+    colors_of(&pattern_counts(guess, answers), answers.len())
+}
+
+fn colors_of(counts: &[u32; 243], total: usize) -> (f64, f64) {
+    let (mut greens, mut yellows) = (0, 0);
+    for (wm, &count) in WordMatch::all().zip(counts) {
+        greens += wm.greens() * count;
+        yellows += wm.yellows() * count;
+    }
+    let total = total as f64;
+    (greens as f64 / total, yellows as f64 / total)
+}
+
+fn narrow_chance_of(counts: &[u32; 243], total: usize) -> f64 {
+    let narrowed: u32 = counts.iter().filter(|&&count| count <= 2).sum();
+    narrowed as f64 / total as f64
+}
+
+pub fn largest_bucket(guess: [u8; 5], answers: &WordDictionary) -> u32 {
+    pattern_counts(guess, answers)
+        .into_iter()
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn rank_guesses(guesses: &WordDictionary, answers: &WordDictionary) -> Vec<RankedGuess> {
+    let mut ranked: Vec<_> = (0..guesses.len())
+        .into_par_iter()
+        .map(|idx| {
+            let word = guesses.word(idx);
+            let counts = pattern_counts(word, answers);
+            let (greens, yellows) = colors_of(&counts, answers.len());
+            let candidate = answers.index_of(word).is_some();
+            RankedGuess {
+                word,
+                entropy: entropy_of(&counts, answers.len()),
+                candidate,
+                greens,
+                yellows,
+                solve_chance: if candidate {
+                    1.0 / answers.len() as f64
+                } else {
+                    0.0
+                },
+                narrow_chance: narrow_chance_of(&counts, answers.len()),
+            }
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.entropy
+            .partial_cmp(&a.entropy)
+            .unwrap()
+            .then_with(|| b.candidate.cmp(&a.candidate))
+            .then_with(|| a.word.cmp(&b.word))
+    });
+    ranked
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{pattern_distribution, rank_guesses, WordDictionary, WordMatch};
+
+    #[test]
+    fn distribution() {
+        let answers = WordDictionary::new(b"lightmightcrane");
+        let uniform = pattern_distribution(*b"light", &answers, None);
+        assert_eq!(uniform[WordMatch::CORRECT.index() as usize], 1.0 / 3.0);
+        assert_eq!(uniform.iter().filter(|&&p| p > 0.0).count(), 3);
+        let weighted = pattern_distribution(*b"light", &answers, Some(&[2.0, 1.0, 1.0]));
+        assert_eq!(weighted[WordMatch::CORRECT.index() as usize], 0.5);
+        assert_eq!(weighted[WordMatch::ABSENT.index() as usize], 0.25);
+        let unweighted = pattern_distribution(*b"light", &answers, Some(&[0.0; 3]));
+        assert!(unweighted.iter().all(|&p| p == 0.0));
+        let empty = pattern_distribution(*b"light", &WordDictionary::new(b""), None);
+        assert!(empty.iter().all(|&p| p == 0.0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn distribution_weights() {
+        let answers = WordDictionary::new(b"lightmightcrane");
+        pattern_distribution(*b"light", &answers, Some(&[1.0, 1.0]));
+    }
+
+    #[test]
+    fn chances() {
+        let answers = WordDictionary::new(b"lightmightnightsightcrane");
+        let guesses = WordDictionary::new(b"lightmnsxx");
+        let ranked = rank_guesses(&guesses, &answers);
+        let mnsxx = ranked.iter().find(|guess| &guess.word == b"mnsxx").unwrap();
+        assert_eq!((mnsxx.solve_chance, mnsxx.narrow_chance), (0.0, 1.0));
+        let light = ranked.iter().find(|guess| &guess.word == b"light").unwrap();
+        assert_eq!((light.solve_chance, light.narrow_chance), (0.2, 0.4));
+    }
+}
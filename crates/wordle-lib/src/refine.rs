@@ -0,0 +1,138 @@
+use crate::{
+    rank_guesses, solve_from_state_with_progress, Breadth, CancellationToken, Dictionary,
+    GameState, GuessPolicy, Tiebreak, WordDictionary,
+};
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::thread;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Suggestion {
+    pub word: [u8; 5],
+    // the root breadth of the search behind the suggestion, or None for the entropy ranking
+    pub breadth: Option<usize>,
+    // the mean number of guesses still to come with the searched tree
+    pub mean: Option<f64>,
+}
+
+// an anytime suggestion: the highest entropy guess straight away, then the opener of a tree
+// searched at root breadth 1, 2, 4 and so on, finishing with the full breadth schedule. every
+// round reports its result, and cancelling stops after the round in progress
+pub fn refine_suggestions<F>(
+    state: &GameState,
+    guesses: &WordDictionary,
+    breadth: Breadth,
+    depth: usize,
+    hard: bool,
+    cancel: &CancellationToken,
+    mut update: F,
+) where
+    F: FnMut(Suggestion),
+{
+    let ranked = rank_guesses(guesses, &state.answers);
+    let Some(best) = ranked.first() else {
+        return;
+    };
+    update(Suggestion {
+        word: best.word,
+        breadth: None,
+        mean: None,
+    });
+    let mut width = 1;
+    loop {
+        let round = if width < breadth.get() {
+            Breadth::from(width)
+        } else {
+            breadth
+        };
+        let solution = solve_from_state_with_progress(
+            state,
+            guesses,
+            round,
+            depth,
+            hard,
+            Tiebreak::default(),
+            GuessPolicy::default(),
+            cancel,
+            |_| {},
+        );
+        if cancel.is_cancelled() {
+            return;
+        }
+        if let Some(solution) = solution {
+            update(Suggestion {
+                word: solution.guess(),
+                breadth: Some(round.get()),
+                mean: Some(solution.size() as f64 / state.answers.len() as f64),
+            });
+        }
+        if width >= breadth.get() {
+            return;
+        }
+        width *= 2;
+    }
+}
+
+// runs refine_suggestions on its own thread, sending each suggestion to the receiver. the
+// search stops when the token is cancelled or the receiver is dropped
+pub fn spawn_refinement(
+    state: GameState,
+    guesses: WordDictionary,
+    breadth: Breadth,
+    depth: usize,
+    hard: bool,
+) -> (Receiver<Suggestion>, CancellationToken) {
+    let (tx, rx) = mpsc::channel();
+    let cancel = CancellationToken::default();
+    let token = cancel.clone();
+    thread::spawn(move || {
+        refine_suggestions(
+            &state,
+            &guesses,
+            breadth,
+            depth,
+            hard,
+            &token,
+            |suggestion| {
+                if tx.send(suggestion).is_err() {
+                    token.cancel();
+                }
+            },
+        );
+    });
+    (rx, cancel)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        refine_suggestions, spawn_refinement, Breadth, CancellationToken, GameState,
+        WordDictionary, WordMatch,
+    };
+
+    #[test]
+    fn refine() {
+        let answers = WordDictionary::new(b"lightmightnightsighttightfightcrane");
+        let mut state = GameState::new(&answers);
+        state.guess(*b"crane", WordMatch::from(*b"crane", *b"light"));
+        let breadth: Breadth = "4,2".parse().unwrap();
+        let mut updates = Vec::new();
+        refine_suggestions(
+            &state,
+            &answers,
+            breadth,
+            6,
+            false,
+            &CancellationToken::default(),
+            |suggestion| updates.push(suggestion),
+        );
+        let breadths: Vec<_> = updates.iter().map(|update| update.breadth).collect();
+        assert_eq!(breadths, [None, Some(1), Some(2), Some(4)]);
+        assert!(updates[1..]
+            .windows(2)
+            .all(|pair| pair[1].mean.unwrap() <= pair[0].mean.unwrap()));
+        let (rx, _) = spawn_refinement(state, answers, breadth, 6, false);
+        let last = rx.iter().last().unwrap();
+        assert_eq!(last, updates[3]);
+    }
+}
@@ -0,0 +1,337 @@
+use crate::{Alphabet, Solution, SolutionNode, WordMatch};
+use std::fmt;
+use std::fmt::Display;
+use std::io;
+use std::io::Write;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum TreeFormat {
+    #[default]
+    Flat,
+    Tree,
+    Markdown,
+    Html,
+}
+
+impl FromStr for TreeFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flat" => Ok(Self::Flat),
+            "tree" => Ok(Self::Tree),
+            "markdown" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            _ => Err(format!("unknown format: {}", s)),
+        }
+    }
+}
+
+// how feedback is drawn. high contrast swaps green and yellow for orange and blue as the daily
+// game's colorblind mode does, and ascii draws no color at all
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ColorScheme {
+    #[default]
+    Standard,
+    HighContrast,
+    Ascii,
+}
+
+impl FromStr for ColorScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "standard" => Ok(Self::Standard),
+            "high-contrast" => Ok(Self::HighContrast),
+            "ascii" => Ok(Self::Ascii),
+            _ => Err(format!("unknown color scheme: {}", s)),
+        }
+    }
+}
+
+impl Display for ColorScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Standard => "standard",
+            Self::HighContrast => "high-contrast",
+            Self::Ascii => "ascii",
+        })
+    }
+}
+
+const GREEN: &str = "\x1b[30;42m";
+const YELLOW: &str = "\x1b[30;43m";
+const ORANGE: &str = "\x1b[30;48;5;208m";
+const BLUE: &str = "\x1b[30;48;5;39m";
+const GRAY: &str = "\x1b[97;100m";
+const RESET: &str = "\x1b[0m";
+
+impl ColorScheme {
+    // the guess's letters on colored tiles, or the feedback letters in ascii
+    pub fn tiles(self, alphabet: &Alphabet, guess: [u8; 5], wm: WordMatch) -> String {
+        let (correct, present) = match self {
+            Self::Standard => (GREEN, YELLOW),
+            Self::HighContrast => (ORANGE, BLUE),
+            Self::Ascii => return wm.to_string(),
+        };
+        let mut line = String::new();
+        for (c, m) in alphabet.decode(guess).chars().zip(wm.to_string().chars()) {
+            line.push_str(match m {
+                'c' => correct,
+                'p' => present,
+                _ => GRAY,
+            });
+            line.push(c);
+        }
+        line.push_str(RESET);
+        line
+    }
+
+    // the feedback as squares, like a shared result
+    pub fn squares(self, wm: WordMatch) -> String {
+        let (correct, present) = match self {
+            Self::Standard => ('🟩', '🟨'),
+            Self::HighContrast => ('🟧', '🟦'),
+            Self::Ascii => return wm.to_string(),
+        };
+        wm.to_string()
+            .chars()
+            .map(|m| match m {
+                'c' => correct,
+                'p' => present,
+                _ => '⬛',
+            })
+            .collect()
+    }
+}
+
+impl Solution {
+    pub fn write_markdown<W: Write>(
+        &self,
+        alphabet: &Alphabet,
+        scheme: ColorScheme,
+        w: &mut W,
+    ) -> io::Result<()> {
+        self.root().write_markdown(alphabet, scheme, w)
+    }
+
+    pub fn write_html<W: Write>(
+        &self,
+        alphabet: &Alphabet,
+        scheme: ColorScheme,
+        w: &mut W,
+    ) -> io::Result<()> {
+        self.root().write_html(alphabet, scheme, w)
+    }
+
+    pub fn print_tree(&self, alphabet: &Alphabet, scheme: ColorScheme) {
+        self.root().print_tree(alphabet, scheme)
+    }
+}
+
+impl SolutionNode<'_> {
+    fn has_children(&self) -> bool {
+        self.children().any(|(wm, _)| wm != WordMatch::CORRECT)
+    }
+
+    fn stats(&self) -> String {
+        let answers = self.answer_count();
+        if answers == 1 {
+            "1 answer".to_string()
+        } else {
+            format!(
+                "{} answers, {:.2} guesses on average, at most {}",
+                answers,
+                self.size() as f32 / answers as f32,
+                self.max_guesses()
+            )
+        }
+    }
+
+    pub fn write_markdown<W: Write>(
+        &self,
+        alphabet: &Alphabet,
+        scheme: ColorScheme,
+        w: &mut W,
+    ) -> io::Result<()> {
+        writeln!(w, "# Start with **{}**", alphabet.decode(self.guess()))?;
+        writeln!(w)?;
+        writeln!(w, "{}", self.stats())?;
+        writeln!(w)?;
+        self.write_markdown_children(alphabet, scheme, w)
+    }
+
+    fn write_markdown_children<W: Write>(
+        &self,
+        alphabet: &Alphabet,
+        scheme: ColorScheme,
+        w: &mut W,
+    ) -> io::Result<()> {
+        for (wm, sub) in self.children() {
+            if wm == WordMatch::CORRECT {
+                continue;
+            }
+            let summary = format!(
+                "{} then <b>{}</b> ({})",
+                scheme.squares(wm),
+                alphabet.decode(sub.guess()),
+                sub.stats()
+            );
+            if !sub.has_children() {
+                writeln!(w, "- {}", summary)?;
+            } else {
+                writeln!(w, "<details><summary>{}</summary>", summary)?;
+                writeln!(w, "<blockquote>")?;
+                writeln!(w)?;
+                sub.write_markdown_children(alphabet, scheme, w)?;
+                writeln!(w)?;
+                writeln!(w, "</blockquote>")?;
+                writeln!(w, "</details>")?;
+                writeln!(w)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn write_html<W: Write>(
+        &self,
+        alphabet: &Alphabet,
+        scheme: ColorScheme,
+        w: &mut W,
+    ) -> io::Result<()> {
+        let guess = alphabet.decode(self.guess());
+        writeln!(w, "<!DOCTYPE html>")?;
+        writeln!(w, "<html>")?;
+        writeln!(w, "<head>")?;
+        writeln!(w, "<meta charset=\"utf-8\">")?;
+        writeln!(w, "<title>Wordle strategy: {}</title>", guess)?;
+        writeln!(
+            w,
+            "<style>ul {{ list-style: none; }} b {{ font-family: monospace; }}</style>"
+        )?;
+        writeln!(w, "</head>")?;
+        writeln!(w, "<body>")?;
+        writeln!(w, "<h1>Start with <b>{}</b></h1>", guess)?;
+        writeln!(w, "<p>{}</p>", self.stats())?;
+        self.write_html_children(alphabet, scheme, w)?;
+        writeln!(w, "</body>")?;
+        writeln!(w, "</html>")
+    }
+
+    fn write_html_children<W: Write>(
+        &self,
+        alphabet: &Alphabet,
+        scheme: ColorScheme,
+        w: &mut W,
+    ) -> io::Result<()> {
+        writeln!(w, "<ul>")?;
+        for (wm, sub) in self.children() {
+            if wm == WordMatch::CORRECT {
+                continue;
+            }
+            let summary = format!(
+                "{} then <b>{}</b> ({})",
+                scheme.squares(wm),
+                alphabet.decode(sub.guess()),
+                sub.stats()
+            );
+            if !sub.has_children() {
+                writeln!(w, "<li>{}</li>", summary)?;
+            } else {
+                writeln!(w, "<li><details><summary>{}</summary>", summary)?;
+                sub.write_html_children(alphabet, scheme, w)?;
+                writeln!(w, "</details></li>")?;
+            }
+        }
+        writeln!(w, "</ul>")
+    }
+
+    pub fn print_tree(&self, alphabet: &Alphabet, scheme: ColorScheme) {
+        println!(
+            "{} ({})",
+            alphabet.decode(self.guess()),
+            self.answer_count()
+        );
+        self.print_children(alphabet, scheme, 1);
+    }
+
+    fn print_children(&self, alphabet: &Alphabet, scheme: ColorScheme, depth: usize) {
+        for (wm, sub) in self.children() {
+            if wm == WordMatch::CORRECT {
+                continue;
+            }
+            println!(
+                "{:indent$}{} {} ({})",
+                "",
+                scheme.tiles(alphabet, self.guess(), wm),
+                alphabet.decode(sub.guess()),
+                sub.answer_count(),
+                indent = 2 * depth
+            );
+            sub.print_children(alphabet, scheme, depth + 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::io::ColorScheme;
+    use crate::{solve, Alphabet, CancellationToken, Dictionary, WordDictionary, WordMatch};
+
+    #[test]
+    fn answer_count() {
+        let answers = WordDictionary::new(b"lightmightnightsighttightfightcrane");
+        let solution = solve(
+            &answers,
+            &answers,
+            10.into(),
+            6,
+            false,
+            &CancellationToken::default(),
+        )
+        .unwrap();
+        assert_eq!(solution.answer_count(), answers.len());
+    }
+
+    #[test]
+    fn html() {
+        let answers = WordDictionary::new(b"lightmightnightsighttightfightcrane");
+        let solution = solve(
+            &answers,
+            &answers,
+            10.into(),
+            6,
+            false,
+            &CancellationToken::default(),
+        )
+        .unwrap();
+        let mut html = Vec::new();
+        solution
+            .write_html(&Alphabet::english(), ColorScheme::Standard, &mut html)
+            .unwrap();
+        let html = String::from_utf8(html).unwrap();
+        assert_eq!(html.matches("<li>").count(), html.matches("</li>").count());
+        assert_eq!(
+            html.matches("<details>").count(),
+            html.matches("</details>").count()
+        );
+        // every answer but the opener is reached through a list entry
+        assert_eq!(html.matches("<li>").count(), answers.len() - 1);
+    }
+
+    #[test]
+    fn schemes() {
+        let wm: WordMatch = "cpaac".parse().unwrap();
+        assert_eq!(ColorScheme::Standard.squares(wm), "🟩🟨⬛⬛🟩");
+        assert_eq!(ColorScheme::HighContrast.squares(wm), "🟧🟦⬛⬛🟧");
+        assert_eq!(ColorScheme::Ascii.squares(wm), "cpaac");
+        let alphabet = Alphabet::english();
+        assert_eq!(ColorScheme::Ascii.tiles(&alphabet, *b"crane", wm), "cpaac");
+        assert!(ColorScheme::HighContrast
+            .tiles(&alphabet, *b"crane", wm)
+            .starts_with("\x1b[30;48;5;208mc"));
+        assert_eq!("high-contrast".parse(), Ok(ColorScheme::HighContrast));
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{Dictionary, WordDictionary, WordMatch};
+use std::ops::ControlFlow;
+
+pub fn parse_share_grid(text: &str) -> Result<Vec<WordMatch>, String> {
+    let mut grid = Vec::new();
+    for line in text.lines() {
+        let mut row = String::with_capacity(5);
+        for c in line.trim().chars() {
+            match c {
+                '⬛' | '⬜' => row.push('a'),
+                '🟨' | '🟦' => row.push('p'),
+                '🟩' | '🟧' => row.push('c'),
+                // variation selectors and zero-width joiners some clients paste
+                '\u{fe0f}' | '\u{200d}' => {}
+                _ => {
+                    row.clear();
+                    break;
+                }
+            }
+        }
+        if !row.is_empty() {
+            grid.push(row.parse()?);
+        }
+    }
+    if grid.is_empty() {
+        Err("no share grid found".to_string())
+    } else {
+        Ok(grid)
+    }
+}
+
+pub fn consistent_guesses(
+    wm: WordMatch,
+    answer: [u8; 5],
+    guesses: &WordDictionary,
+) -> WordDictionary {
+    guesses.filter(|guess| WordMatch::from(guess, answer) == wm)
+}
+
+pub fn consistent_answers(
+    grid: &[WordMatch],
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+) -> WordDictionary {
+    answers.filter(|answer| {
+        grid.iter().all(|&wm| {
+            guesses
+                .try_for_each(|guess| {
+                    if WordMatch::from(guess, answer) == wm {
+                        ControlFlow::Break(())
+                    } else {
+                        ControlFlow::Continue(())
+                    }
+                })
+                .is_break()
+        })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::analysis::parse_share_grid;
+    use crate::WordMatch;
+
+    #[test]
+    fn parse() {
+        let grid =
+            parse_share_grid("Wordle 245 3/6\n\n⬛🟨⬛⬛⬛\n🟩⬜🟨⬛🟩\n🟩🟩🟩🟩🟩\n").unwrap();
+        let grid: Vec<_> = grid.iter().map(WordMatch::to_string).collect();
+        assert_eq!(grid, ["apaaa", "capac", "ccccc"]);
+    }
+}
@@ -1,92 +1,206 @@
-use crate::{Dictionary, WordDictionary, WordMatch};
+use crate::{
+    bucket_difficulty, memory_pressure, throttle, Alphabet, AnswerSet, Breadth, CancellationToken,
+    CanonicalKey, Constraints, Dictionary, GameState, MatchRules, PartitionSummary, WordDictionary,
+    WordMatch,
+};
 use rayon::iter::IntoParallelIterator;
 use rayon::iter::ParallelIterator;
-use std::cmp::Ordering;
+use std::cmp::{Ordering, Reverse};
 use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+use std::fmt::Display;
+use std::mem;
 use std::ops::ControlFlow;
 use std::ptr;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex};
 
-pub struct Guess {
+// the least remaining depth at which slow_solution solves a guess's buckets in parallel
+const PARALLEL_BUCKET_DEPTH: usize = 4;
+// buckets, or a root, with at most this many answers are solved exactly, trying every guess at
+// every node instead of the breadth schedule's best few
+const ENDGAME_ANSWERS: usize = 20;
+
+pub(crate) struct Guess {
     word: [u8; 5],
     entropy: f64,
     partition: HashMap<WordMatch, WordDictionary>,
+    summary: PartitionSummary,
 }
 
 impl Guess {
     pub fn new(guess: [u8; 5], answers: &WordDictionary) -> Self {
-        let partition = answers.partition(guess);
+        let (partition, summary) = answers.summarized_partition(guess);
+        Self::from_parts(guess, partition, summary)
+    }
+
+    // the bucket sizes alone, for when memory is short; the partition is rebuilt when needed
+    fn summarized(guess: [u8; 5], answers: &WordDictionary) -> Self {
+        Self::from_parts(guess, HashMap::new(), answers.partition_summary(guess))
+    }
+
+    fn from_parts(
+        guess: [u8; 5],
+        partition: HashMap<WordMatch, WordDictionary>,
+        summary: PartitionSummary,
+    ) -> Self {
         Guess {
             word: guess,
-            entropy: partition.values().map(|d| f64::log2(d.len() as f64)).sum(),
+            entropy: summary
+                .sizes
+                .iter()
+                .map(|(_, size)| f64::log2(*size as f64))
+                .sum(),
             partition,
+            summary,
         }
     }
 
-    fn fast_solution(&self, depth: usize) -> Option<Solution> {
-        if self.entropy < 1.0 && depth > 1 && self.partition.contains_key(&WordMatch::CORRECT) {
-            let dict = &self.partition[&WordMatch::CORRECT];
-            Some(Solution {
-                guess: dict.word(0),
-                size: 2 * self.partition.len() as u16 - 1,
-                solution: self
-                    .partition
+    // frees the partition while the guess waits its turn, for slow_tree to redo it
+    fn release_partition(&mut self) {
+        self.partition = HashMap::new();
+    }
+
+    fn fast_solution(&self, answers: &WordDictionary, depth: usize) -> Option<Tree> {
+        if self.entropy < 1.0 && depth > 1 && self.summary.has_correct {
+            let rebuilt;
+            let partition = if self.partition.is_empty() {
+                rebuilt = answers.partition(self.word);
+                &rebuilt
+            } else {
+                &self.partition
+            };
+            let dict = &partition[&WordMatch::CORRECT];
+            Some(Tree::node(
+                dict.word(0),
+                2 * self.summary.buckets() as u16 - 1,
+                partition
                     .iter()
-                    .map(|(wm, dict)| {
-                        (
-                            *wm,
-                            Solution {
-                                guess: dict.word(0),
-                                size: 1,
-                                solution: Vec::new(),
-                            },
-                        )
-                    })
+                    .map(|(wm, dict)| (*wm, Tree::leaf(dict.word(0))))
                     .collect(),
-            })
+            ))
         } else {
             None
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn slow_solution(
         self,
         guesses: &WordDictionary,
         answers: &WordDictionary,
-        breadth: usize,
+        breadth: Breadth,
         depth: usize,
         hard: bool,
+        tiebreak: Tiebreak,
+        policy: GuessPolicy,
+        cancel: &CancellationToken,
     ) -> Option<Solution> {
-        let partitions = if hard && !ptr::eq(guesses, answers) {
-            guesses.partition(self.word)
+        let root = Constraints::default();
+        let consistent = ConsistentGuesses::default();
+        let hard = hard.then_some(HardMode {
+            constraints: &root,
+            consistent: &consistent,
+        });
+        self.slow_tree(
+            guesses, answers, breadth, depth, hard, tiebreak, policy, cancel,
+        )
+        .map(Solution::from)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(
+                guess = %String::from_utf8_lossy(&self.word),
+                depth = depth,
+                answers = answers.len()
+            )
+        )
+    )]
+    #[allow(clippy::too_many_arguments)]
+    fn slow_tree(
+        self,
+        guesses: &WordDictionary,
+        answers: &WordDictionary,
+        breadth: Breadth,
+        depth: usize,
+        hard: Option<HardMode<'_>>,
+        tiebreak: Tiebreak,
+        policy: GuessPolicy,
+        cancel: &CancellationToken,
+    ) -> Option<Tree> {
+        let word = self.word;
+        let partition = if self.partition.is_empty() {
+            answers.partition(word)
         } else {
-            HashMap::new()
+            self.partition
         };
-        let solution = Solution {
-            guess: self.word,
-            size: 0,
-            solution: Vec::with_capacity(self.partition.len()),
+        let solve_bucket = |(wm, dict): (WordMatch, WordDictionary)| {
+            let narrowed = hard.map(|hard| hard.narrow(guesses, answers, word, wm));
+            let guesses = match &narrowed {
+                Some((_, Some(consistent))) => &**consistent,
+                Some((_, None)) => &dict,
+                None => guesses,
+            };
+            let hard = narrowed
+                .as_ref()
+                .zip(hard)
+                .map(|((constraints, _), hard)| HardMode {
+                    constraints,
+                    ..hard
+                });
+            let sub_solution = if dict.len() <= ENDGAME_ANSWERS {
+                endgame_tree(
+                    guesses,
+                    &dict,
+                    depth,
+                    hard,
+                    tiebreak,
+                    policy,
+                    cancel,
+                    u16::MAX,
+                )
+            } else {
+                solve_tree(
+                    guesses,
+                    &dict,
+                    breadth,
+                    depth,
+                    hard,
+                    tiebreak,
+                    policy,
+                    cancel,
+                    &|_| {},
+                    &mut |_| ControlFlow::Continue(()),
+                )
+            };
+            sub_solution.map(|sub_solution| (wm, dict.len() as u16, sub_solution))
         };
-        self.partition
-            .into_iter()
-            .try_fold(solution, |mut solution, (wm, dict)| {
-                let guesses = if hard && ptr::eq(guesses, answers) {
-                    &dict
-                } else if hard {
-                    partitions.get(&wm).unwrap()
-                } else {
-                    guesses
-                };
-                let sub_solution = solve(guesses, &dict, breadth, depth, hard);
-
-                sub_solution.map(|sub_solution| {
-                    solution.size += dict.len() as u16;
-                    if wm != WordMatch::CORRECT {
-                        solution.size += sub_solution.size;
-                    }
-                    solution.solution.push((wm, sub_solution));
-                    solution
-                })
-            })
+        // near the root buckets are large enough to be worth solving in parallel, deeper down
+        // the candidates above already keep every thread busy
+        let buckets: Option<Vec<_>> = if depth >= PARALLEL_BUCKET_DEPTH {
+            // largest first, so the slowest buckets don't start last
+            let mut partition: Vec<_> = partition.into_iter().collect();
+            partition.sort_by_key(|(wm, dict)| (Reverse(bucket_difficulty(dict.len())), wm.0));
+            partition.into_par_iter().map(solve_bucket).collect()
+        } else {
+            partition.into_iter().map(solve_bucket).collect()
+        };
+        let buckets = buckets?;
+        let mut size = 0;
+        let mut children = Vec::with_capacity(buckets.len());
+        for (wm, len, sub_solution) in buckets {
+            size += len;
+            if wm != WordMatch::CORRECT {
+                size += sub_solution.size();
+            }
+            children.push((wm, sub_solution));
+        }
+        Some(Tree::node(word, size, children))
     }
 }
 
@@ -114,95 +228,1315 @@ impl Ord for Guess {
     }
 }
 
-pub struct Solution {
-    pub guess: [u8; 5],
+// how to choose between guesses whose trees take the same total number of guesses
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Tiebreak {
+    // the alphabetically first guess
+    #[default]
+    Word,
+    // a guess that could be the answer, since a lucky hit ends the game
+    PreferAnswers,
+}
+
+impl Tiebreak {
+    fn rank(self, tree: &Tree) -> bool {
+        match self {
+            Self::Word => false,
+            Self::PreferAnswers => !tree.child_matches().any(|wm| wm == WordMatch::CORRECT),
+        }
+    }
+
+    // the rank of any tree opening with guess, before it is built
+    fn rank_guess(self, guess: [u8; 5], answers: &WordDictionary) -> bool {
+        self == Self::PreferAnswers && !answers.contains(guess)
+    }
+}
+
+impl FromStr for Tiebreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "word" => Ok(Self::Word),
+            "answers" => Ok(Self::PreferAnswers),
+            _ => Err(format!("unknown tiebreak: {}", s)),
+        }
+    }
+}
+
+impl Display for Tiebreak {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Word => "word",
+            Self::PreferAnswers => "answers",
+        })
+    }
+}
+
+// where each node looks for candidate guesses
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum GuessPolicy {
+    // every allowed guess
+    #[default]
+    Full,
+    // the answers still possible, falling back to every allowed guess only when none of them
+    // solve the node. much cheaper, and rarely far from optimal
+    AnswersFirst,
+}
+
+impl FromStr for GuessPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "full" => Ok(Self::Full),
+            "answers-first" => Ok(Self::AnswersFirst),
+            _ => Err(format!("unknown guess policy: {}", s)),
+        }
+    }
+}
+
+impl Display for GuessPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Full => "full",
+            Self::AnswersFirst => "answers-first",
+        })
+    }
+}
+
+// a node where two trees for the same answers pick different guesses
+pub struct Divergence {
+    // the guesses and feedback leading to the node
+    pub path: Vec<([u8; 5], WordMatch)>,
+    pub this: [u8; 5],
+    pub other: [u8; 5],
+    // total guesses the other subtree needs minus this one's
+    pub delta: i32,
+}
+
+#[derive(Clone, Copy)]
+struct TreeNode {
+    guess: [u8; 5],
+    // the feedback leading here from the parent
+    wm: WordMatch,
+    children: u8,
+    size: u16,
+    // the nodes in this subtree, this one included
+    len: u32,
+}
+
+// the tree as the search builds it, before it is packed into a Solution. its nodes are one
+// vector in preorder, so finishing a node moves its children's nodes in after it instead of
+// keeping an allocation for every node of every candidate tree
+#[derive(Clone)]
+pub(crate) struct Tree {
+    nodes: Vec<TreeNode>,
+}
+
+impl Tree {
+    fn leaf(guess: [u8; 5]) -> Self {
+        Self::node(guess, 1, Vec::new())
+    }
+
+    fn node(guess: [u8; 5], size: u16, mut children: Vec<(WordMatch, Tree)>) -> Self {
+        // partitions are hash maps, so fix the child order to keep output reproducible
+        children.sort_unstable_by_key(|(wm, _)| wm.0);
+        let len = 1 + children
+            .iter()
+            .map(|(_, child)| child.nodes.len())
+            .sum::<usize>();
+        let mut nodes = Vec::with_capacity(len);
+        nodes.push(TreeNode {
+            guess,
+            wm: WordMatch::CORRECT,
+            children: children.len() as u8,
+            size,
+            len: len as u32,
+        });
+        for (wm, child) in children {
+            let start = nodes.len();
+            nodes.extend(child.nodes);
+            nodes[start].wm = wm;
+        }
+        Self { nodes }
+    }
+
+    pub fn guess(&self) -> [u8; 5] {
+        self.nodes[0].guess
+    }
+
+    pub fn size(&self) -> u16 {
+        self.nodes[0].size
+    }
+
+    // where each child of the node at idx starts
+    fn children_of(&self, idx: usize) -> impl Iterator<Item = usize> + '_ {
+        let mut next = idx + 1;
+        (0..self.nodes[idx].children).map(move |_| {
+            let child = next;
+            next += self.nodes[child].len as usize;
+            child
+        })
+    }
+
+    fn child_matches(&self) -> impl Iterator<Item = WordMatch> + '_ {
+        self.children_of(0).map(|idx| self.nodes[idx].wm)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct Node {
+    pub guess: u16,
     pub size: u16,
-    pub solution: Vec<(WordMatch, Solution)>,
+    // the feedback leading here from the parent
+    pub wm: WordMatch,
+    pub children: u8,
+    pub first_child: u32,
+}
+
+// nodes live in one vector with each node's children next to each other, and guesses index
+// into a dictionary of the words the tree uses, so big trees need no allocation per node.
+// children may point at the same nodes, so a tree read from a dag keeps its sharing
+#[derive(Clone)]
+pub struct Solution {
+    pub(crate) words: WordDictionary,
+    pub(crate) nodes: Vec<Node>,
+}
+
+impl From<Tree> for Solution {
+    fn from(tree: Tree) -> Self {
+        let mut this = Self {
+            words: WordDictionary::with_capacity(0),
+            nodes: Vec::new(),
+        };
+        let mut ids = HashMap::new();
+        let mut id = |word: [u8; 5], words: &mut WordDictionary| {
+            *ids.entry(word).or_insert_with(|| {
+                words.push(word);
+                u16::try_from(words.len() - 1).expect("too many distinct guesses")
+            })
+        };
+        this.nodes.reserve_exact(tree.nodes.len());
+        let guess = id(tree.guess(), &mut this.words);
+        this.nodes.push(Node {
+            guess,
+            size: tree.size(),
+            wm: WordMatch::CORRECT,
+            children: 0,
+            first_child: 0,
+        });
+        // breadth first, so every node's children are laid out together
+        let mut queue = std::collections::VecDeque::from([(0, 0)]);
+        while let Some((idx, tree_idx)) = queue.pop_front() {
+            this.nodes[idx].first_child = this.nodes.len() as u32;
+            this.nodes[idx].children = tree.nodes[tree_idx].children;
+            for child in tree.children_of(tree_idx) {
+                let node = tree.nodes[child];
+                let guess = id(node.guess, &mut this.words);
+                queue.push_back((this.nodes.len(), child));
+                this.nodes.push(Node {
+                    guess,
+                    size: node.size,
+                    wm: node.wm,
+                    children: 0,
+                    first_child: 0,
+                });
+            }
+        }
+        this
+    }
 }
 
 impl Solution {
-    pub fn print(&self, line: &mut String) {
+    pub fn root(&self) -> SolutionNode<'_> {
+        SolutionNode {
+            solution: self,
+            idx: 0,
+        }
+    }
+
+    pub fn guess(&self) -> [u8; 5] {
+        self.root().guess()
+    }
+
+    pub fn size(&self) -> u16 {
+        self.root().size()
+    }
+
+    pub fn answer_count(&self) -> usize {
+        self.root().answer_count()
+    }
+
+    pub fn max_guesses(&self) -> usize {
+        self.root().max_guesses()
+    }
+
+    pub fn lookup(&self, path: &[WordMatch]) -> Option<SolutionNode<'_>> {
+        self.root().lookup(path)
+    }
+
+    // the node a game reaches by playing the tree's guesses, or none once a guess leaves it
+    pub fn follow(&self, history: &[([u8; 5], WordMatch)]) -> Option<SolutionNode<'_>> {
+        history.iter().try_fold(self.root(), |node, (guess, wm)| {
+            if node.guess() == *guess {
+                node.lookup(&[*wm])
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn diff(&self, other: &Solution) -> Vec<Divergence> {
+        let mut divergences = Vec::new();
+        self.root()
+            .diff_into(other.root(), &mut Vec::new(), &mut divergences);
+        divergences
+    }
+
+    pub fn path_for_answer(&self, answer: [u8; 5], rules: MatchRules) -> Vec<([u8; 5], WordMatch)> {
+        let mut path = Vec::new();
+        let mut node = Some(self.root());
+        while let Some(solution) = node {
+            let wm = WordMatch::with_rules(solution.guess(), answer, rules);
+            path.push((solution.guess(), wm));
+            if wm == WordMatch::CORRECT {
+                break;
+            }
+            node = solution.lookup(&[wm]);
+        }
+        path
+    }
+
+    // answers the tree misses or needs more than max_guesses guesses for
+    pub fn failures(&self, answers: &WordDictionary, max_guesses: usize) -> usize {
+        let mut failures = 0;
+        answers.for_each(|answer| {
+            let path = self.path_for_answer(answer, answers.rules());
+            if path.len() > max_guesses
+                || path.last().map(|(_, wm)| *wm) != Some(WordMatch::CORRECT)
+            {
+                failures += 1;
+            }
+        });
+        failures
+    }
+
+    pub fn print(&self, alphabet: &Alphabet, line: &mut String) {
+        self.root().print(alphabet, line)
+    }
+
+    pub fn print_trace(&self, alphabet: &Alphabet, answers: &WordDictionary) {
+        answers.for_each(|answer| {
+            let mut line = alphabet.decode(answer);
+            line.push(':');
+            for (guess, _) in self.path_for_answer(answer, answers.rules()) {
+                line.push(' ');
+                line.push_str(&alphabet.decode(guess));
+            }
+            println!("{}", line);
+        });
+    }
+}
+
+// a subtree of a solution, with words resolved on access
+#[derive(Clone, Copy)]
+pub struct SolutionNode<'a> {
+    solution: &'a Solution,
+    idx: usize,
+}
+
+impl<'a> SolutionNode<'a> {
+    fn node(&self) -> &'a Node {
+        &self.solution.nodes[self.idx]
+    }
+
+    pub fn guess(&self) -> [u8; 5] {
+        self.solution.words.word(self.node().guess as usize)
+    }
+
+    pub fn size(&self) -> u16 {
+        self.node().size
+    }
+
+    pub fn is_leaf(&self) -> bool {
+        self.node().children == 0
+    }
+
+    pub fn answer_count(&self) -> usize {
+        if self.is_leaf() {
+            1
+        } else {
+            self.children().map(|(_, sub)| sub.answer_count()).sum()
+        }
+    }
+
+    // the most guesses needed for any answer reaching this node
+    pub fn max_guesses(&self) -> usize {
+        1 + self
+            .children()
+            .filter(|(wm, _)| *wm != WordMatch::CORRECT)
+            .map(|(_, sub)| sub.max_guesses())
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn children(&self) -> impl Iterator<Item = (WordMatch, SolutionNode<'a>)> {
+        let solution = self.solution;
+        let first = self.node().first_child as usize;
+        (first..first + self.node().children as usize)
+            .map(move |idx| (solution.nodes[idx].wm, SolutionNode { solution, idx }))
+    }
+
+    pub fn lookup(&self, path: &[WordMatch]) -> Option<SolutionNode<'a>> {
+        path.iter().try_fold(*self, |node, wm| {
+            node.children()
+                .find(|(child_wm, _)| child_wm == wm)
+                .map(|(_, child)| child)
+        })
+    }
+
+    fn diff_into(
+        &self,
+        other: SolutionNode<'_>,
+        path: &mut Vec<([u8; 5], WordMatch)>,
+        divergences: &mut Vec<Divergence>,
+    ) {
+        if self.guess() != other.guess() {
+            divergences.push(Divergence {
+                path: path.clone(),
+                this: self.guess(),
+                other: other.guess(),
+                delta: other.size() as i32 - self.size() as i32,
+            });
+            return;
+        }
+        for (wm, child) in self.children() {
+            if let Some(other_child) = other.lookup(&[wm]) {
+                path.push((self.guess(), wm));
+                child.diff_into(other_child, path, divergences);
+                path.pop();
+            }
+        }
+    }
+
+    fn print(&self, alphabet: &Alphabet, line: &mut String) {
+        let len = line.len();
         line.push(' ');
-        line.push_str(std::str::from_utf8(&self.guess).unwrap());
-        if self.solution.is_empty() {
+        line.push_str(&alphabet.decode(self.guess()));
+        if self.is_leaf() {
             println!("{}", line);
         } else {
-            for (wm, sub) in self.solution.iter() {
-                if *wm == WordMatch::CORRECT {
+            for (wm, sub) in self.children() {
+                if wm == WordMatch::CORRECT {
                     println!("{}", line);
                 } else {
+                    let len = line.len();
                     line.push(' ');
                     line.push_str(&wm.to_string());
-                    sub.print(line);
-                    line.drain(line.len() - 6..);
+                    sub.print(alphabet, line);
+                    line.truncate(len);
                 }
             }
         }
-        line.drain(line.len() - 6..);
+        line.truncate(len);
     }
 }
 
+pub struct SolveProgress {
+    pub evaluated: usize,
+    pub candidates: usize,
+    pub incumbent: Option<u16>,
+}
+
+// a guess looked at while choosing which openers of the root to search
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RootCandidate {
+    pub word: [u8; 5],
+    pub entropy: f64,
+    pub buckets: usize,
+}
+
 pub fn solve(
     guesses: &WordDictionary,
     answers: &WordDictionary,
-    breadth: usize,
+    breadth: Breadth,
     depth: usize,
     hard: bool,
+    cancel: &CancellationToken,
 ) -> Option<Solution> {
+    solve_with_progress(
+        guesses,
+        answers,
+        breadth,
+        depth,
+        hard,
+        Tiebreak::default(),
+        GuessPolicy::default(),
+        cancel,
+        |_| {},
+    )
+}
+
+// solves a game that must open with first_guess, among the answers that satisfy constraints,
+// as when chained games carry the previous answer over as the next opener
+#[allow(clippy::too_many_arguments)]
+pub fn solve_seeded(
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    first_guess: [u8; 5],
+    constraints: &Constraints,
+    breadth: Breadth,
+    depth: usize,
+    hard: bool,
+    tiebreak: Tiebreak,
+    policy: GuessPolicy,
+    cancel: &CancellationToken,
+) -> Option<Solution> {
+    let limited = ptr::eq(guesses, answers);
+    let answers = &constraints.filter(answers);
+    if answers.is_empty() || depth == 0 {
+        return None;
+    }
+    let guesses = if limited {
+        answers
+    } else if hard {
+        &constraints.filter(guesses)
+    } else {
+        guesses
+    };
+    // the opener is already chosen, so the search starts a level down
+    Guess::new(first_guess, answers)
+        .slow_tree(
+            guesses,
+            answers,
+            breadth.deeper(),
+            depth - 1,
+            hard.then_some(HardMode {
+                constraints,
+                consistent: &ConsistentGuesses::default(),
+            }),
+            tiebreak,
+            policy,
+            cancel,
+        )
+        .map(Solution::from)
+}
+
+// solves the rest of a game in progress, where depth still counts the guesses already made
+pub fn solve_from_state(
+    state: &GameState,
+    guesses: &WordDictionary,
+    breadth: Breadth,
+    depth: usize,
+    hard: bool,
+    cancel: &CancellationToken,
+) -> Option<Solution> {
+    solve_from_state_with_progress(
+        state,
+        guesses,
+        breadth,
+        depth,
+        hard,
+        Tiebreak::default(),
+        GuessPolicy::default(),
+        cancel,
+        |_| {},
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn solve_from_state_with_progress<F>(
+    state: &GameState,
+    guesses: &WordDictionary,
+    breadth: Breadth,
+    depth: usize,
+    hard: bool,
+    tiebreak: Tiebreak,
+    policy: GuessPolicy,
+    cancel: &CancellationToken,
+    progress: F,
+) -> Option<Solution>
+where
+    F: Fn(SolveProgress) + Sync,
+{
+    solve_from_state_streaming(
+        state,
+        guesses,
+        breadth,
+        depth,
+        hard,
+        tiebreak,
+        policy,
+        cancel,
+        progress,
+        |_| ControlFlow::Continue(()),
+    )
+}
+
+// also streams every guess considered at the root to consider, as it is looked at. breaking
+// skips that guess and the rest, and the search goes on with the ones kept so far
+#[allow(clippy::too_many_arguments)]
+pub fn solve_from_state_streaming<F, G>(
+    state: &GameState,
+    guesses: &WordDictionary,
+    breadth: Breadth,
+    depth: usize,
+    hard: bool,
+    tiebreak: Tiebreak,
+    policy: GuessPolicy,
+    cancel: &CancellationToken,
+    progress: F,
+    mut consider: G,
+) -> Option<Solution>
+where
+    F: Fn(SolveProgress) + Sync,
+    G: FnMut(RootCandidate) -> ControlFlow<()>,
+{
+    let depth = depth
+        .checked_sub(state.history.len())
+        .filter(|&depth| depth > 0)?;
+    if state.answers.is_empty() {
+        return None;
+    }
+    // hard mode guesses must be consistent with all the feedback so far
+    let mut constraints = Constraints::default();
+    for (prev, wm) in state.history.iter() {
+        constraints.apply(*prev, *wm);
+    }
+    let hard_guesses;
+    let guesses = if hard {
+        hard_guesses = constraints.filter(guesses);
+        &hard_guesses
+    } else {
+        guesses
+    };
+    let consistent = ConsistentGuesses::default();
+    let hard = hard.then_some(HardMode {
+        constraints: &constraints,
+        consistent: &consistent,
+    });
+    // the guesses already made used up the top of the breadth schedule
+    let breadth = (0..state.history.len()).fold(breadth, |breadth, _| breadth.deeper());
+    solve_tree(
+        guesses,
+        &state.answers,
+        breadth,
+        depth,
+        hard,
+        tiebreak,
+        policy,
+        cancel,
+        &progress,
+        &mut consider,
+    )
+    .map(Solution::from)
+}
+
+// reports each finished top level candidate along with the best total found so far
+#[allow(clippy::too_many_arguments)]
+pub fn solve_with_progress<F>(
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    breadth: Breadth,
+    depth: usize,
+    hard: bool,
+    tiebreak: Tiebreak,
+    policy: GuessPolicy,
+    cancel: &CancellationToken,
+    progress: F,
+) -> Option<Solution>
+where
+    F: Fn(SolveProgress) + Sync,
+{
+    let root = Constraints::default();
+    solve_tree(
+        guesses,
+        answers,
+        breadth,
+        depth,
+        hard.then_some(HardMode {
+            constraints: &root,
+            consistent: &ConsistentGuesses::default(),
+        }),
+        tiebreak,
+        policy,
+        cancel,
+        &progress,
+        &mut |_| ControlFlow::Continue(()),
+    )
+    .map(Solution::from)
+}
+
+// hard mode's view of a node: the constraints the feedback on the way down puts on every later
+// guess, which the node's guesses already meet, and the search's lists of consistent guesses
+#[derive(Clone, Copy)]
+struct HardMode<'a> {
+    constraints: &'a Constraints,
+    consistent: &'a ConsistentGuesses,
+}
+
+// the hard mode guesses meeting each set of constraints the search has reached, so a bucket
+// reached again, by another candidate or another order of the same feedback, doesn't filter the
+// guesses again. the constraints decide the list on their own: a node's guesses are the ones
+// meeting its constraints, less at most some that can't split its answers and so can't split a
+// bucket of them either
+#[derive(Default)]
+struct ConsistentGuesses(Mutex<HashMap<Constraints, Option<Arc<WordDictionary>>>>);
+
+impl HardMode<'_> {
+    // the constraints once guess got feedback wm too, and the node's guesses that still meet
+    // them. the list is None when the guesses are the answers, whose bucket is already at hand,
+    // or when only the bucket's answers are left, as with a restricted guess list
+    fn narrow(
+        self,
+        guesses: &WordDictionary,
+        answers: &WordDictionary,
+        guess: [u8; 5],
+        wm: WordMatch,
+    ) -> (Constraints, Option<Arc<WordDictionary>>) {
+        let mut constraints = self.constraints.clone();
+        constraints.apply(guess, wm);
+        if ptr::eq(guesses, answers) {
+            return (constraints, None);
+        }
+        if let Some(consistent) = self.consistent.0.lock().unwrap().get(&constraints) {
+            return (constraints, consistent.clone());
+        }
+        let consistent = constraints.filter(guesses);
+        let consistent = (!consistent.is_empty()).then(|| Arc::new(consistent));
+        let mut cache = self.consistent.0.lock().unwrap();
+        if memory_pressure() {
+            cache.clear();
+        } else {
+            cache.insert(constraints.clone(), consistent.clone());
+        }
+        (constraints, consistent)
+    }
+}
+
+// the best tree for a small bucket within max_size, trying the answers first under
+// GuessPolicy::AnswersFirst just as solve_tree does
+#[allow(clippy::too_many_arguments)]
+fn endgame_tree(
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    depth: usize,
+    hard: Option<HardMode<'_>>,
+    tiebreak: Tiebreak,
+    policy: GuessPolicy,
+    cancel: &CancellationToken,
+    max_size: u16,
+) -> Option<Tree> {
+    if policy == GuessPolicy::AnswersFirst && answers.len() < guesses.len() {
+        // an answer opener wins whenever there is one, even if another guess would fit the
+        // budget
+        if let Some(tree) = exact_tree(
+            answers,
+            guesses,
+            answers,
+            depth,
+            hard,
+            tiebreak,
+            policy,
+            cancel,
+            u16::MAX,
+        ) {
+            return Some(tree).filter(|tree| tree.size() <= max_size);
+        }
+    }
+    exact_tree(
+        guesses, guesses, answers, depth, hard, tiebreak, policy, cancel, max_size,
+    )
+}
+
+// the best tree opening with one of candidates, trying them all in order of their partition's
+// lower bound until none left can beat the best so far
+#[allow(clippy::too_many_arguments)]
+fn exact_tree(
+    candidates: &WordDictionary,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    depth: usize,
+    hard: Option<HardMode<'_>>,
+    tiebreak: Tiebreak,
+    policy: GuessPolicy,
+    cancel: &CancellationToken,
+    max_size: u16,
+) -> Option<Tree> {
     if answers.len() == 1 {
-        return Some(Solution {
-            guess: answers.word(0),
-            size: 1,
-            solution: Vec::new(),
+        return Some(Tree::leaf(answers.word(0))).filter(|_| max_size >= 1);
+    }
+    if depth == 1 || cancel.is_cancelled() {
+        return None;
+    }
+    throttle();
+    if let Some(tree) = discriminator(candidates, answers, tiebreak) {
+        return Some(tree).filter(|tree| tree.size() <= max_size);
+    }
+    let mut bounds = Vec::new();
+    // a guess that can't split these answers can't split any bucket of them either, so when the
+    // candidates are the guesses the buckets below only look at the ones that did
+    let splits = ptr::eq(candidates, guesses);
+    let mut splitting = WordDictionary::with_capacity(if splits { candidates.len() } else { 0 });
+    candidates.for_each(|guess| {
+        let mut sizes = [0; 243];
+        answers.for_each(|answer| sizes[answers.word_match(guess, answer).0 as usize] += 1);
+        let mut buckets = 0;
+        let mut bound = 0;
+        for (wm, &size) in WordMatch::all().zip(&sizes) {
+            if size > 0 {
+                buckets += 1;
+                bound += bucket_lower_bound(wm, size);
+            }
+        }
+        // a guess that learns nothing is never part of an optimal tree
+        if buckets > 1 {
+            bounds.push((bound, guess));
+            if splits {
+                splitting.push(guess);
+            }
+        }
+    });
+    let guesses = if splits { &splitting } else { guesses };
+    bounds.sort_unstable();
+    let mut best: Option<Tree> = None;
+    for (bound, guess) in bounds {
+        let limit = best
+            .as_ref()
+            .map_or(max_size as u32, |best| best.size() as u32);
+        if bound > limit {
+            break;
+        }
+        // a guess behind the best on the tiebreak or alphabetically has to beat it on size. the
+        // best may have come from a smaller bound, so this doesn't end the search
+        let ties_win = best.as_ref().is_none_or(|best| {
+            (tiebreak.rank_guess(guess, answers), guess) < (tiebreak.rank(best), best.guess())
         });
+        let limit = if ties_win { limit } else { limit - 1 };
+        if bound > limit {
+            continue;
+        }
+        let Some(tree) = exact_guess_tree(
+            guess,
+            guesses,
+            answers,
+            depth,
+            hard,
+            tiebreak,
+            policy,
+            cancel,
+            limit as u16,
+        ) else {
+            continue;
+        };
+        let key = |tree: &Tree| (tree.size(), tiebreak.rank(tree), tree.guess());
+        if best.as_ref().is_none_or(|best| key(&tree) < key(best)) {
+            best = Some(tree);
+        }
+    }
+    best.filter(|_| !cancel.is_cancelled())
+}
+
+// the answers guess fails to tell apart, counting all but one answer of each bucket, or
+// anything over limit once it is passed
+fn collisions(guess: [u8; 5], answers: &WordDictionary, limit: usize) -> usize {
+    let mut seen = [false; 243];
+    let mut collisions = 0;
+    let cf = answers.try_for_each(|answer| {
+        let wm = answers.word_match(guess, answer);
+        if mem::replace(&mut seen[wm.0 as usize], true) {
+            collisions += 1;
+            if collisions > limit {
+                return ControlFlow::Break(());
+            }
+        }
+        ControlFlow::Continue(())
+    });
+    match cf {
+        ControlFlow::Break(()) => limit + 1,
+        ControlFlow::Continue(()) => collisions,
     }
-    if depth == 1 {
+}
+
+// a guess among candidates that tells all the answers apart, when that is known to be the best
+// tree. an answer that does meets the lower bound of 2n - 1, and the alphabetically first one
+// is taken. any answer of a pair qualifies, as does most often one of three. failing that, a
+// guess that isn't an answer costs 2n, which only an answer leaving a single pair together
+// ties, so it is taken unless such an answer would win the tiebreak
+fn discriminator(
+    candidates: &WordDictionary,
+    answers: &WordDictionary,
+    tiebreak: Tiebreak,
+) -> Option<Tree> {
+    if answers.len() > 243 {
         return None;
     }
-    let mut best_guesses = BinaryHeap::with_capacity(breadth);
-    let cf = guesses.try_for_each(|guess| {
-        let guess = Guess::new(guess, answers);
-        if guess.partition.len() == 1 {
+    let mut best: Option<[u8; 5]> = None;
+    let mut near: Option<[u8; 5]> = None;
+    answers.for_each(|guess| {
+        if best.is_some_and(|best| best < guess) || !candidates.contains(guess) {
+            return;
+        }
+        match collisions(guess, answers, 1) {
+            0 => best = Some(guess),
+            1 if near.is_none_or(|near| guess < near) => near = Some(guess),
+            _ => {}
+        }
+    });
+    let size = match best {
+        Some(_) => 2 * answers.len() as u16 - 1,
+        None => {
+            candidates.for_each(|guess| {
+                if best.is_none_or(|best| guess < best)
+                    && !answers.contains(guess)
+                    && collisions(guess, answers, 0) == 0
+                {
+                    best = Some(guess);
+                }
+            });
+            let guess = best?;
+            if near.is_some_and(|near| tiebreak == Tiebreak::PreferAnswers || near < guess) {
+                return None;
+            }
+            2 * answers.len() as u16
+        }
+    };
+    let guess = best?;
+    let mut children = Vec::with_capacity(answers.len());
+    answers
+        .for_each(|answer| children.push((answers.word_match(guess, answer), Tree::leaf(answer))));
+    Some(Tree::node(guess, size, children))
+}
+
+// the best tree opening with guess, or None when it can't stay within max_size
+#[allow(clippy::too_many_arguments)]
+fn exact_guess_tree(
+    guess: [u8; 5],
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    depth: usize,
+    hard: Option<HardMode<'_>>,
+    tiebreak: Tiebreak,
+    policy: GuessPolicy,
+    cancel: &CancellationToken,
+    max_size: u16,
+) -> Option<Tree> {
+    let partition = answers.partition(guess);
+    let mut remaining: u32 = partition
+        .iter()
+        .map(|(wm, dict)| bucket_lower_bound(*wm, dict.len()))
+        .sum();
+    let mut size = 0;
+    let mut children = Vec::with_capacity(partition.len());
+    for (wm, dict) in partition {
+        remaining -= bucket_lower_bound(wm, dict.len());
+        let len = dict.len() as u16;
+        let sub_solution = if wm == WordMatch::CORRECT {
+            Tree::leaf(guess)
+        } else {
+            // what this bucket may take while the others still get their lower bounds
+            let budget = (max_size as u32).checked_sub(size as u32 + len as u32 + remaining)?;
+            let narrowed = hard.map(|hard| hard.narrow(guesses, answers, guess, wm));
+            let guesses = match &narrowed {
+                Some((_, Some(consistent))) => &**consistent,
+                Some((_, None)) => &dict,
+                None => guesses,
+            };
+            let hard = narrowed
+                .as_ref()
+                .zip(hard)
+                .map(|((constraints, _), hard)| HardMode {
+                    constraints,
+                    ..hard
+                });
+            let sub_solution = endgame_tree(
+                guesses,
+                &dict,
+                depth - 1,
+                hard,
+                tiebreak,
+                policy,
+                cancel,
+                budget.min(u16::MAX as u32) as u16,
+            )?;
+            size += sub_solution.size();
+            sub_solution
+        };
+        size += len;
+        children.push((wm, sub_solution));
+    }
+    Some(Tree::node(guess, size, children)).filter(|tree| tree.size() <= max_size)
+}
+
+// the feedback the guess gives for each answer, in dictionary order
+fn labels(guess: [u8; 5], answers: &WordDictionary) -> Vec<WordMatch> {
+    let mut labels = Vec::with_capacity(answers.len());
+    answers.for_each(|answer| labels.push(answers.word_match(guess, answer)));
+    labels
+}
+
+// whether fine's partition strictly refines coarse's, so that any tree after coarse can be
+// followed bucket by bucket after fine at no extra cost. the exception is a coarse guess that
+// could be the answer, which only loses that edge to a fine guess that could be too
+pub(crate) fn dominates(
+    fine: &Guess,
+    fine_labels: &[WordMatch],
+    coarse: &Guess,
+    coarse_labels: &[WordMatch],
+) -> bool {
+    if fine.summary.buckets() <= coarse.summary.buckets()
+        || (coarse.summary.has_correct && !fine.summary.has_correct)
+    {
+        return false;
+    }
+    let mut coarse_of = [None; 243];
+    fine_labels
+        .iter()
+        .zip(coarse_labels)
+        .all(|(fine, coarse)| *coarse_of[fine.0 as usize].get_or_insert(*coarse) == *coarse)
+}
+
+// the hardest candidates first, so long searches don't leave one thread finishing a slow
+// candidate after the rest are done
+pub(crate) fn order_candidates(mut candidates: Vec<Guess>) -> Vec<Guess> {
+    candidates.sort_by_key(|guess| (Reverse(guess.summary.difficulty()), guess.word));
+    candidates
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(depth = depth, answers = answers.len()))
+)]
+#[allow(clippy::too_many_arguments)]
+fn solve_tree(
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    breadth: Breadth,
+    depth: usize,
+    hard: Option<HardMode<'_>>,
+    tiebreak: Tiebreak,
+    policy: GuessPolicy,
+    cancel: &CancellationToken,
+    progress: &(dyn Fn(SolveProgress) + Sync),
+    consider: &mut dyn FnMut(RootCandidate) -> ControlFlow<()>,
+) -> Option<Tree> {
+    if answers.len() == 1 {
+        return Some(Tree::leaf(answers.word(0)));
+    }
+    if depth == 1 || cancel.is_cancelled() {
+        return None;
+    }
+    throttle();
+    // a root as small as an endgame bucket is solved exactly too
+    if answers.len() <= ENDGAME_ANSWERS {
+        return small_root_tree(
+            guesses, answers, depth, hard, tiebreak, policy, cancel, progress, consider,
+        );
+    }
+    let first = match policy {
+        GuessPolicy::AnswersFirst if answers.len() < guesses.len() => answers,
+        _ => guesses,
+    };
+    if let Some(tree) = discriminator(first, answers, tiebreak) {
+        return Some(tree);
+    }
+    // only this node's candidates narrow to the answers, the buckets below still get to fall
+    // back to every guess
+    if policy == GuessPolicy::AnswersFirst && answers.len() < guesses.len() {
+        let tree = best_tree(
+            answers, guesses, answers, breadth, depth, hard, tiebreak, policy, cancel, progress,
+            consider,
+        );
+        if tree.is_some() {
+            return tree;
+        }
+    }
+    best_tree(
+        guesses, guesses, answers, breadth, depth, hard, tiebreak, policy, cancel, progress,
+        consider,
+    )
+}
+
+// the exact tree for a few answers, first offering every guess to consider as best_tree does
+#[allow(clippy::too_many_arguments)]
+fn small_root_tree(
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    depth: usize,
+    hard: Option<HardMode<'_>>,
+    tiebreak: Tiebreak,
+    policy: GuessPolicy,
+    cancel: &CancellationToken,
+    progress: &(dyn Fn(SolveProgress) + Sync),
+    consider: &mut dyn FnMut(RootCandidate) -> ControlFlow<()>,
+) -> Option<Tree> {
+    let mut kept = WordDictionary::with_capacity(guesses.len());
+    let _ = guesses.try_for_each(|word| {
+        let guess = Guess::summarized(word, answers);
+        let candidate = RootCandidate {
+            word,
+            entropy: guess.entropy,
+            buckets: guess.summary.buckets(),
+        };
+        if consider(candidate).is_break() {
+            return ControlFlow::Break(());
+        }
+        kept.push(word);
+        ControlFlow::Continue(())
+    });
+    let candidates = if kept.len() == guesses.len() {
+        guesses
+    } else {
+        &kept
+    };
+    let mut tree = None;
+    if policy == GuessPolicy::AnswersFirst && answers.len() < guesses.len() {
+        let kept_answers = answers.filter(|word| candidates.contains(word));
+        tree = exact_tree(
+            &kept_answers,
+            guesses,
+            answers,
+            depth,
+            hard,
+            tiebreak,
+            policy,
+            cancel,
+            u16::MAX,
+        );
+    }
+    let tree = tree.or_else(|| {
+        exact_tree(
+            candidates,
+            guesses,
+            answers,
+            depth,
+            hard,
+            tiebreak,
+            policy,
+            cancel,
+            u16::MAX,
+        )
+    });
+    // the candidates are searched together, so they all finish at once
+    progress(SolveProgress {
+        evaluated: candidates.len(),
+        candidates: candidates.len(),
+        incumbent: tree.as_ref().map(Tree::size),
+    });
+    tree.filter(|_| !cancel.is_cancelled())
+}
+
+// the best tree opening with one of candidates
+#[allow(clippy::too_many_arguments)]
+fn best_tree(
+    candidates: &WordDictionary,
+    guesses: &WordDictionary,
+    answers: &WordDictionary,
+    breadth: Breadth,
+    depth: usize,
+    hard: Option<HardMode<'_>>,
+    tiebreak: Tiebreak,
+    policy: GuessPolicy,
+    cancel: &CancellationToken,
+    progress: &(dyn Fn(SolveProgress) + Sync),
+    consider: &mut dyn FnMut(RootCandidate) -> ControlFlow<()>,
+) -> Option<Tree> {
+    let mut best_guesses: BinaryHeap<Guess> = BinaryHeap::with_capacity(breadth.get());
+    let mut candidate_labels: HashMap<[u8; 5], Vec<WordMatch>> = HashMap::new();
+    let cf = candidates.try_for_each(|guess| {
+        let pressure = memory_pressure();
+        if pressure && best_guesses.iter().any(|other| !other.partition.is_empty()) {
+            best_guesses = mem::take(&mut best_guesses)
+                .into_iter()
+                .map(|mut other| {
+                    other.release_partition();
+                    other
+                })
+                .collect();
+        }
+        let guess = if pressure {
+            Guess::summarized(guess, answers)
+        } else {
+            Guess::new(guess, answers)
+        };
+        let candidate = RootCandidate {
+            word: guess.word,
+            entropy: guess.entropy,
+            buckets: guess.summary.buckets(),
+        };
+        if consider(candidate).is_break() {
+            return ControlFlow::Break(None);
+        }
+        if guess.summary.buckets() == 1 {
             // learned nothing, not a useful guess
             return ControlFlow::Continue(());
         }
-        if let Some(solution) = guess.fast_solution(depth - 1) {
-            return ControlFlow::Break(solution);
+        if let Some(solution) = guess.fast_solution(answers, depth - 1) {
+            return ControlFlow::Break(Some(solution));
         }
-        if best_guesses.len() < best_guesses.capacity() {
-            best_guesses.push(guess);
-        } else if guess < *best_guesses.peek().unwrap() {
+        let full = best_guesses.len() == breadth.get();
+        if full && guess >= *best_guesses.peek().unwrap() {
+            return ControlFlow::Continue(());
+        }
+        // hard mode ties later guesses to this one's feedback, so a finer partition isn't
+        // necessarily better there
+        if hard.is_none() {
+            let guess_labels = labels(guess.word, answers);
+            if best_guesses.iter().any(|other: &Guess| {
+                dominates(other, &candidate_labels[&other.word], &guess, &guess_labels)
+            }) {
+                return ControlFlow::Continue(());
+            }
+            best_guesses.retain(|other| {
+                !dominates(&guess, &guess_labels, other, &candidate_labels[&other.word])
+            });
+            candidate_labels.insert(guess.word, guess_labels);
+        }
+        if best_guesses.len() == breadth.get() {
             best_guesses.pop();
-            best_guesses.push(guess);
         }
+        best_guesses.push(guess);
         ControlFlow::Continue(())
     });
-    if let ControlFlow::Break(solution) = cf {
+    if let ControlFlow::Break(Some(solution)) = cf {
         return Some(solution);
     }
-    best_guesses
+    let candidates = best_guesses.len();
+    let state = Mutex::new((0, None));
+    order_candidates(best_guesses.into_vec())
         .into_par_iter()
-        .filter_map(|guess: Guess| guess.slow_solution(guesses, answers, breadth, depth - 1, hard))
-        .min_by_key(|solution: &Solution| solution.size)
+        .filter_map(|guess: Guess| {
+            if cancel.is_cancelled() {
+                return None;
+            }
+            let solution = guess.slow_tree(
+                guesses,
+                answers,
+                breadth.deeper(),
+                depth - 1,
+                hard,
+                tiebreak,
+                policy,
+                cancel,
+            );
+            let mut state = state.lock().unwrap();
+            let (evaluated, incumbent) = &mut *state;
+            *evaluated += 1;
+            if let Some(solution) = &solution {
+                *incumbent =
+                    Some(incumbent.map_or(solution.size(), |size: u16| size.min(solution.size())));
+            }
+            progress(SolveProgress {
+                evaluated: *evaluated,
+                candidates,
+                incumbent: *incumbent,
+            });
+            solution
+        })
+        .min_by_key(|solution: &Tree| (solution.size(), tiebreak.rank(solution), solution.guess()))
+        // a search cut short may have skipped the best candidate
+        .filter(|_| !cancel.is_cancelled())
+}
+
+pub fn solve_hard_limited<D: Dictionary>(
+    guess: D::Word,
+    dict: &D,
+    depth: usize,
+    metrics: &SearchMetrics,
+    cancel: &CancellationToken,
+) -> Option<u32> {
+    solve_hard_limited_within(guess, dict, depth, u32::MAX, metrics, cancel)
+}
+
+// like solve_hard_limited, but gives up with None once the total can't stay within max_total
+pub fn solve_hard_limited_within<D: Dictionary>(
+    guess: D::Word,
+    dict: &D,
+    depth: usize,
+    max_total: u32,
+    metrics: &SearchMetrics,
+    cancel: &CancellationToken,
+) -> Option<u32> {
+    hard_limited_within(guess, dict, None, depth, max_total, metrics, cancel)
+}
+
+// like solve_hard_limited_within, but past the opener only the answers allowed accepts are
+// guessed, as when the guesses were narrowed after the table was built. the last answer left
+// in a bucket is still guessed, since the final guess only has to be the answer
+pub fn solve_hard_limited_allowed_within<D: Dictionary>(
+    guess: D::Word,
+    dict: &D,
+    allowed: &(dyn Fn(D::Word) -> bool + Sync),
+    depth: usize,
+    max_total: u32,
+    metrics: &SearchMetrics,
+    cancel: &CancellationToken,
+) -> Option<u32> {
+    hard_limited_within(
+        guess,
+        dict,
+        Some(allowed),
+        depth,
+        max_total,
+        metrics,
+        cancel,
+    )
 }
 
-pub fn solve_hard_limited<D: Dictionary>(guess: D::Word, dict: &D, depth: usize) -> Option<u32> {
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "trace",
+        skip_all,
+        fields(?guess, depth = depth, answers = dict.len())
+    )
+)]
+fn hard_limited_within<D: Dictionary>(
+    guess: D::Word,
+    dict: &D,
+    allowed: Option<&(dyn Fn(D::Word) -> bool + Sync)>,
+    depth: usize,
+    max_total: u32,
+    metrics: &SearchMetrics,
+    cancel: &CancellationToken,
+) -> Option<u32> {
+    metrics.node();
+    if cancel.is_cancelled() {
+        return None;
+    }
+    throttle();
     if dict.len() == 1 {
-        return Some(1);
+        return Some(1).filter(|&total| total <= max_total);
     }
     if depth == 0 {
         return None;
     }
-    let mut partition = dict.partition(guess);
+    let mut partition = metrics.partition(dict, guess);
     if partition.len() == dict.len() {
-        return Some(2 * partition.len() as u32 - 1);
+        return Some(2 * partition.len() as u32 - 1).filter(|&total| total <= max_total);
     }
     partition.remove(&WordMatch::CORRECT);
-    partition.into_values().try_fold(1, |total, dict| {
-        dict.par_process(total + dict.len() as u32, |guess| {
-            solve_hard_limited(guess, &dict, depth - 1)
-        })
+    fold_within(1, partition, max_total, |total, _, dict, budget| {
+        // a total cut short by the budget isn't the bucket's best, so the searches the table
+        // remembers run without one
+        let budget = if metrics.table.is_some() {
+            u32::MAX
+        } else {
+            budget.checked_sub(dict.len() as u32)?
+        };
+        // renaming letters can change which answers are allowed, so those sets can't share
+        let best = metrics.memoized(&dict, depth - 1, allowed.is_none(), cancel, || {
+            dict.par_process(0, |guess| {
+                if dict.len() > 1 && allowed.is_some_and(|allowed| !allowed(guess)) {
+                    return None;
+                }
+                hard_limited_within(guess, &dict, allowed, depth - 1, budget, metrics, cancel)
+            })
+        })?;
+        Some(total + dict.len() as u32 + best)
     })
+    .filter(|_| !cancel.is_cancelled())
 }
 
 pub fn solve_easy<D: Dictionary>(
@@ -210,14 +1544,42 @@ pub fn solve_easy<D: Dictionary>(
     guesses: &D,
     answers: &D,
     depth: usize,
+    metrics: &SearchMetrics,
+    cancel: &CancellationToken,
+) -> Option<u32> {
+    solve_easy_within(guess, guesses, answers, depth, u32::MAX, metrics, cancel)
+}
+
+// like solve_easy, but gives up with None once the total can't stay within max_total
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "trace",
+        skip_all,
+        fields(?guess, depth = depth, answers = answers.len())
+    )
+)]
+pub fn solve_easy_within<D: Dictionary>(
+    guess: D::Word,
+    guesses: &D,
+    answers: &D,
+    depth: usize,
+    max_total: u32,
+    metrics: &SearchMetrics,
+    cancel: &CancellationToken,
 ) -> Option<u32> {
+    metrics.node();
+    if cancel.is_cancelled() {
+        return None;
+    }
+    throttle();
     if answers.len() == 1 {
-        return Some(1);
+        return Some(1).filter(|&total| total <= max_total);
     }
     if depth == 0 {
         return None;
     }
-    let mut partition = answers.partition(guess);
+    let mut partition = metrics.partition(answers, guess);
     if partition.len() == 1 {
         return None;
     }
@@ -226,13 +1588,15 @@ pub fn solve_easy<D: Dictionary>(
         .map(|_| 1)
         .unwrap_or(0);
     if partition.len() == answers.len() {
-        return Some(2 * partition.len() as u32 - init);
+        return Some(2 * partition.len() as u32 - init).filter(|&total| total <= max_total);
     }
-    partition.into_values().try_fold(init, |total, dict| {
+    fold_within(init, partition, max_total, |total, _, dict, budget| {
+        let budget = budget.checked_sub(dict.len() as u32)?;
         guesses.par_process(total + dict.len() as u32, |guess| {
-            solve_easy(guess, guesses, &dict, depth - 1)
+            solve_easy_within(guess, guesses, &dict, depth - 1, budget, metrics, cancel)
         })
     })
+    .filter(|_| !cancel.is_cancelled())
 }
 
 pub fn solve_hard<D: Dictionary>(
@@ -240,14 +1604,42 @@ pub fn solve_hard<D: Dictionary>(
     guesses: &D,
     answers: &D,
     depth: usize,
+    metrics: &SearchMetrics,
+    cancel: &CancellationToken,
 ) -> Option<u32> {
+    solve_hard_within(guess, guesses, answers, depth, u32::MAX, metrics, cancel)
+}
+
+// like solve_hard, but gives up with None once the total can't stay within max_total
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        level = "trace",
+        skip_all,
+        fields(?guess, depth = depth, answers = answers.len())
+    )
+)]
+pub fn solve_hard_within<D: Dictionary>(
+    guess: D::Word,
+    guesses: &D,
+    answers: &D,
+    depth: usize,
+    max_total: u32,
+    metrics: &SearchMetrics,
+    cancel: &CancellationToken,
+) -> Option<u32> {
+    metrics.node();
+    if cancel.is_cancelled() {
+        return None;
+    }
+    throttle();
     if answers.len() == 1 {
-        return Some(1);
+        return Some(1).filter(|&total| total <= max_total);
     }
     if depth == 0 {
         return None;
     }
-    let mut partition = answers.partition(guess);
+    let mut partition = metrics.partition(answers, guess);
     if partition.len() == 1 {
         return None;
     }
@@ -256,16 +1648,891 @@ pub fn solve_hard<D: Dictionary>(
         .map(|_| 1)
         .unwrap_or(0);
     if partition.len() == answers.len() {
-        return Some(2 * partition.len() as u32 - init);
+        return Some(2 * partition.len() as u32 - init).filter(|&total| total <= max_total);
     }
-    let guess_partition = guesses.partition(guess);
+    // the guesses that would have given the same feedback are the ones consistent with it, and
+    // each level narrows them again
+    let guess_partition = metrics.partition(guesses, guess);
+    fold_within(init, partition, max_total, |total, wm, answers, budget| {
+        let budget = budget.checked_sub(answers.len() as u32)?;
+        // the last answer left is guessed even when it isn't one of the guesses
+        if answers.len() == 1 {
+            return Some(total + 2).filter(|_| budget >= 1);
+        }
+        guess_partition.get(&wm).and_then(|guesses| {
+            guesses.par_process(total + answers.len() as u32, |guess| {
+                solve_hard_within(guess, guesses, &answers, depth - 1, budget, metrics, cancel)
+            })
+        })
+    })
+    .filter(|_| !cancel.is_cancelled())
+}
+
+// adds up the buckets' totals with solve, giving up as soon as the total so far plus the least
+// the unsolved buckets could add exceeds max_total. solve gets the most its bucket may add
+// while the rest still get their least, to pass on to the searches below
+fn fold_within<D, F>(
+    init: u32,
+    partition: HashMap<WordMatch, D>,
+    max_total: u32,
+    mut solve: F,
+) -> Option<u32>
+where
+    D: Dictionary,
+    F: FnMut(u32, WordMatch, D, u32) -> Option<u32>,
+{
+    let mut remaining: u32 = partition
+        .iter()
+        .map(|(wm, dict)| bucket_lower_bound(*wm, dict.len()))
+        .sum();
     partition
         .into_iter()
-        .try_fold(init, |total, (wm, answers)| {
-            guess_partition.get(&wm).and_then(|guesses| {
-                guesses.par_process(total + answers.len() as u32, |guess| {
-                    solve_easy(guess, guesses, &answers, depth - 1)
-                })
-            })
+        .try_fold(init, |total, (wm, dict)| {
+            if total.saturating_add(remaining) > max_total {
+                return None;
+            }
+            remaining -= bucket_lower_bound(wm, dict.len());
+            let budget = max_total.saturating_sub(total).saturating_sub(remaining);
+            solve(total, wm, dict, budget)
+        })
+        .filter(|&total| total <= max_total)
+}
+
+#[derive(Eq, Hash, PartialEq)]
+enum SetKey {
+    Indices(Box<AnswerSet>),
+    Canonical(CanonicalKey),
+}
+
+// which transposition table entries make way once it is full
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Eviction {
+    // the ones looked up or stored longest ago
+    #[default]
+    Lru,
+    // the ones with the least depth left, which took the least search to find
+    Depth,
+    // the ones with the fewest answers, which are the cheapest to search again
+    Size,
+}
+
+impl Eviction {
+    // entries ranking lowest are evicted first
+    fn rank(self, depth: usize, entry: &Transposition) -> (u64, u64) {
+        match self {
+            Self::Lru => (entry.last_used, 0),
+            Self::Depth => (depth as u64, entry.last_used),
+            Self::Size => (entry.answers as u64, entry.last_used),
+        }
+    }
+}
+
+impl FromStr for Eviction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lru" => Ok(Self::Lru),
+            "depth" => Ok(Self::Depth),
+            "size" => Ok(Self::Size),
+            _ => Err(format!("unknown eviction policy: {}", s)),
+        }
+    }
+}
+
+impl Display for Eviction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Lru => "lru",
+            Self::Depth => "depth",
+            Self::Size => "size",
         })
+    }
+}
+
+struct Transposition {
+    total: Option<u32>,
+    answers: usize,
+    last_used: u64,
+}
+
+// best totals for answer sets already searched at a depth, dropping the half eviction ranks
+// lowest whenever it reaches capacity or memory runs short
+struct Transpositions {
+    entries: HashMap<(SetKey, usize), Transposition>,
+    capacity: usize,
+    eviction: Eviction,
+    clock: u64,
+}
+
+impl Transpositions {
+    fn new(capacity: usize, eviction: Eviction) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity,
+            eviction,
+            clock: 0,
+        }
+    }
+
+    fn get(&mut self, key: &(SetKey, usize)) -> Option<Option<u32>> {
+        self.clock += 1;
+        let entry = self.entries.get_mut(key)?;
+        entry.last_used = self.clock;
+        Some(entry.total)
+    }
+
+    // how many entries were evicted to make room
+    fn insert(&mut self, key: (SetKey, usize), answers: usize, total: Option<u32>) -> usize {
+        let mut evicted = 0;
+        if self.entries.len() >= self.capacity || memory_pressure() {
+            evicted = self.evict();
+        }
+        if self.capacity > 0 {
+            self.clock += 1;
+            let last_used = self.clock;
+            self.entries.insert(
+                key,
+                Transposition {
+                    total,
+                    answers,
+                    last_used,
+                },
+            );
+        }
+        evicted
+    }
+
+    fn evict(&mut self) -> usize {
+        let eviction = self.eviction;
+        let mut ranks: Vec<_> = self
+            .entries
+            .iter()
+            .map(|((_, depth), entry)| eviction.rank(*depth, entry))
+            .collect();
+        if ranks.is_empty() {
+            return 0;
+        }
+        // the ranks are distinct, so this keeps the higher half
+        let mid = (ranks.len() - 1) / 2;
+        let (_, &mut cutoff, _) = ranks.select_nth_unstable(mid);
+        let before = self.entries.len();
+        self.entries
+            .retain(|(_, depth), entry| eviction.rank(*depth, entry) > cutoff);
+        self.entries.shrink_to_fit();
+        before - self.entries.len()
+    }
+}
+
+#[derive(Default)]
+pub struct SearchMetrics {
+    nodes: AtomicU64,
+    partitions: AtomicU64,
+    lookups: AtomicU64,
+    transpositions: AtomicU64,
+    evictions: AtomicU64,
+    table: Option<Mutex<Transpositions>>,
+}
+
+impl SearchMetrics {
+    pub fn with_transpositions() -> Self {
+        Self::with_transposition_table(usize::MAX, Eviction::default())
+    }
+
+    // a table of at most capacity entries
+    pub fn with_transposition_table(capacity: usize, eviction: Eviction) -> Self {
+        Self {
+            table: Some(Mutex::new(Transpositions::new(capacity, eviction))),
+            ..Self::default()
+        }
+    }
+
+    pub fn nodes(&self) -> u64 {
+        self.nodes.load(AtomicOrdering::Relaxed)
+    }
+
+    pub fn partitions(&self) -> u64 {
+        self.partitions.load(AtomicOrdering::Relaxed)
+    }
+
+    // the searches looked up in the table, and of those, the transpositions found there
+    pub fn lookups(&self) -> u64 {
+        self.lookups.load(AtomicOrdering::Relaxed)
+    }
+
+    pub fn transpositions(&self) -> u64 {
+        self.transpositions.load(AtomicOrdering::Relaxed)
+    }
+
+    // the percent of lookups that were transpositions, none before the first lookup
+    pub fn hit_rate(&self) -> Option<f64> {
+        match self.lookups() {
+            0 => None,
+            lookups => Some(100.0 * self.transpositions() as f64 / lookups as f64),
+        }
+    }
+
+    pub fn evictions(&self) -> u64 {
+        self.evictions.load(AtomicOrdering::Relaxed)
+    }
+
+    fn node(&self) {
+        self.nodes.fetch_add(1, AtomicOrdering::Relaxed);
+    }
+
+    fn partition<D: Dictionary>(&self, dict: &D, guess: D::Word) -> HashMap<WordMatch, D> {
+        self.partitions.fetch_add(1, AtomicOrdering::Relaxed);
+        dict.partition(guess)
+    }
+
+    // different guess orders often leave the same answers, so reuse their result, and with
+    // renamable the result of answers that only differ by renaming letters. a search cut short
+    // may have missed the best total, so its result isn't kept
+    fn memoized<D: Dictionary, F>(
+        &self,
+        dict: &D,
+        depth: usize,
+        renamable: bool,
+        cancel: &CancellationToken,
+        f: F,
+    ) -> Option<u32>
+    where
+        F: FnOnce() -> Option<u32>,
+    {
+        let Some(table) = &self.table else {
+            return f();
+        };
+        let canonical = if renamable {
+            dict.canonical_key()
+        } else {
+            None
+        };
+        let key = match (canonical, dict.answer_set()) {
+            (Some(key), _) => SetKey::Canonical(key),
+            (None, Some(set)) => SetKey::Indices(Box::new(set)),
+            (None, None) => return f(),
+        };
+        let key = (key, depth);
+        self.lookups.fetch_add(1, AtomicOrdering::Relaxed);
+        if let Some(result) = table.lock().unwrap().get(&key) {
+            self.transpositions.fetch_add(1, AtomicOrdering::Relaxed);
+            return result;
+        }
+        let result = f();
+        if cancel.is_cancelled() {
+            return result;
+        }
+        // evict rather than grow, the search is still right without the table, only slower
+        let evicted = table.lock().unwrap().insert(key, dict.len(), result);
+        self.evictions
+            .fetch_add(evicted as u64, AtomicOrdering::Relaxed);
+        result
+    }
+}
+
+pub fn estimate_lower_bound<D: Dictionary>(guess: D::Word, answers: &D) -> u32 {
+    answers
+        .partition_iter(guess)
+        .map(|(wm, bucket)| bucket_lower_bound(wm, bucket.len()))
+        .sum()
+}
+
+// no opener totals less than this, so an opener that reaches it is optimal
+pub fn optimal_lower_bound<D: Dictionary>(guesses: &D, answers: &D) -> u32 {
+    let mut bound = u32::MAX;
+    guesses.for_each(|guess| bound = bound.min(estimate_lower_bound(guess, answers)));
+    bound
+}
+
+fn bucket_lower_bound(wm: WordMatch, len: usize) -> u32 {
+    if wm == WordMatch::CORRECT {
+        // solved by the guess itself
+        1
+    } else {
+        // at best one answer is found by the next guess and the rest by the one after
+        3 * len as u32 - 1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{discriminator, endgame_tree, exact_tree, labels, ConsistentGuesses, HardMode};
+    use crate::{
+        dominates, estimate_lower_bound, optimal_lower_bound, order_candidates, solve, solve_easy,
+        solve_easy_within, solve_from_state, solve_from_state_streaming, solve_hard,
+        solve_hard_limited_allowed_within, solve_hard_within, solve_seeded, solve_with_progress,
+        CancellationToken, Constraints, Dictionary, GameState, Guess, GuessPolicy, MatchRules,
+        MatchTable, OffsetDictionary, SearchMetrics, Tiebreak, WordDictionary, WordMatch,
+    };
+    use std::ops::ControlFlow;
+    use std::sync::Arc;
+
+    #[cfg(feature = "io")]
+    #[test]
+    fn deterministic() {
+        let answers = WordDictionary::new(b"lightmightnightsighttightfightcrane");
+        let dag = || {
+            let mut dag = Vec::new();
+            let solution = solve(
+                &answers,
+                &answers,
+                10.into(),
+                6,
+                false,
+                &CancellationToken::default(),
+            )
+            .unwrap();
+            solution.write_dag(&mut dag).unwrap();
+            dag
+        };
+        let first = dag();
+        for threads in 1..4 {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            assert_eq!(pool.install(dag), first);
+        }
+    }
+
+    #[test]
+    fn seeded() {
+        let answers = WordDictionary::new(b"lightmightnightsightcrane");
+        let constraints = Constraints::from_flags(Some("_ight"), None, None).unwrap();
+        let solution = solve_seeded(
+            &answers,
+            &answers,
+            *b"crane",
+            &constraints,
+            10.into(),
+            6,
+            false,
+            Tiebreak::Word,
+            GuessPolicy::Full,
+            &CancellationToken::default(),
+        )
+        .unwrap();
+        assert_eq!(solution.guess(), *b"crane");
+        assert_eq!(solution.answer_count(), 4);
+    }
+
+    #[test]
+    fn tiebreak() {
+        let answers = WordDictionary::new(b"lightmightnight");
+        let guesses = WordDictionary::new(b"almxxlightmightnight");
+        let solve = |tiebreak| {
+            solve_with_progress(
+                &guesses,
+                &answers,
+                10.into(),
+                6,
+                false,
+                tiebreak,
+                GuessPolicy::Full,
+                &CancellationToken::default(),
+                |_| {},
+            )
+            .unwrap()
+        };
+        let first = solve(Tiebreak::Word);
+        let answer = solve(Tiebreak::PreferAnswers);
+        assert_eq!(first.guess(), *b"almxx");
+        assert_eq!(answer.guess(), *b"light");
+        assert_eq!(first.size(), answer.size());
+    }
+
+    #[test]
+    fn guess_policy() {
+        let answers = WordDictionary::new(b"fightlightmightnightsight");
+        let guesses = WordDictionary::new(b"fmnsxfightlightmightnightsight");
+        let solve = |depth, policy| {
+            solve_with_progress(
+                &guesses,
+                &answers,
+                10.into(),
+                depth,
+                false,
+                Tiebreak::Word,
+                policy,
+                &CancellationToken::default(),
+                |_| {},
+            )
+            .unwrap()
+        };
+        let full = solve(3, GuessPolicy::Full);
+        assert_eq!(full.guess(), *b"fmnsx");
+        assert_eq!(full.size(), 10);
+        // an answer opener leaves four answers only another guess separates
+        let answers_first = solve(3, GuessPolicy::AnswersFirst);
+        assert_eq!(answers_first.guess(), *b"fight");
+        assert_eq!(answers_first.size(), 13);
+        assert_eq!(
+            answers_first
+                .lookup(&[WordMatch::from(*b"fight", *b"light")])
+                .unwrap()
+                .guess(),
+            *b"fmnsx"
+        );
+        // no answer opener fits in two guesses
+        assert_eq!(solve(2, GuessPolicy::AnswersFirst).guess(), *b"fmnsx");
+    }
+
+    #[test]
+    fn cancel() {
+        let answers = WordDictionary::new(b"lightmightnightsighttightfightcrane");
+        let solve = |cancel: &CancellationToken, cancel_on_progress: bool| {
+            solve_with_progress(
+                &answers,
+                &answers,
+                10.into(),
+                6,
+                false,
+                Tiebreak::Word,
+                GuessPolicy::Full,
+                cancel,
+                |_| {
+                    if cancel_on_progress {
+                        cancel.cancel();
+                    }
+                },
+            )
+        };
+        assert!(solve(&CancellationToken::default(), false).is_some());
+        let cancel = CancellationToken::default();
+        assert!(solve(&cancel, true).is_none());
+        assert!(cancel.is_cancelled());
+        let metrics = &SearchMetrics::default();
+        let easy = |cancel| solve_easy(*b"crane", &answers, &answers, 5, metrics, cancel);
+        assert!(easy(&cancel).is_none());
+        assert!(easy(&CancellationToken::default()).is_some());
+        // a cancelled search's result isn't remembered for the next one
+        let table = MatchTable::for_lists(&answers, &answers, MatchRules::Nyt);
+        let dict = OffsetDictionary::new(&table);
+        let metrics = SearchMetrics::with_transpositions();
+        assert_eq!(metrics.memoized(&dict, 3, true, &cancel, || None), None);
+        let fresh = CancellationToken::default();
+        assert_eq!(
+            metrics.memoized(&dict, 3, true, &fresh, || Some(13)),
+            Some(13)
+        );
+        assert_eq!(metrics.memoized(&dict, 3, true, &fresh, || None), Some(13));
+    }
+
+    #[test]
+    fn from_state() {
+        let answers = WordDictionary::new(b"lightmightnightsighttightfightcrane");
+        let mut state = GameState::new(&answers);
+        state.guess(*b"crane", WordMatch::from(*b"crane", *b"night"));
+        let solution = solve_from_state(
+            &state,
+            &answers,
+            10.into(),
+            3,
+            false,
+            &CancellationToken::default(),
+        )
+        .unwrap();
+        assert_eq!(solution.answer_count(), 1);
+        assert_eq!(solution.guess(), *b"night");
+        assert!(solve_from_state(
+            &state,
+            &answers,
+            10.into(),
+            1,
+            false,
+            &CancellationToken::default(),
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn streaming() {
+        let answers = WordDictionary::new(b"lightmightnightsighttightfightcrane");
+        let state = GameState::new(&answers);
+        let solve = |limit: usize| {
+            let mut considered = Vec::new();
+            let solution = solve_from_state_streaming(
+                &state,
+                &answers,
+                10.into(),
+                6,
+                false,
+                Tiebreak::Word,
+                GuessPolicy::Full,
+                &CancellationToken::default(),
+                |_| {},
+                |candidate| {
+                    considered.push(candidate);
+                    if considered.len() == limit {
+                        ControlFlow::Break(())
+                    } else {
+                        ControlFlow::Continue(())
+                    }
+                },
+            );
+            (solution, considered)
+        };
+        let (solution, considered) = solve(usize::MAX);
+        let solution = solution.unwrap();
+        assert_eq!(considered.len(), answers.len());
+        assert_eq!(considered[6].word, *b"crane");
+        assert_eq!(considered[6].buckets, 3);
+        assert_eq!(
+            solution.guess(),
+            solve_from_state(
+                &state,
+                &answers,
+                10.into(),
+                6,
+                false,
+                &CancellationToken::default(),
+            )
+            .unwrap()
+            .guess()
+        );
+        // breaking skips the guess it was reported for, so only light is searched
+        let (solution, considered) = solve(2);
+        assert_eq!(considered.len(), 2);
+        assert_eq!(solution.unwrap().guess(), *b"light");
+        assert!(solve(1).0.is_none());
+    }
+
+    #[test]
+    fn endgame() {
+        let answers = WordDictionary::new(b"fightlightmightnightsight");
+        let guesses = WordDictionary::new(b"fmnsxfightlightmightnightsight");
+        let endgame = |depth, policy, max_size| {
+            endgame_tree(
+                &guesses,
+                &answers,
+                depth,
+                None,
+                Tiebreak::Word,
+                policy,
+                &CancellationToken::default(),
+                max_size,
+            )
+        };
+        let full = endgame(3, GuessPolicy::Full, u16::MAX).unwrap();
+        assert_eq!((full.guess(), full.size()), (*b"fmnsx", 10));
+        assert!(endgame(3, GuessPolicy::Full, 9).is_none());
+        let answers_first = endgame(3, GuessPolicy::AnswersFirst, u16::MAX).unwrap();
+        assert_eq!(
+            (answers_first.guess(), answers_first.size()),
+            (*b"fight", 13)
+        );
+        assert!(endgame(3, GuessPolicy::AnswersFirst, 12).is_none());
+        // without a third guess only the splitting guess finishes
+        let two = endgame(2, GuessPolicy::AnswersFirst, u16::MAX).unwrap();
+        assert_eq!(two.guess(), *b"fmnsx");
+    }
+
+    #[test]
+    fn exact_ties() {
+        // photo has the smallest lower bound but only ties bingo, found later, which comes first
+        let answers = WordDictionary::new(b"bingodingolingomintyphotopinchpinkypinto");
+        let tree = exact_tree(
+            &answers,
+            &answers,
+            &answers,
+            4,
+            None,
+            Tiebreak::Word,
+            GuessPolicy::Full,
+            &CancellationToken::default(),
+            u16::MAX,
+        )
+        .unwrap();
+        assert_eq!((tree.guess(), tree.size()), (*b"bingo", 18));
+        // a root this small is searched exactly whatever the breadth
+        let solution = solve(
+            &answers,
+            &answers,
+            1.into(),
+            4,
+            false,
+            &CancellationToken::default(),
+        )
+        .unwrap();
+        assert_eq!((solution.guess(), solution.size()), (*b"bingo", 18));
+    }
+
+    #[test]
+    fn closed_form() {
+        let tree = |answers: &[u8], candidates: &[u8], tiebreak| {
+            discriminator(
+                &WordDictionary::new(candidates),
+                &WordDictionary::new(answers),
+                tiebreak,
+            )
+            .map(|tree| (tree.guess(), tree.size(), tree.child_matches().count()))
+        };
+        let word = Tiebreak::Word;
+        // a pair opens with its first answer
+        assert_eq!(
+            tree(b"sightlight", b"lightsight", word),
+            Some((*b"light", 3, 2))
+        );
+        assert_eq!(tree(b"sightlight", b"sight", word), Some((*b"sight", 3, 2)));
+        // or with a guess that tells them apart, when neither is a candidate
+        assert_eq!(tree(b"sightlight", b"fmnsx", word), Some((*b"fmnsx", 4, 2)));
+        assert_eq!(tree(b"sightlight", b"might", word), None);
+        // crane can't tell light from sight, but light tells both others apart
+        assert_eq!(
+            tree(b"cranelightsight", b"cranelightsight", word),
+            Some((*b"light", 5, 3))
+        );
+        // no ?ight answer tells the other two apart
+        assert_eq!(tree(b"lightmightnight", b"lightmightnight", word), None);
+        // a discriminator that isn't an answer costs a guess more, which ties an answer that
+        // leaves one pair, as light does here, so it is only taken when it also wins the
+        // tiebreak
+        let answers = b"cranelightmightsight";
+        assert_eq!(
+            tree(
+                b"fightlightmightnightsight",
+                b"fmnsxfightlightmightnightsight",
+                word
+            ),
+            Some((*b"fmnsx", 10, 5))
+        );
+        assert_eq!(tree(answers, b"mslxxlight", word), None);
+        assert_eq!(tree(answers, b"bmslxlight", word), Some((*b"bmslx", 8, 4)));
+        let prefer = Tiebreak::PreferAnswers;
+        assert_eq!(tree(answers, b"bmslxlight", prefer), None);
+        assert_eq!(
+            tree(answers, b"bmslxcrane", prefer),
+            Some((*b"bmslx", 8, 4))
+        );
+        // unless it is an answer too
+        assert_eq!(
+            tree(
+                b"fightmightnightsightfmnsx",
+                b"fmnsxfightmightnightsight",
+                word
+            ),
+            Some((*b"fmnsx", 9, 5))
+        );
+    }
+
+    #[test]
+    fn hard_guesses() {
+        let answers = WordDictionary::new(b"lightmightcrane");
+        let guesses = WordDictionary::new(b"lightmightcranetrace");
+        let root = Constraints::default();
+        let consistent = ConsistentGuesses::default();
+        let hard = HardMode {
+            constraints: &root,
+            consistent: &consistent,
+        };
+        let wm = WordMatch::from(*b"light", *b"might");
+        let (constraints, consistent) = hard.narrow(&guesses, &answers, *b"light", wm);
+        assert_eq!(consistent.map(|dict| dict.len()), Some(1));
+        // the constraints carry on down, so the next bucket narrows the list further
+        assert!(!constraints.matches(*b"trace"));
+        let wm = WordMatch::from(*b"light", *b"trace");
+        let (constraints, consistent) = hard.narrow(&guesses, &answers, *b"light", wm);
+        // a guess that isn't an answer still lands in the bucket it would have shown
+        assert!(consistent.is_some_and(|dict| dict.contains(*b"trace")));
+        let below = HardMode {
+            constraints: &constraints,
+            ..hard
+        };
+        let crane_wm = WordMatch::from(*b"crane", *b"trace");
+        let (_, first) = below.narrow(&guesses, &answers, *b"crane", crane_wm);
+        let first = first.unwrap();
+        assert!(first.len() == 1 && first.contains(*b"trace"));
+        // the same feedback in the other order reaches the same constraints, and the list kept
+        // for them
+        let (constraints, _) = hard.narrow(&guesses, &answers, *b"crane", crane_wm);
+        let below = HardMode {
+            constraints: &constraints,
+            ..hard
+        };
+        let (_, again) = below.narrow(&guesses, &answers, *b"light", wm);
+        assert!(Arc::ptr_eq(&first, &again.unwrap()));
+        assert!(hard.narrow(&answers, &answers, *b"light", wm).1.is_none());
+    }
+
+    #[test]
+    fn rules() {
+        // chuck tells the others apart by nyt scoring, but when a green doesn't use up its letter
+        // chuck's second c shows present against both colon and cynic
+        let nyt = WordDictionary::new(b"chuckcoloncreekcynic");
+        let other = nyt.clone().with_rules(MatchRules::GreensDontConsume);
+        let cancel = CancellationToken::default();
+        let nyt_solution = solve(&nyt, &nyt, 10.into(), 6, false, &cancel).unwrap();
+        let other_solution = solve(&other, &other, 10.into(), 6, false, &cancel).unwrap();
+        assert_eq!(nyt_solution.size(), 7);
+        assert_eq!(other_solution.size(), 8);
+        assert_eq!(other_solution.failures(&other, 6), 0);
+        // and the nyt tree loses colon, and creek, whose feedback changes too
+        assert_eq!(nyt_solution.failures(&other, 6), 2);
+    }
+
+    #[test]
+    fn hard_limited_allowed() {
+        // after blitz only bloke tells blend, bloom and bluff apart in time
+        let answers = WordDictionary::new(b"blendblindblitzblokebloombluff");
+        let table = MatchTable::for_lists(&answers, &answers, MatchRules::Nyt);
+        let dict = OffsetDictionary::new(&table);
+        let cancel = CancellationToken::default();
+        let solve = |allowed: &(dyn Fn(u16) -> bool + Sync)| {
+            let metrics = SearchMetrics::default();
+            solve_hard_limited_allowed_within(2, &dict, allowed, 2, u32::MAX, &metrics, &cancel)
+        };
+        assert_eq!(solve(&|_| true), Some(14));
+        assert_eq!(solve(&|guess| guess != 3), None);
+        // a forbidden answer can still be the last guess
+        assert_eq!(solve(&|guess| guess != 1), Some(14));
+    }
+
+    #[test]
+    fn hard_every_level() {
+        // three guesses in only fit when the third level may play a word that the second
+        // level's feedback already ruled out
+        let answers = WordDictionary::new(
+            b"cheekcheerchesschestchickchidechiefchildchilichillchimechinachirpchock",
+        );
+        let metrics = &SearchMetrics::default();
+        let cancel = &CancellationToken::default();
+        let hard = |depth| solve_hard(*b"chock", &answers, &answers, depth, metrics, cancel);
+        assert!(solve_easy(*b"chock", &answers, &answers, 3, metrics, cancel).is_some());
+        assert_eq!(hard(3), None);
+        assert_eq!(hard(4), Some(43));
+    }
+
+    #[test]
+    fn diff() {
+        let answers = WordDictionary::new(b"lightmightnightsighttightfightcrane");
+        let solution = solve(
+            &answers,
+            &answers,
+            10.into(),
+            6,
+            false,
+            &CancellationToken::default(),
+        )
+        .unwrap();
+        assert!(solution.diff(&solution).is_empty());
+        let seeded = solve_seeded(
+            &answers,
+            &answers,
+            *b"light",
+            &Constraints::default(),
+            10.into(),
+            6,
+            false,
+            Tiebreak::Word,
+            GuessPolicy::Full,
+            &CancellationToken::default(),
+        )
+        .unwrap();
+        let divergences = solution.diff(&seeded);
+        assert_eq!(divergences.len(), 1);
+        assert!(divergences[0].path.is_empty());
+        assert_eq!(divergences[0].other, *b"light");
+        assert_eq!(
+            divergences[0].delta,
+            seeded.size() as i32 - solution.size() as i32
+        );
+    }
+
+    #[test]
+    fn lower_bound() {
+        let answers = WordDictionary::new(b"lightmightnightsight");
+        assert_eq!(estimate_lower_bound(*b"light", &answers), 9);
+        assert_eq!(estimate_lower_bound(*b"mnsxx", &answers), 8);
+        let guesses = WordDictionary::new(b"lightmnsxx");
+        assert_eq!(optimal_lower_bound(&guesses, &answers), 8);
+    }
+
+    #[test]
+    fn within() {
+        let guesses = WordDictionary::new(b"lightmightnightsighttightfightcrane");
+        let answers = WordDictionary::new(b"lightnightsightfightcrane");
+        let metrics = &SearchMetrics::default();
+        let cancel = &CancellationToken::default();
+        for guess in [*b"light", *b"crane"] {
+            let total = solve_easy(guess, &guesses, &answers, 3, metrics, cancel).unwrap();
+            let within =
+                |max| solve_easy_within(guess, &guesses, &answers, 3, max, metrics, cancel);
+            assert_eq!(within(total), Some(total));
+            assert_eq!(within(total - 1), None);
+            let total = solve_hard(guess, &guesses, &answers, 3, metrics, cancel).unwrap();
+            let within =
+                |max| solve_hard_within(guess, &guesses, &answers, 3, max, metrics, cancel);
+            assert_eq!(within(total), Some(total));
+            assert_eq!(within(total - 1), None);
+        }
+        // the budget reaches the buckets' own searches, which stop early too
+        let guesses = WordDictionary::new(
+            b"lightmightnightsighttightfightcranebightwightrightcrateslatetrace",
+        );
+        let answers = WordDictionary::new(b"lightnightsightfightcranebightwightrightcrate");
+        let nodes = |max| {
+            let metrics = SearchMetrics::default();
+            let total = solve_easy_within(*b"crane", &guesses, &answers, 4, max, &metrics, cancel);
+            (total, metrics.nodes())
+        };
+        let (total, unlimited) = nodes(u32::MAX);
+        let (within, limited) = nodes(total.unwrap());
+        assert_eq!(within, total);
+        assert!(limited < unlimited / 2);
+    }
+
+    #[test]
+    fn dominance() {
+        let answers = WordDictionary::new(b"lightmightnightsight");
+        let guess = |word: &[u8; 5]| (Guess::new(*word, &answers), labels(*word, &answers));
+        let (fine, fine_labels) = guess(b"mnsxx");
+        let (coarse, coarse_labels) = guess(b"mxxxx");
+        let (answer, answer_labels) = guess(b"might");
+        assert!(dominates(&fine, &fine_labels, &coarse, &coarse_labels));
+        assert!(!dominates(&coarse, &coarse_labels, &fine, &fine_labels));
+        assert!(!dominates(&fine, &fine_labels, &fine, &fine_labels));
+        // might splits the answers no finer than mxxxx, but could win outright
+        assert!(!dominates(&fine, &fine_labels, &answer, &answer_labels));
+    }
+
+    #[test]
+    fn candidate_order() {
+        let answers = WordDictionary::new(b"lightmightnightsightcrane");
+        let candidates = [*b"crane", *b"mnsxx", *b"light"]
+            .map(|word| Guess::new(word, &answers))
+            .into();
+        let ordered: Vec<_> = order_candidates(candidates)
+            .iter()
+            .map(|guess| guess.word)
+            .collect();
+        // crane and light each leave three answers together, mnsxx splits them all
+        assert_eq!(ordered, vec![*b"crane", *b"light", *b"mnsxx"]);
+    }
+
+    #[test]
+    fn path_for_answer() {
+        let answers = WordDictionary::new(b"lightmightnightsight");
+        let solution = solve(
+            &answers,
+            &answers,
+            10.into(),
+            6,
+            false,
+            &CancellationToken::default(),
+        )
+        .unwrap();
+        answers.for_each(|answer| {
+            let path = solution.path_for_answer(answer, answers.rules());
+            assert_eq!(path.last(), Some(&(answer, WordMatch::CORRECT)));
+            let wms: Vec<_> = path.iter().map(|(_, wm)| *wm).collect();
+            for (i, (guess, _)) in path.iter().enumerate() {
+                assert_eq!(solution.lookup(&wms[..i]).unwrap().guess(), *guess);
+                assert_eq!(solution.follow(&path[..i]).unwrap().guess(), *guess);
+            }
+        });
+        let wm = WordMatch::from(*b"crane", *b"light");
+        assert!(solution.follow(&[(*b"crane", wm)]).is_none());
+        assert_eq!(solution.failures(&answers, 6), 0);
+        assert_eq!(solution.failures(&answers, 1), 3);
+    }
 }
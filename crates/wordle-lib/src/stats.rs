@@ -0,0 +1,122 @@
+use crate::{Dictionary, WordDictionary, MAX_LETTERS};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+pub struct LetterStats {
+    pub words: [u32; MAX_LETTERS],
+    pub by_position: [[u32; MAX_LETTERS]; 5],
+}
+
+pub fn letter_stats(dict: &WordDictionary) -> LetterStats {
+    let mut stats = LetterStats {
+        words: [0; MAX_LETTERS],
+        by_position: [[0; MAX_LETTERS]; 5],
+    };
+    dict.for_each(|word| {
+        let mut seen = 0u32;
+        for (pos, &c) in word.iter().enumerate() {
+            let c = (c - b'a') as usize;
+            stats.by_position[pos][c] += 1;
+            if seen & (1 << c) == 0 {
+                stats.words[c] += 1;
+                seen |= 1 << c;
+            }
+        }
+    });
+    stats
+}
+
+impl LetterStats {
+    pub fn coverage(&self, word: [u8; 5]) -> u32 {
+        let mut seen = 0u32;
+        let mut coverage = 0;
+        for &c in word.iter() {
+            let c = (c - b'a') as usize;
+            if seen & (1 << c) == 0 {
+                coverage += self.words[c];
+                seen |= 1 << c;
+            }
+        }
+        coverage
+    }
+
+    pub fn rank_by_coverage(&self, guesses: &WordDictionary) -> Vec<([u8; 5], u32)> {
+        let mut ranked = Vec::with_capacity(guesses.len());
+        guesses.for_each(|guess| ranked.push((guess, self.coverage(guess))));
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        ranked
+    }
+
+    pub fn disjoint_openers(
+        &self,
+        guesses: &WordDictionary,
+        count: usize,
+        top: usize,
+    ) -> Vec<(Vec<[u8; 5]>, u32)> {
+        // anagrams cover the same letters, so keep one word per letter set
+        let mut by_mask = HashMap::new();
+        guesses.for_each(|guess| {
+            let mask = guess.iter().fold(0u32, |mask, &c| mask | 1 << (c - b'a'));
+            if mask.count_ones() == 5 {
+                by_mask.entry(mask).or_insert(guess);
+            }
+        });
+        let mut candidates: Vec<_> = by_mask
+            .into_iter()
+            .map(|(mask, word)| (self.coverage(word), mask, word))
+            .collect();
+        candidates.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.2.cmp(&b.2)));
+        let mut search = DisjointSearch {
+            candidates: &candidates,
+            top,
+            chosen: Vec::with_capacity(count),
+            best: BinaryHeap::with_capacity(top + 1),
+        };
+        search.search(0, count, 0, 0);
+        let mut best: Vec<_> = search
+            .best
+            .into_iter()
+            .map(|Reverse((coverage, words))| (words, coverage))
+            .collect();
+        best.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        best
+    }
+}
+
+struct DisjointSearch<'a> {
+    candidates: &'a [(u32, u32, [u8; 5])],
+    top: usize,
+    chosen: Vec<[u8; 5]>,
+    best: BinaryHeap<Reverse<(u32, Vec<[u8; 5]>)>>,
+}
+
+impl DisjointSearch<'_> {
+    fn search(&mut self, start: usize, remaining: usize, mask: u32, coverage: u32) {
+        if remaining == 0 {
+            self.best.push(Reverse((coverage, self.chosen.clone())));
+            if self.best.len() > self.top {
+                self.best.pop();
+            }
+            return;
+        }
+        for idx in start..self.candidates.len() {
+            let (word_coverage, word_mask, word) = self.candidates[idx];
+            // candidates are sorted by coverage, so nothing later can beat the current worst
+            if self.best.len() == self.top
+                && coverage + remaining as u32 * word_coverage <= self.best.peek().unwrap().0 .0
+            {
+                break;
+            }
+            if mask & word_mask == 0 {
+                self.chosen.push(word);
+                self.search(
+                    idx + 1,
+                    remaining - 1,
+                    mask | word_mask,
+                    coverage + word_coverage,
+                );
+                self.chosen.pop();
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{
+    largest_bucket, rank_guesses, Dictionary, GameState, RankedGuess, Rng, Solution, WordDictionary,
+};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use std::fmt;
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::{Mutex, OnceLock};
+
+pub trait Strategy: Sync {
+    fn next_guess(&self, state: &GameState) -> [u8; 5];
+}
+
+impl<S: Strategy + ?Sized> Strategy for Box<S> {
+    fn next_guess(&self, state: &GameState) -> [u8; 5] {
+        (**self).next_guess(state)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StrategyKind {
+    Human,
+    OptimalTree,
+    EntropyGreedy,
+    MinimaxBucket,
+    Random,
+}
+
+impl FromStr for StrategyKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "human" => Ok(Self::Human),
+            "optimal" => Ok(Self::OptimalTree),
+            "entropy" => Ok(Self::EntropyGreedy),
+            "minimax" => Ok(Self::MinimaxBucket),
+            "random" => Ok(Self::Random),
+            _ => Err(format!("unknown strategy: {}", s)),
+        }
+    }
+}
+
+impl Display for StrategyKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Human => "human",
+            Self::OptimalTree => "optimal",
+            Self::EntropyGreedy => "entropy",
+            Self::MinimaxBucket => "minimax",
+            Self::Random => "random",
+        })
+    }
+}
+
+pub struct HumanStrategy<'a> {
+    guesses: &'a WordDictionary,
+    top_k: usize,
+    temperature: f64,
+    rng: Mutex<Rng>,
+    opening: OnceLock<Vec<RankedGuess>>,
+}
+
+impl<'a> HumanStrategy<'a> {
+    pub fn new(guesses: &'a WordDictionary, top_k: usize, temperature: f64, seed: u64) -> Self {
+        Self {
+            guesses,
+            top_k,
+            temperature,
+            rng: Mutex::new(Rng::new(seed)),
+            opening: OnceLock::new(),
+        }
+    }
+
+    fn top_guesses(&self, state: &GameState) -> Vec<RankedGuess> {
+        let mut ranked = rank_guesses(self.guesses, &state.answers);
+        ranked.truncate(self.top_k.max(1));
+        ranked
+    }
+
+    fn choose(&self, ranked: &[RankedGuess]) -> [u8; 5] {
+        if self.temperature <= 0.0 {
+            return ranked[0].word;
+        }
+        // softmax over entropy, relative to the best guess to keep the weights finite
+        let best = ranked[0].entropy;
+        let weights: Vec<f64> = ranked
+            .iter()
+            .map(|guess| ((guess.entropy - best) / self.temperature).exp())
+            .collect();
+        let mut target = self.rng.lock().unwrap().next_f64() * weights.iter().sum::<f64>();
+        for (guess, weight) in ranked.iter().zip(weights) {
+            if target < weight {
+                return guess.word;
+            }
+            target -= weight;
+        }
+        ranked[ranked.len() - 1].word
+    }
+}
+
+impl Strategy for HumanStrategy<'_> {
+    fn next_guess(&self, state: &GameState) -> [u8; 5] {
+        if state.answers.len() == 1 {
+            return state.answers.word(0);
+        }
+        if state.history.is_empty() {
+            self.choose(self.opening.get_or_init(|| self.top_guesses(state)))
+        } else {
+            self.choose(&self.top_guesses(state))
+        }
+    }
+}
+
+pub struct OptimalTreeStrategy {
+    solution: Solution,
+}
+
+impl OptimalTreeStrategy {
+    pub fn new(solution: Solution) -> Self {
+        Self { solution }
+    }
+}
+
+impl Strategy for OptimalTreeStrategy {
+    fn next_guess(&self, state: &GameState) -> [u8; 5] {
+        let path: Vec<_> = state.history.iter().map(|(_, wm)| *wm).collect();
+        match self.solution.lookup(&path) {
+            Some(node) => node.guess(),
+            // off the tree, any remaining answer is as good as another
+            None => state.answers.word(0),
+        }
+    }
+}
+
+pub struct EntropyGreedyStrategy<'a> {
+    guesses: &'a WordDictionary,
+    opening: OnceLock<[u8; 5]>,
+}
+
+impl<'a> EntropyGreedyStrategy<'a> {
+    pub fn new(guesses: &'a WordDictionary) -> Self {
+        Self {
+            guesses,
+            opening: OnceLock::new(),
+        }
+    }
+
+    fn best_guess(&self, state: &GameState) -> [u8; 5] {
+        rank_guesses(self.guesses, &state.answers)[0].word
+    }
+}
+
+impl Strategy for EntropyGreedyStrategy<'_> {
+    fn next_guess(&self, state: &GameState) -> [u8; 5] {
+        if state.answers.len() == 1 {
+            return state.answers.word(0);
+        }
+        if state.history.is_empty() {
+            *self.opening.get_or_init(|| self.best_guess(state))
+        } else {
+            self.best_guess(state)
+        }
+    }
+}
+
+pub struct MinimaxBucketStrategy<'a> {
+    guesses: &'a WordDictionary,
+    opening: OnceLock<[u8; 5]>,
+}
+
+impl<'a> MinimaxBucketStrategy<'a> {
+    pub fn new(guesses: &'a WordDictionary) -> Self {
+        Self {
+            guesses,
+            opening: OnceLock::new(),
+        }
+    }
+
+    fn best_guess(&self, state: &GameState) -> [u8; 5] {
+        (0..self.guesses.len())
+            .into_par_iter()
+            .map(|idx| {
+                let word = self.guesses.word(idx);
+                let candidate = state.answers.index_of(word).is_some();
+                (largest_bucket(word, &state.answers), !candidate, word)
+            })
+            .min()
+            .unwrap()
+            .2
+    }
+}
+
+impl Strategy for MinimaxBucketStrategy<'_> {
+    fn next_guess(&self, state: &GameState) -> [u8; 5] {
+        if state.answers.len() == 1 {
+            return state.answers.word(0);
+        }
+        if state.history.is_empty() {
+            *self.opening.get_or_init(|| self.best_guess(state))
+        } else {
+            self.best_guess(state)
+        }
+    }
+}
+
+pub struct RandomStrategy {
+    rng: Mutex<Rng>,
+}
+
+impl RandomStrategy {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Mutex::new(Rng::new(seed)),
+        }
+    }
+}
+
+impl Strategy for RandomStrategy {
+    fn next_guess(&self, state: &GameState) -> [u8; 5] {
+        let idx = self.rng.lock().unwrap().below(state.answers.len());
+        state.answers.word(idx)
+    }
+}
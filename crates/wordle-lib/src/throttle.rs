@@ -0,0 +1,80 @@
+use std::cell::Cell;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// how long a thread works between pauses, short enough that the cores look evenly loaded rather
+// than pegged and then idle
+const SLICE: Duration = Duration::from_millis(20);
+
+// the share of each core the solvers may use, 100 for no limit. set once for the whole process,
+// like rayon's global pool, so long searches can run in the background
+static CPU_PERCENT: AtomicU32 = AtomicU32::new(100);
+
+thread_local! {
+    static DUTY_CYCLE: Cell<Option<DutyCycle>> = const { Cell::new(None) };
+}
+
+pub fn set_cpu_limit(percent: u32) {
+    CPU_PERCENT.store(percent.clamp(1, 100), Ordering::Relaxed);
+}
+
+// called by the solvers at every node, it sleeps the calling thread often enough that it keeps
+// to the cpu limit
+pub(crate) fn throttle() {
+    let percent = CPU_PERCENT.load(Ordering::Relaxed);
+    if percent >= 100 {
+        return;
+    }
+    let now = Instant::now();
+    let pause = DUTY_CYCLE.with(|cell| {
+        let duty = cell.get().unwrap_or_else(|| DutyCycle::new(now));
+        cell.set(Some(duty));
+        duty.pause(percent, now)
+    });
+    if let Some(pause) = pause {
+        thread::sleep(pause);
+        DUTY_CYCLE.with(|cell| cell.set(Some(DutyCycle::new(Instant::now()))));
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct DutyCycle {
+    // when the thread last woke up
+    start: Instant,
+}
+
+impl DutyCycle {
+    fn new(start: Instant) -> Self {
+        Self { start }
+    }
+
+    // once a slice of work is done, the sleep that leaves the thread busy percent of the time
+    fn pause(&self, percent: u32, now: Instant) -> Option<Duration> {
+        let busy = now.duration_since(self.start);
+        if busy < SLICE {
+            return None;
+        }
+        Some(busy * (100 - percent) / percent)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::throttle::{DutyCycle, SLICE};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn duty_cycle() {
+        let start = Instant::now();
+        let duty = DutyCycle::new(start);
+        assert_eq!(duty.pause(25, start + SLICE / 2), None);
+        let busy = Duration::from_millis(30);
+        assert_eq!(
+            duty.pause(25, start + busy),
+            Some(Duration::from_millis(90))
+        );
+        assert_eq!(duty.pause(50, start + busy), Some(busy));
+        assert_eq!(duty.pause(100, start + busy), Some(Duration::ZERO));
+    }
+}
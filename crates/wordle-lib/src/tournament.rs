@@ -0,0 +1,120 @@
+use crate::{play_game, Dictionary, GameState, Rng, Strategy, WordDictionary};
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use std::sync::Mutex;
+
+pub struct NoisyStrategy<S> {
+    inner: S,
+    noise: f64,
+    rng: Mutex<Rng>,
+}
+
+impl<S: Strategy> NoisyStrategy<S> {
+    pub fn new(inner: S, noise: f64, seed: u64) -> Self {
+        Self {
+            inner,
+            noise,
+            rng: Mutex::new(Rng::new(seed)),
+        }
+    }
+}
+
+impl<S: Strategy> Strategy for NoisyStrategy<S> {
+    fn next_guess(&self, state: &GameState) -> [u8; 5] {
+        let mut rng = self.rng.lock().unwrap();
+        if rng.next_f64() < self.noise {
+            let idx = rng.below(state.answers.len());
+            state.answers.word(idx)
+        } else {
+            drop(rng);
+            self.inner.next_guess(state)
+        }
+    }
+}
+
+pub struct Standing {
+    pub name: String,
+    // over the games won, so there is none when every game is lost
+    pub mean: Option<f64>,
+    pub variance: Option<f64>,
+    pub failure_rate: f64,
+    pub wins: Vec<u32>,
+}
+
+pub fn run_tournament(
+    entrants: &[(String, Box<dyn Strategy + '_>)],
+    answers: &WordDictionary,
+    weights: Option<&[f64]>,
+    max_guesses: usize,
+) -> Vec<Standing> {
+    let scores_by_entrant: Vec<Vec<Option<usize>>> = entrants
+        .par_iter()
+        .map(|(_, strategy)| {
+            let mut scores = Vec::with_capacity(answers.len());
+            answers.for_each(|answer| {
+                scores.push(play_game(&**strategy, answers, answer, max_guesses))
+            });
+            scores
+        })
+        .collect();
+    let weight = |idx: usize| weights.map_or(1.0, |weights| weights[idx]);
+    let total_weight: f64 = (0..answers.len()).map(weight).sum();
+    entrants
+        .iter()
+        .zip(scores_by_entrant.iter())
+        .map(|((name, _), scores)| {
+            let solved = || {
+                scores
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(idx, score)| score.map(|n| (weight(idx), n as f64)))
+            };
+            let solved_weight: f64 = solved().map(|(w, _)| w).sum();
+            let mean = (solved_weight > 0.0)
+                .then(|| solved().map(|(w, n)| w * n).sum::<f64>() / solved_weight);
+            let variance = mean.map(|mean| {
+                solved()
+                    .map(|(w, n)| w * (n - mean) * (n - mean))
+                    .sum::<f64>()
+                    / solved_weight
+            });
+            let wins = scores_by_entrant
+                .iter()
+                .map(|other| {
+                    scores
+                        .iter()
+                        .zip(other.iter())
+                        .filter(|(a, b)| a.unwrap_or(usize::MAX) < b.unwrap_or(usize::MAX))
+                        .count() as u32
+                })
+                .collect();
+            Standing {
+                name: name.clone(),
+                mean,
+                variance,
+                failure_rate: 1.0 - solved_weight / total_weight,
+                wins,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::analysis::run_tournament;
+    use crate::{EntropyGreedyStrategy, Strategy, WordDictionary};
+
+    #[test]
+    fn all_failed() {
+        let answers = WordDictionary::new(b"lightmightsight");
+        let entrants: Vec<(String, Box<dyn Strategy>)> = vec![(
+            "greedy".to_string(),
+            Box::new(EntropyGreedyStrategy::new(&answers)),
+        )];
+        let standings = run_tournament(&entrants, &answers, None, 6);
+        assert!(standings[0].mean.is_some());
+        let standings = run_tournament(&entrants, &answers, None, 0);
+        assert_eq!(standings[0].mean, None);
+        assert_eq!(standings[0].variance, None);
+        assert_eq!(standings[0].failure_rate, 1.0);
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{
+    format_history, parse_history, Alphabet, Dictionary, GameState, Rng, Solution, WordDictionary,
+    WordMatch,
+};
+use std::io;
+use std::io::Write;
+
+// one graded quiz answer, as train mode records it: a line of the time in unix seconds, the
+// guess given, the tree's guess, how many more guesses the given guess takes on average, or -
+// when it can't finish in time, and the position in the form --history takes
+#[derive(Clone, Debug, PartialEq)]
+pub struct QuizResult {
+    pub time: u64,
+    pub guess: [u8; 5],
+    pub best: [u8; 5],
+    pub extra: Option<f64>,
+    pub history: Vec<([u8; 5], WordMatch)>,
+}
+
+impl QuizResult {
+    // no worse than the tree's guess, which a search can sometimes beat
+    pub fn correct(&self) -> bool {
+        self.extra.is_some_and(|extra| extra <= 1e-9)
+    }
+
+    pub fn write<W: Write>(&self, alphabet: &Alphabet, w: &mut W) -> io::Result<()> {
+        let extra = match self.extra {
+            Some(extra) => extra.to_string(),
+            None => "-".to_string(),
+        };
+        writeln!(
+            w,
+            "{} {} {} {} {}",
+            self.time,
+            alphabet.decode(self.guess),
+            alphabet.decode(self.best),
+            extra,
+            format_history(&self.history, alphabet)
+        )
+    }
+}
+
+pub fn parse_quiz_results(text: &str, alphabet: &Alphabet) -> Result<Vec<QuizResult>, String> {
+    let mut results = Vec::new();
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let bad = || format!("line {}: bad quiz result: {}", idx + 1, line);
+        let fields: Vec<_> = line.split_whitespace().collect();
+        let [time, guess, best, extra, history] = fields[..] else {
+            return Err(bad());
+        };
+        results.push(QuizResult {
+            time: time.parse().map_err(|_| bad())?,
+            guess: alphabet.encode(guess)?,
+            best: alphabet.encode(best)?,
+            extra: match extra {
+                "-" => None,
+                extra => Some(extra.parse().map_err(|_| bad())?),
+            },
+            history: parse_history(history, alphabet)?,
+        });
+    }
+    Ok(results)
+}
+
+// a position partway through the tree's game for a random answer, past the opener and with a
+// choice still to make between several answers
+pub fn quiz_position(
+    solution: &Solution,
+    answers: &WordDictionary,
+    rng: &mut Rng,
+) -> Option<GameState> {
+    for _ in 0..answers.len() {
+        let answer = answers.word(rng.below(answers.len()));
+        let mut state = GameState::new(answers);
+        let mut positions = Vec::new();
+        for (guess, wm) in solution.path_for_answer(answer, answers.rules()) {
+            if !state.history.is_empty() && state.answers.len() > 1 {
+                positions.push(state.clone());
+            }
+            state.guess(guess, wm);
+        }
+        if !positions.is_empty() {
+            return Some(positions.swap_remove(rng.below(positions.len())));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use crate::analysis::{parse_quiz_results, quiz_position, QuizResult};
+    use crate::{solve, Alphabet, CancellationToken, Dictionary, Rng, WordDictionary, WordMatch};
+
+    #[test]
+    fn quiz() {
+        let answers = WordDictionary::new(b"lightmightnightsighttightfightcrane");
+        let solution = solve(
+            &answers,
+            &answers,
+            10.into(),
+            6,
+            false,
+            &CancellationToken::default(),
+        )
+        .unwrap();
+        let mut rng = Rng::new(3);
+        for _ in 0..10 {
+            let state = quiz_position(&solution, &answers, &mut rng).unwrap();
+            assert!(!state.history.is_empty());
+            assert!(state.answers.len() > 1);
+            assert!(solution.follow(&state.history).is_some());
+        }
+        // every answer is found by the opener or the guess after it
+        let pair = WordDictionary::new(b"lightcrane");
+        let solution = solve(
+            &pair,
+            &pair,
+            10.into(),
+            6,
+            false,
+            &CancellationToken::default(),
+        )
+        .unwrap();
+        assert!(quiz_position(&solution, &pair, &mut rng).is_none());
+    }
+
+    #[test]
+    fn results() {
+        let alphabet = Alphabet::english();
+        let result = QuizResult {
+            time: 1700000000,
+            guess: *b"might",
+            best: *b"fight",
+            extra: Some(0.25),
+            history: vec![(*b"crane", WordMatch::from(*b"crane", *b"light"))],
+        };
+        let missed = QuizResult {
+            extra: None,
+            ..result.clone()
+        };
+        let mut text = Vec::new();
+        result.write(&alphabet, &mut text).unwrap();
+        missed.write(&alphabet, &mut text).unwrap();
+        let text = String::from_utf8(text).unwrap();
+        assert_eq!(
+            text.lines().next(),
+            Some("1700000000 might fight 0.25 crane:aaaaa")
+        );
+        let parsed = parse_quiz_results(&text, &alphabet).unwrap();
+        assert_eq!(parsed, [result.clone(), missed]);
+        assert!(!result.correct());
+        assert!(QuizResult {
+            extra: Some(0.0),
+            ..result
+        }
+        .correct());
+        assert!(parse_quiz_results("1700000000 might fight\n", &alphabet).is_err());
+    }
+}
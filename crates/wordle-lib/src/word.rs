@@ -0,0 +1,55 @@
+use crate::WordDictionary;
+use std::fmt;
+use std::fmt::Display;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Word(pub [u8; 5]);
+
+impl Word {
+    pub fn check_in(self, dict: &WordDictionary) -> Result<Self, String> {
+        match dict.index_of(self.0) {
+            Some(_) => Ok(self),
+            None => Err(format!("not in word list: {}", self)),
+        }
+    }
+}
+
+impl TryFrom<&str> for Word {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let word: [u8; 5] = s
+            .as_bytes()
+            .try_into()
+            .map_err(|_| format!("word must have 5 letters: {}", s))?;
+        if !word.iter().all(u8::is_ascii_alphabetic) {
+            return Err(format!("word must only contain letters a-z: {}", s));
+        }
+        Ok(Self(word.map(|c| c.to_ascii_lowercase())))
+    }
+}
+
+impl From<Word> for [u8; 5] {
+    fn from(word: Word) -> Self {
+        word.0
+    }
+}
+
+impl Display for Word {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(std::str::from_utf8(&self.0).unwrap())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Word;
+
+    #[test]
+    fn try_from() {
+        assert_eq!(Word::try_from("CRANE"), Ok(Word(*b"crane")));
+        assert!(Word::try_from("cranes").is_err());
+        assert!(Word::try_from("cr4ne").is_err());
+        assert!(Word::try_from("crané").is_err());
+    }
+}
@@ -1,47 +1,158 @@
 use std::fmt;
 use std::fmt::Display;
+use std::str::FromStr;
 
 #[derive(Eq, PartialEq)]
 #[repr(u8)]
-enum CharMatch {
+pub(crate) enum CharMatch {
     Absent = 0,
     Present = 1,
     Correct = 2,
 }
 
-#[derive(Clone, Eq, PartialEq, Hash, Copy)]
-pub struct WordMatch(pub u8);
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum MatchRules {
+    // a guessed letter is only marked present as many times as it is otherwise unmatched
+    #[default]
+    Nyt,
+    // every occurrence of a letter in the answer is marked present
+    EveryOccurrence,
+    // letters matched correct still count towards marking other occurrences present
+    GreensDontConsume,
+}
+
+impl FromStr for MatchRules {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "nyt" => Ok(Self::Nyt),
+            "every" => Ok(Self::EveryOccurrence),
+            "greens" => Ok(Self::GreensDontConsume),
+            _ => Err(format!("unknown match rules: {}", s)),
+        }
+    }
+}
+
+impl Display for MatchRules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Nyt => "nyt",
+            Self::EveryOccurrence => "every",
+            Self::GreensDontConsume => "greens",
+        })
+    }
+}
+
+// the feedback for a guess, one of 243 codes counting each letter absent 0, present 1 or
+// correct 2 in base 3, first letter lowest
+#[derive(Clone, Eq, PartialEq, Hash, Copy, Debug)]
+pub struct WordMatch(pub(crate) u8);
 
 impl WordMatch {
     const POWERS: [u8; 5] = [1, 3, 9, 27, 81];
     pub const ABSENT: WordMatch = WordMatch(0);
+    // every letter in the answer, none in place
+    pub const PRESENT: WordMatch = WordMatch(121);
     pub const CORRECT: WordMatch = WordMatch(242);
+    pub const COUNT: usize = 243;
+
+    pub const fn from_index(index: u8) -> Option<Self> {
+        if (index as usize) < Self::COUNT {
+            Some(Self(index))
+        } else {
+            None
+        }
+    }
+
+    pub const fn index(self) -> u8 {
+        self.0
+    }
+
+    // every pattern in index order, all absent first and all correct last
+    pub fn all() -> impl Iterator<Item = Self> + Clone {
+        (0..Self::COUNT as u8).map(Self)
+    }
+
+    // one mark per letter, absent 0, present 1 or correct 2
+    pub const fn from_marks(marks: [u8; 5]) -> Option<Self> {
+        let mut index = 0;
+        let mut i = 0;
+        while i < 5 {
+            if marks[i] > 2 {
+                return None;
+            }
+            index += marks[i] * Self::POWERS[i];
+            i += 1;
+        }
+        Some(Self(index))
+    }
 
     pub fn from(guess: [u8; 5], answer: [u8; 5]) -> Self {
         let mut matches = Self::ABSENT;
-        let mut available = 0u64;
+        // three bits per letter, since all five answer letters may be the same
+        let mut available = 0u128;
         for i in 0..5 {
             let g = guess[i];
             let a = answer[i];
             if g == a {
                 matches.set(i, CharMatch::Correct);
             } else {
-                available += 1 << (2 * (a - 97));
+                available += 1 << (3 * (a - 97));
             }
         }
+        for (i, &g) in guess.iter().enumerate() {
+            if matches.get(i) == CharMatch::Absent && (available >> (3 * (g - 97))) & 7 != 0 {
+                matches.set(i, CharMatch::Present);
+                available -= 1 << (3 * (g - 97));
+            }
+        }
+        matches
+    }
+
+    pub fn with_rules(guess: [u8; 5], answer: [u8; 5], rules: MatchRules) -> Self {
+        if rules == MatchRules::Nyt {
+            return Self::from(guess, answer);
+        }
+        let mut matches = Self::ABSENT;
+        let mut available = 0u128;
         for i in 0..5 {
-            if matches.get(i) == CharMatch::Absent {
-                let g = guess[i];
-                if (available >> (2 * (g - 97))) & 3 != 0 {
-                    matches.set(i, CharMatch::Present);
-                    available -= 1 << (2 * (g - 97));
+            if guess[i] == answer[i] {
+                matches.set(i, CharMatch::Correct);
+            }
+            if guess[i] != answer[i] || rules == MatchRules::GreensDontConsume {
+                available += 1 << (3 * (answer[i] - 97));
+            }
+        }
+        for (i, &g) in guess.iter().enumerate() {
+            if matches.get(i) == CharMatch::Absent && (available >> (3 * (g - 97))) & 7 != 0 {
+                matches.set(i, CharMatch::Present);
+                if rules != MatchRules::EveryOccurrence {
+                    available -= 1 << (3 * (g - 97));
                 }
             }
         }
         matches
     }
 
-    fn get(&self, idx: usize) -> CharMatch {
+    pub fn greens(&self) -> u32 {
+        (0..5)
+            .filter(|&i| self.get(i) == CharMatch::Correct)
+            .count() as u32
+    }
+
+    pub fn yellows(&self) -> u32 {
+        (0..5)
+            .filter(|&i| self.get(i) == CharMatch::Present)
+            .count() as u32
+    }
+
+    // the number of tiles marked differently
+    pub fn distance(self, other: WordMatch) -> u32 {
+        (0..5).filter(|&i| self.get(i) != other.get(i)).count() as u32
+    }
+
+    pub(crate) fn get(&self, idx: usize) -> CharMatch {
         match self.0 / Self::POWERS[idx] % 3 {
             0 => CharMatch::Absent,
             1 => CharMatch::Present,
@@ -66,3 +177,104 @@ impl Display for WordMatch {
         Ok(())
     }
 }
+
+impl FromStr for WordMatch {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 5 {
+            return Err(format!("invalid match: {}", s));
+        }
+        let mut wm = Self::ABSENT;
+        for (i, c) in s.bytes().enumerate() {
+            match c {
+                b'a' => {}
+                b'p' => wm.set(i, CharMatch::Present),
+                b'c' => wm.set(i, CharMatch::Correct),
+                _ => return Err(format!("invalid match: {}", s)),
+            }
+        }
+        Ok(wm)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{MatchRules, WordMatch};
+    use proptest::array::uniform5;
+    use proptest::prelude::*;
+
+    // straightforward feedback computation to check the bit packed version against
+    fn reference(guess: [u8; 5], answer: [u8; 5]) -> String {
+        let mut result = ['a'; 5];
+        let mut unused: Vec<Option<u8>> = answer.iter().copied().map(Some).collect();
+        for i in 0..5 {
+            if guess[i] == answer[i] {
+                result[i] = 'c';
+                unused[i] = None;
+            }
+        }
+        for i in 0..5 {
+            if result[i] == 'a' {
+                if let Some(j) = unused.iter().position(|&c| c == Some(guess[i])) {
+                    result[i] = 'p';
+                    unused[j] = None;
+                }
+            }
+        }
+        result.iter().collect()
+    }
+
+    proptest! {
+        #[test]
+        fn matches_reference(guess in uniform5(b'a'..=b'z'), answer in uniform5(b'a'..=b'z')) {
+            prop_assert_eq!(WordMatch::from(guess, answer).to_string(), reference(guess, answer));
+        }
+
+        #[test]
+        fn matches_reference_duplicates(
+            guess in uniform5(b'a'..=b'c'),
+            answer in uniform5(b'a'..=b'c'),
+        ) {
+            prop_assert_eq!(WordMatch::from(guess, answer).to_string(), reference(guess, answer));
+        }
+    }
+
+    fn with_rules(guess: &[u8; 5], answer: &[u8; 5], rules: MatchRules) -> String {
+        WordMatch::with_rules(*guess, *answer, rules).to_string()
+    }
+
+    #[test]
+    fn rules() {
+        assert_eq!(with_rules(b"speed", b"abide", MatchRules::Nyt), "aapap");
+        assert_eq!(
+            with_rules(b"speed", b"abide", MatchRules::EveryOccurrence),
+            "aappp"
+        );
+        assert_eq!(with_rules(b"eerie", b"there", MatchRules::Nyt), "papac");
+        assert_eq!(
+            with_rules(b"eerie", b"there", MatchRules::GreensDontConsume),
+            "pppac"
+        );
+    }
+
+    #[test]
+    fn colors() {
+        let wm = WordMatch::from(*b"eerie", *b"there");
+        assert_eq!((wm.greens(), wm.yellows()), (1, 2));
+        assert_eq!(wm.distance("aapcc".parse().unwrap()), 2);
+        assert_eq!(wm.distance(wm), 0);
+    }
+
+    #[test]
+    fn indices() {
+        let wm: WordMatch = "papac".parse().unwrap();
+        assert_eq!(WordMatch::from_index(wm.index()), Some(wm));
+        assert_eq!(WordMatch::from_index(242), Some(WordMatch::CORRECT));
+        assert_eq!(WordMatch::from_index(243), None);
+        assert_eq!(WordMatch::from_marks([1, 0, 1, 0, 2]), Some(wm));
+        assert_eq!(WordMatch::from_marks([1; 5]), Some(WordMatch::PRESENT));
+        assert_eq!(WordMatch::from_marks([0, 0, 3, 0, 0]), None);
+        assert!(WordMatch::all().eq((0..243).map(|idx| WordMatch::from_index(idx).unwrap())));
+    }
+}